@@ -0,0 +1,117 @@
+//! Runs random sequences of `create`, `breed` and `transfer` from a few accounts against the
+//! kitties mock, checking every result and the resulting storage against a simple model.
+
+#![no_main]
+
+use arbitrary::Arbitrary;
+use frame_support::{assert_noop, assert_ok};
+use libfuzzer_sys::fuzz_target;
+use pallet_kitties::{mock::*, Error, Kitties, KittyId, KittyOwner, KittyParents, NextKittyId};
+use std::collections::BTreeMap;
+
+/// Number of accounts making calls, few enough for them to trade kitties with each other.
+const ACCOUNTS: u8 = 4;
+/// Number of kitty ids the calls refer to, starting at the first id of the run.
+const KITTY_IDS: u8 = 16;
+
+#[derive(Debug, Arbitrary)]
+enum Op {
+	Create { who: u8 },
+	Breed { who: u8, kitty_1: u8, kitty_2: u8 },
+	Transfer { who: u8, to: u8, kitty: u8 },
+}
+
+#[derive(Debug, Arbitrary)]
+struct Input {
+	/// Starts this many ids below `KittyId::max_value()` instead of at 0, to run out of ids.
+	ids_left: Option<u8>,
+	ops: Vec<Op>,
+}
+
+/// What storage should look like after the calls made so far.
+#[derive(Default)]
+struct Model {
+	next_kitty_id: KittyId,
+	owners: BTreeMap<KittyId, u64>,
+	parents: BTreeMap<KittyId, (KittyId, KittyId)>,
+}
+
+impl Model {
+	/// Takes the next id the way the pallet does, which fails once `NextKittyId` can't grow.
+	fn next_id(&mut self) -> Option<KittyId> {
+		let id = self.next_kitty_id;
+		self.next_kitty_id = id.checked_add(1)?;
+		Some(id)
+	}
+
+	fn check(&self) {
+		assert_eq!(NextKittyId::<Test>::get(), self.next_kitty_id);
+		assert_eq!(KittyOwner::<Test>::iter().collect::<BTreeMap<_, _>>(), self.owners);
+		assert_eq!(KittyParents::<Test>::iter().collect::<BTreeMap<_, _>>(), self.parents);
+		let kitties: Vec<_> = Kitties::<Test>::iter_keys().collect();
+		assert_eq!(kitties.len(), self.owners.len(), "kitties and owners differ: {:?}", kitties);
+		KittiesModule::do_try_state().unwrap();
+	}
+}
+
+fuzz_target!(|input: Input| {
+	new_test_ext().execute_with(|| {
+		let mut model = Model::default();
+		if let Some(ids_left) = input.ids_left {
+			model.next_kitty_id = KittyId::max_value() - KittyId::from(ids_left);
+			NextKittyId::<Test>::put(model.next_kitty_id);
+		}
+		let first_id = model.next_kitty_id;
+		let account = |who: u8| u64::from(who % ACCOUNTS);
+		let kitty_id = |kitty: u8| first_id.wrapping_add(KittyId::from(kitty % KITTY_IDS));
+
+		for op in input.ops {
+			match op {
+				Op::Create { who } => {
+					let origin = RuntimeOrigin::signed(account(who));
+					match model.next_id() {
+						Some(id) => {
+							assert_ok!(KittiesModule::create(origin));
+							model.owners.insert(id, account(who));
+						},
+						None => assert_noop!(
+							KittiesModule::create(origin),
+							Error::<Test>::InvalidKittyId
+						),
+					}
+				},
+				Op::Breed { who, kitty_1, kitty_2 } => {
+					let (kitty_1, kitty_2) = (kitty_id(kitty_1), kitty_id(kitty_2));
+					let origin = RuntimeOrigin::signed(account(who));
+					let parents_exist =
+						model.owners.contains_key(&kitty_1) && model.owners.contains_key(&kitty_2);
+					match parents_exist.then(|| model.next_id()).flatten() {
+						Some(id) => {
+							assert_ok!(KittiesModule::breed(origin, kitty_1, kitty_2));
+							model.owners.insert(id, account(who));
+							model.parents.insert(id, (kitty_1, kitty_2));
+						},
+						None => assert_noop!(
+							KittiesModule::breed(origin, kitty_1, kitty_2),
+							Error::<Test>::InvalidKittyId
+						),
+					}
+				},
+				Op::Transfer { who, to, kitty } => {
+					let (from, to, kitty) = (account(who), account(to), kitty_id(kitty));
+					let origin = RuntimeOrigin::signed(from);
+					if model.owners.get(&kitty) == Some(&from) {
+						assert_ok!(KittiesModule::transfer(origin, to, kitty));
+						model.owners.insert(kitty, to);
+					} else {
+						assert_noop!(
+							KittiesModule::transfer(origin, to, kitty),
+							Error::<Test>::InvalidKittyId
+						);
+					}
+				},
+			}
+			model.check();
+		}
+	});
+});
@@ -0,0 +1,92 @@
+//! Runs random sequences of `create_claim`, `revoke_claim` and `transfer_claim` from a few
+//! accounts against the PoE mock, checking every result and the resulting storage against a
+//! simple model.
+
+#![no_main]
+
+use arbitrary::Arbitrary;
+use frame_support::{assert_noop, assert_ok, BoundedVec};
+use libfuzzer_sys::fuzz_target;
+use pallet_poe::{mock::*, Error, Proofs};
+use std::collections::BTreeMap;
+
+/// Number of accounts making calls, few enough for them to trade claims with each other.
+const ACCOUNTS: u8 = 4;
+/// Number of distinct claims the calls refer to.
+const CLAIMS: u8 = 8;
+
+#[derive(Debug, Arbitrary)]
+enum Op {
+	CreateClaim { who: u8, claim: u8 },
+	RevokeClaim { who: u8, claim: u8 },
+	TransferClaim { who: u8, claim: u8, to: u8 },
+	NextBlock,
+}
+
+fuzz_target!(|ops: Vec<Op>| {
+	new_test_ext().execute_with(|| {
+		// What `Proofs` should hold after the calls made so far.
+		let mut proofs = BTreeMap::<BoundedVec<u8, _>, (u64, u64)>::new();
+		let account = |who: u8| u64::from(who % ACCOUNTS);
+		let claim_of = |claim: u8| BoundedVec::truncate_from(vec![claim % CLAIMS]);
+
+		for op in ops {
+			let now = System::block_number();
+			match op {
+				Op::CreateClaim { who, claim } => {
+					let (who, claim) = (account(who), claim_of(claim));
+					let origin = RuntimeOrigin::signed(who);
+					if proofs.contains_key(&claim) {
+						assert_noop!(
+							PoeModule::create_claim(origin, claim, None),
+							Error::<Test>::ProofAlreadyExist
+						);
+					} else {
+						assert_ok!(PoeModule::create_claim(origin, claim.clone(), None));
+						proofs.insert(claim, (who, now));
+					}
+				},
+				Op::RevokeClaim { who, claim } => {
+					let (who, claim) = (account(who), claim_of(claim));
+					let origin = RuntimeOrigin::signed(who);
+					match proofs.get(&claim) {
+						None => assert_noop!(
+							PoeModule::revoke_claim(origin, claim),
+							Error::<Test>::ClaimNotExist
+						),
+						Some((owner, _)) if *owner != who => assert_noop!(
+							PoeModule::revoke_claim(origin, claim),
+							Error::<Test>::NotClaimOwner
+						),
+						Some(_) => {
+							assert_ok!(PoeModule::revoke_claim(origin, claim.clone()));
+							proofs.remove(&claim);
+						},
+					}
+				},
+				Op::TransferClaim { who, claim, to } => {
+					let (who, claim, to) = (account(who), claim_of(claim), account(to));
+					let origin = RuntimeOrigin::signed(who);
+					match proofs.get(&claim) {
+						None => assert_noop!(
+							PoeModule::transfer_claim(origin, claim, to),
+							Error::<Test>::ClaimNotExist
+						),
+						Some((owner, _)) if *owner != who => assert_noop!(
+							PoeModule::transfer_claim(origin, claim, to),
+							Error::<Test>::NotClaimOwner
+						),
+						Some(_) => {
+							assert_ok!(PoeModule::transfer_claim(origin, claim.clone(), to));
+							proofs.insert(claim, (to, now));
+						},
+					}
+				},
+				Op::NextBlock => System::set_block_number(now + 1),
+			}
+
+			assert_eq!(Proofs::<Test>::iter().collect::<BTreeMap<_, _>>(), proofs);
+			PoeModule::do_try_state().unwrap();
+		}
+	});
+});
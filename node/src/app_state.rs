@@ -0,0 +1,291 @@
+//! The `export-app-state` subcommand, which writes the kitties and PoE claims of a block as
+//! human-readable records.
+
+use crate::service::FullClient;
+use codec::Decode;
+use frame_support::storage::StoragePrefixedMap;
+use node_template_runtime::{opaque::Block, AccountId, BlockNumber, Hash, Runtime};
+use pallet_kitties::KittyId;
+use sc_cli::{
+	BlockNumberOrHash, CliConfiguration, DatabaseParams, Error, PruningParams, Result, SharedParams,
+};
+use sc_client_api::StorageProvider;
+use serde::Serialize;
+use sp_blockchain::HeaderBackend;
+use sp_core::{bytes::to_hex, crypto::Ss58Codec, storage::StorageKey};
+use std::{
+	collections::BTreeMap,
+	fs::File,
+	io::{BufWriter, Write},
+	path::PathBuf,
+};
+
+/// The format of the exported records.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Format {
+	Json,
+	Csv,
+}
+
+/// Export the kitties and PoE claims of a block to `kitties.<format>` and `claims.<format>`.
+#[derive(Debug, Clone, clap::Parser)]
+pub struct ExportAppStateCmd {
+	/// Block hash or number to export the state of. Defaults to the best block.
+	#[arg(long, value_name = "HASH or NUMBER")]
+	pub at: Option<BlockNumberOrHash>,
+
+	/// Format of the exported records.
+	#[arg(long, value_enum, default_value_t = Format::Json)]
+	pub format: Format,
+
+	/// Directory the exported files are written to.
+	#[arg(long, value_name = "DIR", default_value = ".")]
+	pub output_dir: PathBuf,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub pruning_params: PruningParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub database_params: DatabaseParams,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct KittyRecord {
+	id: KittyId,
+	dna: String,
+	owner: Option<String>,
+	parents: Option<(KittyId, KittyId)>,
+}
+
+impl KittyRecord {
+	const CSV_HEADER: &'static str = "id,dna,owner,parent_1,parent_2";
+
+	fn csv_row(&self) -> String {
+		let (parent_1, parent_2) = match self.parents {
+			Some((a, b)) => (a.to_string(), b.to_string()),
+			None => Default::default(),
+		};
+		format!(
+			"{},{},{},{},{}",
+			self.id,
+			self.dna,
+			self.owner.as_deref().unwrap_or_default(),
+			parent_1,
+			parent_2
+		)
+	}
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ClaimRecord {
+	claim: String,
+	owner: String,
+	block_number: BlockNumber,
+}
+
+impl ClaimRecord {
+	const CSV_HEADER: &'static str = "claim,owner,block_number";
+
+	fn csv_row(&self) -> String {
+		format!("{},{},{}", self.claim, self.owner, self.block_number)
+	}
+}
+
+impl ExportAppStateCmd {
+	pub fn run(&self, client: &FullClient) -> Result<()> {
+		let hash = match &self.at {
+			Some(at) => client.expect_block_hash_from_id(&at.parse::<Block>()?)?,
+			None => client.info().best_hash,
+		};
+
+		let owners: BTreeMap<KittyId, AccountId> =
+			read_map(client, hash, &pallet_kitties::KittyOwner::<Runtime>::final_prefix())?
+				.into_iter()
+				.collect();
+		let parents: BTreeMap<KittyId, (KittyId, KittyId)> =
+			read_map(client, hash, &pallet_kitties::KittyParents::<Runtime>::final_prefix())?
+				.into_iter()
+				.collect();
+		let mut kitties: Vec<_> = read_map::<KittyId, pallet_kitties::Kitty>(
+			client,
+			hash,
+			&pallet_kitties::Kitties::<Runtime>::final_prefix(),
+		)?
+		.into_iter()
+		.map(|(id, kitty)| KittyRecord {
+			id,
+			dna: to_hex(&kitty.0, false),
+			owner: owners.get(&id).map(|owner| owner.to_ss58check()),
+			parents: parents.get(&id).copied(),
+		})
+		.collect();
+		kitties.sort_by_key(|kitty| kitty.id);
+
+		let mut claims: Vec<_> = read_map::<Vec<u8>, (AccountId, BlockNumber)>(
+			client,
+			hash,
+			&pallet_poe::Proofs::<Runtime>::final_prefix(),
+		)?
+		.into_iter()
+		.map(|(claim, (owner, block_number))| ClaimRecord {
+			claim: to_hex(&claim, false),
+			owner: owner.to_ss58check(),
+			block_number,
+		})
+		.collect();
+		claims.sort_by_key(|claim| claim.block_number);
+
+		match self.format {
+			Format::Json => {
+				self.write_json("kitties.json", &kitties)?;
+				self.write_json("claims.json", &claims)?;
+			},
+			Format::Csv => {
+				self.write_csv(
+					"kitties.csv",
+					KittyRecord::CSV_HEADER,
+					kitties.iter().map(KittyRecord::csv_row),
+				)?;
+				self.write_csv(
+					"claims.csv",
+					ClaimRecord::CSV_HEADER,
+					claims.iter().map(ClaimRecord::csv_row),
+				)?;
+			},
+		}
+		Ok(())
+	}
+
+	fn create(&self, name: &str) -> Result<BufWriter<File>> {
+		Ok(BufWriter::new(File::create(self.output_dir.join(name))?))
+	}
+
+	fn write_json<T: Serialize>(&self, name: &str, records: &[T]) -> Result<()> {
+		let mut file = self.create(name)?;
+		serde_json::to_writer_pretty(&mut file, records)
+			.map_err(|e| Error::Application(e.into()))?;
+		file.flush()?;
+		Ok(())
+	}
+
+	fn write_csv(
+		&self,
+		name: &str,
+		header: &str,
+		rows: impl Iterator<Item = String>,
+	) -> Result<()> {
+		let mut file = self.create(name)?;
+		writeln!(file, "{}", header)?;
+		for row in rows {
+			writeln!(file, "{}", row)?;
+		}
+		file.flush()?;
+		Ok(())
+	}
+}
+
+/// Reads every entry of the `Blake2_128Concat` map stored under `prefix`.
+fn read_map<K: Decode, V: Decode>(
+	client: &FullClient,
+	hash: Hash,
+	prefix: &[u8],
+) -> Result<Vec<(K, V)>> {
+	let prefix = StorageKey(prefix.to_vec());
+	let mut entries = Vec::new();
+	for key in client.storage_keys(hash, Some(&prefix), None)? {
+		let map_key = decode_map_key(&prefix.0, &key.0)
+			.map_err(|e| Error::Input(format!("Failed to decode storage key {:?}: {}", key, e)))?;
+		let Some(value) = client.storage(hash, &key)? else { continue };
+		let value = V::decode(&mut &value.0[..])
+			.map_err(|e| Error::Input(format!("Failed to decode value of {:?}: {}", key, e)))?;
+		entries.push((map_key, value));
+	}
+	Ok(entries)
+}
+
+/// Decodes the map key of `key`, a key of the `Blake2_128Concat` map stored under `prefix`.
+fn decode_map_key<K: Decode>(prefix: &[u8], key: &[u8]) -> std::result::Result<K, codec::Error> {
+	// The key ends with the blake2_128 hash of the map key followed by the key itself.
+	let hashed = key.get(prefix.len() + 16..).ok_or("Storage key too short")?;
+	K::decode(&mut &hashed[..])
+}
+
+impl CliConfiguration for ExportAppStateCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn pruning_params(&self) -> Option<&PruningParams> {
+		Some(&self.pruning_params)
+	}
+
+	fn database_params(&self) -> Option<&DatabaseParams> {
+		Some(&self.database_params)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use frame_support::BoundedVec;
+
+	#[test]
+	fn map_keys_are_decoded() {
+		let prefix = pallet_kitties::KittyOwner::<Runtime>::final_prefix();
+		let key = pallet_kitties::KittyOwner::<Runtime>::hashed_key_for(42);
+		assert_eq!(decode_map_key::<KittyId>(&prefix, &key).ok(), Some(42));
+
+		let prefix = pallet_poe::Proofs::<Runtime>::final_prefix();
+		let claim: BoundedVec<u8, <Runtime as pallet_poe::Config>::MaxClaimLength> =
+			vec![1, 2, 3].try_into().unwrap();
+		let key = pallet_poe::Proofs::<Runtime>::hashed_key_for(&claim);
+		assert_eq!(decode_map_key::<Vec<u8>>(&prefix, &key).ok(), Some(vec![1, 2, 3]));
+
+		assert!(decode_map_key::<KittyId>(&prefix, &prefix).is_err());
+	}
+
+	fn kitty() -> KittyRecord {
+		KittyRecord {
+			id: 2,
+			dna: "0x0102".into(),
+			owner: Some("5Alice".into()),
+			parents: Some((0, 1)),
+		}
+	}
+
+	fn claim() -> ClaimRecord {
+		ClaimRecord { claim: "0x0a0b".into(), owner: "5Alice".into(), block_number: 7 }
+	}
+
+	#[test]
+	fn records_are_written_as_csv_rows() {
+		assert_eq!(kitty().csv_row(), "2,0x0102,5Alice,0,1");
+		let orphan = KittyRecord { owner: None, parents: None, ..kitty() };
+		assert_eq!(orphan.csv_row(), "2,0x0102,,,");
+		assert_eq!(claim().csv_row(), "0x0a0b,5Alice,7");
+
+		let columns = |header: &str| header.split(',').count();
+		assert_eq!(columns(KittyRecord::CSV_HEADER), kitty().csv_row().split(',').count());
+		assert_eq!(columns(ClaimRecord::CSV_HEADER), claim().csv_row().split(',').count());
+	}
+
+	#[test]
+	fn records_are_written_as_camel_case_json() {
+		assert_eq!(
+			serde_json::to_value(kitty()).unwrap(),
+			serde_json::json!({ "id": 2, "dna": "0x0102", "owner": "5Alice", "parents": [0, 1] })
+		);
+		assert_eq!(
+			serde_json::to_value(claim()).unwrap(),
+			serde_json::json!({ "claim": "0x0a0b", "owner": "5Alice", "blockNumber": 7 })
+		);
+	}
+}
@@ -2,13 +2,14 @@
 //!
 //! Should only be used for benchmarking as it may break in other contexts.
 
-use crate::service::FullClient;
+use crate::{chain_spec::BENCHMARK_KITTIES, service::FullClient};
 
 use node_template_runtime as runtime;
-use runtime::{AccountId, Balance, BalancesCall, SystemCall};
+use runtime::{AccountId, Balance, BalancesCall, KittiesCall, PoeCall, SystemCall};
 use sc_cli::Result;
 use sc_client_api::BlockBackend;
-use sp_core::{Encode, Pair};
+use sp_blockchain::HeaderBackend;
+use sp_core::{hashing::blake2_256, Encode, Pair};
 use sp_inherents::{InherentData, InherentDataProvider};
 use sp_keyring::Sr25519Keyring;
 use sp_runtime::{OpaqueExtrinsic, SaturatedConversion};
@@ -95,6 +96,128 @@ impl frame_benchmarking_cli::ExtrinsicBuilder for TransferKeepAliveBuilder {
 	}
 }
 
+/// Generates `Kitties::create` extrinsics for the benchmarks.
+///
+/// Note: Should only be used for benchmarking.
+pub struct KittyCreateBuilder {
+	client: Arc<FullClient>,
+}
+
+impl KittyCreateBuilder {
+	/// Creates a new [`Self`] from the given client.
+	pub fn new(client: Arc<FullClient>) -> Self {
+		Self { client }
+	}
+}
+
+impl frame_benchmarking_cli::ExtrinsicBuilder for KittyCreateBuilder {
+	fn pallet(&self) -> &str {
+		"kitties"
+	}
+
+	fn extrinsic(&self) -> &str {
+		"create"
+	}
+
+	fn build(&self, nonce: u32) -> std::result::Result<OpaqueExtrinsic, &'static str> {
+		let acc = Sr25519Keyring::Bob.pair();
+		let extrinsic: OpaqueExtrinsic = create_benchmark_extrinsic(
+			self.client.as_ref(),
+			acc,
+			KittiesCall::create {}.into(),
+			nonce,
+		)
+		.into();
+
+		Ok(extrinsic)
+	}
+}
+
+/// Generates `Kitties::transfer` extrinsics for the benchmarks, moving the kitties Bob owns on
+/// the development chain.
+///
+/// The extrinsic with nonce `n` transfers kitty `n`, so at most
+/// [`BENCHMARK_KITTIES`](crate::chain_spec::BENCHMARK_KITTIES) of them can be built.
+///
+/// Note: Should only be used for benchmarking.
+pub struct KittyTransferBuilder {
+	client: Arc<FullClient>,
+	dest: AccountId,
+}
+
+impl KittyTransferBuilder {
+	/// Creates a new [`Self`] from the given client.
+	pub fn new(client: Arc<FullClient>, dest: AccountId) -> Self {
+		Self { client, dest }
+	}
+}
+
+impl frame_benchmarking_cli::ExtrinsicBuilder for KittyTransferBuilder {
+	fn pallet(&self) -> &str {
+		"kitties"
+	}
+
+	fn extrinsic(&self) -> &str {
+		"transfer"
+	}
+
+	fn build(&self, nonce: u32) -> std::result::Result<OpaqueExtrinsic, &'static str> {
+		if nonce >= BENCHMARK_KITTIES {
+			return Err("Bob has no kitty left to transfer, lower --max-ext-per-block")
+		}
+		let acc = Sr25519Keyring::Bob.pair();
+		let extrinsic: OpaqueExtrinsic = create_benchmark_extrinsic(
+			self.client.as_ref(),
+			acc,
+			KittiesCall::transfer { to: self.dest.clone(), kitty_id: nonce }.into(),
+			nonce,
+		)
+		.into();
+
+		Ok(extrinsic)
+	}
+}
+
+/// Generates `PoeModule::create_claim` extrinsics for the benchmarks, with a unique claim of the
+/// maximum length for every nonce.
+///
+/// Note: Should only be used for benchmarking.
+pub struct PoeCreateClaimBuilder {
+	client: Arc<FullClient>,
+}
+
+impl PoeCreateClaimBuilder {
+	/// Creates a new [`Self`] from the given client.
+	pub fn new(client: Arc<FullClient>) -> Self {
+		Self { client }
+	}
+}
+
+impl frame_benchmarking_cli::ExtrinsicBuilder for PoeCreateClaimBuilder {
+	fn pallet(&self) -> &str {
+		"poe"
+	}
+
+	fn extrinsic(&self) -> &str {
+		"create_claim"
+	}
+
+	fn build(&self, nonce: u32) -> std::result::Result<OpaqueExtrinsic, &'static str> {
+		let acc = Sr25519Keyring::Bob.pair();
+		let claim =
+			blake2_256(&nonce.encode()).to_vec().try_into().map_err(|_| "claim too long")?;
+		let extrinsic: OpaqueExtrinsic = create_benchmark_extrinsic(
+			self.client.as_ref(),
+			acc,
+			PoeCall::create_claim { claim, metadata: None }.into(),
+			nonce,
+		)
+		.into();
+
+		Ok(extrinsic)
+	}
+}
+
 /// Create a transaction using the given `call`.
 ///
 /// Note: Should only be used for benchmarking.
@@ -104,50 +227,78 @@ pub fn create_benchmark_extrinsic(
 	call: runtime::RuntimeCall,
 	nonce: u32,
 ) -> runtime::UncheckedExtrinsic {
-	let genesis_hash = client.block_hash(0).ok().flatten().expect("Genesis block exists; qed");
-	let best_hash = client.chain_info().best_hash;
-	let best_block = client.chain_info().best_number;
-
-	let period = runtime::BlockHashCount::get()
-		.checked_next_power_of_two()
-		.map(|c| c / 2)
-		.unwrap_or(2) as u64;
-	let extra: runtime::SignedExtra = (
-		frame_system::CheckNonZeroSender::<runtime::Runtime>::new(),
-		frame_system::CheckSpecVersion::<runtime::Runtime>::new(),
-		frame_system::CheckTxVersion::<runtime::Runtime>::new(),
-		frame_system::CheckGenesis::<runtime::Runtime>::new(),
-		frame_system::CheckEra::<runtime::Runtime>::from(sp_runtime::generic::Era::mortal(
-			period,
-			best_block.saturated_into(),
-		)),
-		frame_system::CheckNonce::<runtime::Runtime>::from(nonce),
-		frame_system::CheckWeight::<runtime::Runtime>::new(),
-		pallet_transaction_payment::ChargeTransactionPayment::<runtime::Runtime>::from(0),
-	);
-
-	let raw_payload = runtime::SignedPayload::from_raw(
-		call.clone(),
-		extra.clone(),
-		(
-			(),
-			runtime::VERSION.spec_version,
-			runtime::VERSION.transaction_version,
+	let context = SigningContext {
+		genesis_hash: client.block_hash(0).ok().flatten().expect("Genesis block exists; qed"),
+		best_hash: client.chain_info().best_hash,
+		best_block: client.chain_info().best_number,
+		spec_version: runtime::VERSION.spec_version,
+		transaction_version: runtime::VERSION.transaction_version,
+	};
+	context.sign(&sender, call, nonce)
+}
+
+/// The chain state a transaction is signed against.
+pub struct SigningContext {
+	pub genesis_hash: runtime::Hash,
+	pub best_hash: runtime::Hash,
+	pub best_block: runtime::BlockNumber,
+	pub spec_version: u32,
+	pub transaction_version: u32,
+}
+
+impl SigningContext {
+	/// Signs `call` with the runtime's `SignedExtra`, mortal from the best block on.
+	pub fn sign(
+		&self,
+		sender: &sp_core::sr25519::Pair,
+		call: runtime::RuntimeCall,
+		nonce: u32,
+	) -> runtime::UncheckedExtrinsic {
+		let SigningContext {
 			genesis_hash,
 			best_hash,
-			(),
-			(),
-			(),
-		),
-	);
-	let signature = raw_payload.using_encoded(|e| sender.sign(e));
-
-	runtime::UncheckedExtrinsic::new_signed(
-		call.clone(),
-		sp_runtime::AccountId32::from(sender.public()).into(),
-		runtime::Signature::Sr25519(signature.clone()),
-		extra.clone(),
-	)
+			best_block,
+			spec_version,
+			transaction_version,
+		} = *self;
+
+		let period = runtime::BlockHashCount::get()
+			.checked_next_power_of_two()
+			.map(|c| c / 2)
+			.unwrap_or(2) as u64;
+		let extra: runtime::SignedExtra = (
+			frame_system::CheckNonZeroSender::<runtime::Runtime>::new(),
+			frame_system::CheckSpecVersion::<runtime::Runtime>::new(),
+			frame_system::CheckTxVersion::<runtime::Runtime>::new(),
+			frame_system::CheckGenesis::<runtime::Runtime>::new(),
+			frame_system::CheckEra::<runtime::Runtime>::from(sp_runtime::generic::Era::mortal(
+				period,
+				best_block.saturated_into(),
+			)),
+			frame_system::CheckWeight::<runtime::Runtime>::new(),
+			pallet_tx_pause::CheckNotPaused::<runtime::Runtime>::new(),
+			runtime::ChargeTransaction::paid(
+				nonce,
+				pallet_sponsor::ChargeSponsor::Signer(
+					pallet_asset_tx_payment::ChargeAssetTxPayment::from(0, None),
+				),
+			),
+		);
+
+		let raw_payload = runtime::SignedPayload::from_raw(
+			call.clone(),
+			extra.clone(),
+			((), spec_version, transaction_version, genesis_hash, best_hash, (), (), ()),
+		);
+		let signature = raw_payload.using_encoded(|e| sender.sign(e));
+
+		runtime::UncheckedExtrinsic::new_signed(
+			call.clone(),
+			sp_runtime::AccountId32::from(sender.public()).into(),
+			runtime::Signature::Sr25519(signature.clone()),
+			extra.clone(),
+		)
+	}
 }
 
 /// Generates inherent data for the `benchmark overhead` command.
@@ -1,12 +1,14 @@
 use node_template_runtime::{
-	AccountId, AuraConfig, BalancesConfig, GenesisConfig, GrandpaConfig, Signature, SudoConfig,
-	SystemConfig, WASM_BINARY,
+	AccountId, AuraConfig, Balance, BalancesConfig, BlockNumber, GenesisConfig, GrandpaConfig,
+	KittiesConfig, Signature, SudoConfig, SystemConfig, VestingConfig, WASM_BINARY,
 };
 use sc_service::ChainType;
+use serde::{Deserialize, Serialize};
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
 use sp_consensus_grandpa::AuthorityId as GrandpaId;
 use sp_core::{sr25519, Pair, Public};
 use sp_runtime::traits::{IdentifyAccount, Verify};
+use std::collections::BTreeMap;
 
 // The URL for the telemetry server.
 // const STAGING_TELEMETRY_URL: &str = "wss://telemetry.polkadot.io/submit/";
@@ -14,6 +16,53 @@ use sp_runtime::traits::{IdentifyAccount, Verify};
 /// Specialized `ChainSpec`. This is a specialization of the general Substrate ChainSpec type.
 pub type ChainSpec = sc_service::GenericChainSpec<GenesisConfig>;
 
+/// Balance endowed to every pre-funded account.
+const ENDOWMENT: Balance = 1 << 60;
+
+/// Kitties Bob owns on the development chain, for `benchmark extrinsic` to transfer. More than
+/// fit into a block.
+pub const BENCHMARK_KITTIES: u32 = 20_000;
+
+/// A locked genesis allocation, as listed in a vesting allocation file such as
+/// `res/public_testnet_vesting.json`.
+///
+/// `amount` is added to the account's balance and is locked until block `cliff`, after which it
+/// unlocks linearly over `period` blocks.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct VestedAllocation {
+	pub who: AccountId,
+	pub amount: Balance,
+	pub cliff: BlockNumber,
+	pub period: BlockNumber,
+}
+
+/// Parse a vesting allocation file.
+pub fn vested_allocations_from_json(json: &str) -> Result<Vec<VestedAllocation>, String> {
+	let vested =
+		serde_json::from_str(json).map_err(|e| format!("Invalid vesting allocations: {}", e))?;
+	merge_vested_allocations(vested)
+}
+
+/// Merge the allocations of each account into one.
+///
+/// `pallet_vesting` locks an account once per genesis schedule, each lock replacing the last, so
+/// an account can only be given one schedule. Allocations to the same account must therefore
+/// share their `cliff` and `period`.
+pub fn merge_vested_allocations(
+	vested: Vec<VestedAllocation>,
+) -> Result<Vec<VestedAllocation>, String> {
+	let mut merged: Vec<VestedAllocation> = Vec::with_capacity(vested.len());
+	for allocation in vested {
+		match merged.iter_mut().find(|a| a.who == allocation.who) {
+			Some(a) if a.cliff == allocation.cliff && a.period == allocation.period =>
+				a.amount += allocation.amount,
+			Some(_) => return Err(format!("Conflicting vesting schedules for {}", allocation.who)),
+			None => merged.push(allocation),
+		}
+	}
+	Ok(merged)
+}
+
 /// Generate a crypto pair from seed.
 pub fn get_from_seed<TPublic: Public>(seed: &str) -> <TPublic::Pair as Pair>::Public {
 	TPublic::Pair::from_string(&format!("//{}", seed), None)
@@ -59,6 +108,10 @@ pub fn development_config() -> Result<ChainSpec, String> {
 					get_account_id_from_seed::<sr25519::Public>("Alice//stash"),
 					get_account_id_from_seed::<sr25519::Public>("Bob//stash"),
 				],
+				// Vested allocations
+				vec![],
+				// Kitties
+				vec![(get_account_id_from_seed::<sr25519::Public>("Bob"), BENCHMARK_KITTIES)],
 				true,
 			)
 		},
@@ -107,6 +160,10 @@ pub fn local_testnet_config() -> Result<ChainSpec, String> {
 					get_account_id_from_seed::<sr25519::Public>("Eve//stash"),
 					get_account_id_from_seed::<sr25519::Public>("Ferdie//stash"),
 				],
+				// Vested allocations
+				vec![],
+				// Kitties
+				vec![],
 				true,
 			)
 		},
@@ -130,16 +187,19 @@ fn testnet_genesis(
 	initial_authorities: Vec<(AuraId, GrandpaId)>,
 	root_key: AccountId,
 	endowed_accounts: Vec<AccountId>,
+	vested: Vec<VestedAllocation>,
+	kitties: Vec<(AccountId, u32)>,
 	_enable_println: bool,
 ) -> GenesisConfig {
+	let (balances, vesting) = genesis_balances(endowed_accounts, vested);
 	GenesisConfig {
 		system: SystemConfig {
 			// Add Wasm runtime to storage.
 			code: wasm_binary.to_vec(),
 		},
 		balances: BalancesConfig {
-			// Configure endowed accounts with initial balance of 1 << 60.
-			balances: endowed_accounts.iter().cloned().map(|k| (k, 1 << 60)).collect(),
+			// Configure endowed accounts with initial balance of 1 << 60, plus any vested amount.
+			balances,
 		},
 		aura: AuraConfig {
 			authorities: initial_authorities.iter().map(|x| (x.0.clone())).collect(),
@@ -152,6 +212,10 @@ fn testnet_genesis(
 			key: Some(root_key),
 		},
 		transaction_payment: Default::default(),
+		assets: Default::default(),
+		treasury: Default::default(),
+		vesting: VestingConfig { vesting },
+		kitties: KittiesConfig { kitties },
 	}
 }
 
@@ -159,6 +223,7 @@ fn testnet_genesis(
 
 pub fn public_testnet_config() -> Result<ChainSpec, String> {
 	let wasm_binary = WASM_BINARY.ok_or_else(|| "Development wasm not available".to_string())?;
+	let vested = vested_allocations_from_json(include_str!("../res/public_testnet_vesting.json"))?;
 
 	Ok(ChainSpec::from_genesis(
 		// Name
@@ -188,6 +253,8 @@ pub fn public_testnet_config() -> Result<ChainSpec, String> {
 					get_account_id_from_seed::<sr25519::Public>("Eve//stash"),
 					get_account_id_from_seed::<sr25519::Public>("Ferdie//stash"),
 				],
+				// Vested allocations
+				vested.clone(),
 				true,
 			)
 		},
@@ -211,16 +278,18 @@ fn public_testnet_genesis(
 	initial_authorities: Vec<(AuraId, GrandpaId)>,
 	root_key: AccountId,
 	endowed_accounts: Vec<AccountId>,
+	vested: Vec<VestedAllocation>,
 	_enable_println: bool,
 ) -> GenesisConfig {
+	let (balances, vesting) = genesis_balances(endowed_accounts, vested);
 	GenesisConfig {
 		system: SystemConfig {
 			// Add Wasm runtime to storage.
 			code: wasm_binary.to_vec(),
 		},
 		balances: BalancesConfig {
-			// Configure endowed accounts with initial balance of 1 << 60.
-			balances: endowed_accounts.iter().cloned().map(|k| (k, 1 << 60)).collect(),
+			// Configure endowed accounts with initial balance of 1 << 60, plus any vested amount.
+			balances,
 		},
 		aura: AuraConfig {
 			authorities: initial_authorities.iter().map(|x| (x.0.clone())).collect(),
@@ -233,5 +302,122 @@ fn public_testnet_genesis(
 			key: Some(root_key),
 		},
 		transaction_payment: Default::default(),
+		assets: Default::default(),
+		treasury: Default::default(),
+		vesting: VestingConfig { vesting },
+		kitties: Default::default(),
+	}
+}
+
+/// Genesis balances and vesting schedules for the endowed accounts and vested allocations.
+///
+/// An account may be both endowed and vested, in which case only the vested amount is locked.
+/// Panics if an account is given conflicting vesting schedules.
+fn genesis_balances(
+	endowed_accounts: Vec<AccountId>,
+	vested: Vec<VestedAllocation>,
+) -> (Vec<(AccountId, Balance)>, Vec<(AccountId, BlockNumber, BlockNumber, Balance)>) {
+	let vested = merge_vested_allocations(vested).unwrap_or_else(|e| panic!("{}", e));
+	let mut balances: BTreeMap<AccountId, Balance> =
+		endowed_accounts.into_iter().map(|k| (k, ENDOWMENT)).collect();
+	for allocation in &vested {
+		*balances.entry(allocation.who.clone()).or_default() += allocation.amount;
+	}
+
+	let vesting = vested
+		.into_iter()
+		.map(|a| {
+			let liquid = balances[&a.who] - a.amount;
+			(a.who, a.cliff, a.period, liquid)
+		})
+		.collect();
+
+	(balances.into_iter().collect(), vesting)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use node_template_runtime::{Balances, BuildStorage, RuntimeOrigin, System, Vesting};
+
+	#[test]
+	fn public_testnet_vesting_file_is_valid() {
+		let vested =
+			vested_allocations_from_json(include_str!("../res/public_testnet_vesting.json"))
+				.unwrap();
+		assert_eq!(vested[0].who, get_account_id_from_seed::<sr25519::Public>("Charlie"));
+		assert_eq!(vested[1].who, get_account_id_from_seed::<sr25519::Public>("Dave"));
+	}
+
+	#[test]
+	fn vested_allocations_are_locked_until_they_vest() {
+		let team = get_account_id_from_seed::<sr25519::Public>("Charlie");
+		let allocation =
+			VestedAllocation { who: team.clone(), amount: 1_000_000, cliff: 10, period: 100 };
+		let genesis = testnet_genesis(
+			&[],
+			vec![authority_keys_from_seed("Alice")],
+			team.clone(),
+			vec![team.clone()],
+			vec![allocation],
+			vec![],
+			true,
+		);
+		let mut ext: sp_io::TestExternalities = genesis.build_storage().unwrap().into();
+
+		ext.execute_with(|| {
+			let locked = || Balances::locks(&team).iter().map(|l| l.amount).max().unwrap_or(0);
+			let vest_at = |block| {
+				System::set_block_number(block);
+				assert!(Vesting::vest(RuntimeOrigin::signed(team.clone())).is_ok());
+			};
+			assert_eq!(Balances::free_balance(&team), ENDOWMENT + 1_000_000);
+			assert_eq!(locked(), 1_000_000);
+
+			// Nothing unlocks before the cliff.
+			vest_at(10);
+			assert_eq!(locked(), 1_000_000);
+
+			vest_at(35);
+			assert_eq!(locked(), 750_000);
+
+			vest_at(110);
+			assert_eq!(locked(), 0);
+		});
+	}
+
+	#[test]
+	fn allocations_to_one_account_are_locked_together() {
+		let team = get_account_id_from_seed::<sr25519::Public>("Charlie");
+		let allocation =
+			VestedAllocation { who: team.clone(), amount: 1_000_000, cliff: 10, period: 100 };
+		let genesis = testnet_genesis(
+			&[],
+			vec![authority_keys_from_seed("Alice")],
+			team.clone(),
+			vec![],
+			vec![allocation.clone(), VestedAllocation { amount: 500_000, ..allocation }],
+			vec![],
+			true,
+		);
+		let mut ext: sp_io::TestExternalities = genesis.build_storage().unwrap().into();
+
+		ext.execute_with(|| {
+			assert_eq!(Balances::free_balance(&team), 1_500_000);
+			assert_eq!(Balances::locks(&team)[0].amount, 1_500_000);
+			assert_eq!(Vesting::vesting(&team).unwrap().len(), 1);
+		});
+	}
+
+	#[test]
+	fn conflicting_vesting_schedules_are_rejected() {
+		let team = get_account_id_from_seed::<sr25519::Public>("Charlie");
+		let allocation =
+			VestedAllocation { who: team.clone(), amount: 1_000_000, cliff: 10, period: 100 };
+		assert!(merge_vested_allocations(vec![
+			allocation.clone(),
+			VestedAllocation { cliff: 20, ..allocation }
+		])
+		.is_err());
 	}
 }
@@ -1,4 +1,5 @@
 use sc_cli::RunCmd;
+use std::path::PathBuf;
 
 #[derive(Debug, clap::Parser)]
 pub struct Cli {
@@ -7,6 +8,16 @@ pub struct Cli {
 
 	#[clap(flatten)]
 	pub run: RunCmd,
+
+	/// URL of the timestamp authority the PoE offchain worker sends new claims to.
+	///
+	/// Stored in the node's offchain storage, so it persists across restarts.
+	#[arg(long)]
+	pub poe_tsa_endpoint: Option<String>,
+
+	/// Index kitty, PoE claim, transfer and balance events into the SQLite database at `PATH`.
+	#[arg(long, value_name = "PATH")]
+	pub index_events: Option<PathBuf>,
 }
 
 #[derive(Debug, clap::Subcommand)]
@@ -27,6 +38,9 @@ pub enum Subcommand {
 	/// Export the state of a given block into a chain spec.
 	ExportState(sc_cli::ExportStateCmd),
 
+	/// Export the kitties and PoE claims of a given block as JSON or CSV records.
+	ExportAppState(crate::app_state::ExportAppStateCmd),
+
 	/// Import blocks.
 	ImportBlocks(sc_cli::ImportBlocksCmd),
 
@@ -36,6 +50,9 @@ pub enum Subcommand {
 	/// Revert the chain to a previous state.
 	Revert(sc_cli::RevertCmd),
 
+	/// Send a mix of kitty, PoE and transfer transactions to a running node.
+	LoadTest(crate::load_test::LoadTestCmd),
+
 	/// Sub-commands concerned with benchmarking.
 	#[command(subcommand)]
 	Benchmark(frame_benchmarking_cli::BenchmarkCmd),
@@ -1,5 +1,8 @@
 use crate::{
-	benchmarking::{inherent_benchmark_data, RemarkBuilder, TransferKeepAliveBuilder},
+	benchmarking::{
+		inherent_benchmark_data, KittyCreateBuilder, KittyTransferBuilder, PoeCreateClaimBuilder,
+		RemarkBuilder, TransferKeepAliveBuilder,
+	},
 	chain_spec,
 	cli::{Cli, Subcommand},
 	service,
@@ -85,6 +88,13 @@ pub fn run() -> sc_cli::Result<()> {
 				Ok((cmd.run(client, config.chain_spec), task_manager))
 			})
 		},
+		Some(Subcommand::ExportAppState(cmd)) => {
+			let runner = cli.create_runner(cmd)?;
+			runner.sync_run(|config| {
+				let PartialComponents { client, .. } = service::new_partial(&config)?;
+				cmd.run(&client)
+			})
+		},
 		Some(Subcommand::ImportBlocks(cmd)) => {
 			let runner = cli.create_runner(cmd)?;
 			runner.async_run(|config| {
@@ -109,6 +119,7 @@ pub fn run() -> sc_cli::Result<()> {
 				Ok((cmd.run(client, backend, Some(aux_revert)), task_manager))
 			})
 		},
+		Some(Subcommand::LoadTest(cmd)) => sc_cli::build_runtime()?.block_on(cmd.run()),
 		Some(Subcommand::Benchmark(cmd)) => {
 			let runner = cli.create_runner(cmd)?;
 
@@ -159,7 +170,8 @@ pub fn run() -> sc_cli::Result<()> {
 					},
 					BenchmarkCmd::Extrinsic(cmd) => {
 						let PartialComponents { client, .. } = service::new_partial(&config)?;
-						// Register the *Remark* and *TKA* builders.
+						// Register the *Remark*, *TKA*, kitties and PoE builders. The command logs
+						// how many of the chosen extrinsic fit into a block.
 						let ext_factory = ExtrinsicFactory(vec![
 							Box::new(RemarkBuilder::new(client.clone())),
 							Box::new(TransferKeepAliveBuilder::new(
@@ -167,6 +179,12 @@ pub fn run() -> sc_cli::Result<()> {
 								Sr25519Keyring::Alice.to_account_id(),
 								EXISTENTIAL_DEPOSIT,
 							)),
+							Box::new(KittyCreateBuilder::new(client.clone())),
+							Box::new(KittyTransferBuilder::new(
+								client.clone(),
+								Sr25519Keyring::Alice.to_account_id(),
+							)),
+							Box::new(PoeCreateClaimBuilder::new(client.clone())),
 						]);
 
 						cmd.run(client, inherent_benchmark_data()?, Vec::new(), &ext_factory)
@@ -209,8 +227,11 @@ pub fn run() -> sc_cli::Result<()> {
 		},
 		None => {
 			let runner = cli.create_runner(&cli.run)?;
+			let poe_tsa_endpoint = cli.poe_tsa_endpoint.clone();
+			let index_events = cli.index_events.clone();
 			runner.run_node_until_exit(|config| async move {
-				service::new_full(config).map_err(sc_cli::Error::Service)
+				service::new_full(config, poe_tsa_endpoint, index_events)
+					.map_err(sc_cli::Error::Service)
 			})
 		},
 	}
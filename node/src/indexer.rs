@@ -0,0 +1,435 @@
+//! Indexes kitty, PoE claim, transfer and balance events into an SQLite database.
+//!
+//! Rows are written as soon as a block becomes the best block and are tied to it through the
+//! `blocks` table. When a reorg retracts unfinalized blocks their rows are deleted again, and once
+//! a block is finalized its rows are kept for good.
+//!
+//! A new database starts at the block that is finalized when the indexer first runs; later runs
+//! catch up from the blocks indexed before. Blocks whose events can't be read, because their
+//! state was pruned or their events don't decode with the current runtime, are logged and
+//! indexed without rows, so that they don't hold up the blocks after them.
+
+use crate::service::FullClient;
+use codec::Decode;
+use frame_support::storage::StorageValue;
+use futures::{future, StreamExt};
+use node_template_runtime::{Hash, Runtime, RuntimeEvent};
+use rusqlite::{params, Connection, OptionalExtension};
+use sc_client_api::{BlockchainEvents, StorageProvider};
+use sp_blockchain::HeaderBackend;
+use sp_core::{bytes::to_hex, crypto::Ss58Codec, storage::StorageKey};
+use sp_runtime::traits::Header;
+use std::{error::Error, path::Path, sync::Arc};
+
+const LOG_TARGET: &str = "indexer";
+
+type Result<T> = std::result::Result<T, Box<dyn Error + Send + Sync>>;
+type EventRecord = frame_system::EventRecord<RuntimeEvent, Hash>;
+
+const SCHEMA: &str = "
+	PRAGMA foreign_keys = ON;
+	CREATE TABLE IF NOT EXISTS blocks (
+		hash TEXT PRIMARY KEY,
+		number INTEGER NOT NULL,
+		finalized INTEGER NOT NULL DEFAULT 0
+	);
+	CREATE TABLE IF NOT EXISTS kitties (
+		block_hash TEXT NOT NULL REFERENCES blocks(hash) ON DELETE CASCADE,
+		event_index INTEGER NOT NULL,
+		kind TEXT NOT NULL,
+		kitty_id INTEGER NOT NULL,
+		owner TEXT NOT NULL,
+		previous_owner TEXT,
+		dna TEXT
+	);
+	CREATE TABLE IF NOT EXISTS claims (
+		block_hash TEXT NOT NULL REFERENCES blocks(hash) ON DELETE CASCADE,
+		event_index INTEGER NOT NULL,
+		kind TEXT NOT NULL,
+		owner TEXT NOT NULL,
+		claim TEXT NOT NULL
+	);
+	CREATE TABLE IF NOT EXISTS transfers (
+		block_hash TEXT NOT NULL REFERENCES blocks(hash) ON DELETE CASCADE,
+		event_index INTEGER NOT NULL,
+		sender TEXT NOT NULL,
+		recipient TEXT NOT NULL,
+		amount TEXT NOT NULL
+	);
+	CREATE TABLE IF NOT EXISTS balances (
+		block_hash TEXT NOT NULL REFERENCES blocks(hash) ON DELETE CASCADE,
+		event_index INTEGER NOT NULL,
+		kind TEXT NOT NULL,
+		account TEXT NOT NULL,
+		amount TEXT NOT NULL
+	);
+";
+
+/// The SQLite side of the indexer.
+pub struct Database {
+	conn: Connection,
+}
+
+impl Database {
+	pub fn open(path: &Path) -> Result<Self> {
+		Self::new(Connection::open(path)?)
+	}
+
+	fn new(conn: Connection) -> Result<Self> {
+		conn.execute_batch(SCHEMA)?;
+		Ok(Self { conn })
+	}
+
+	/// The number of the lowest indexed block, or `None` if nothing is indexed yet.
+	pub fn first_indexed(&self) -> Result<Option<u32>> {
+		Ok(self.conn.query_row("SELECT MIN(number) FROM blocks", [], |row| row.get(0))?)
+	}
+
+	pub fn is_indexed(&self, hash: Hash) -> Result<bool> {
+		let found = self
+			.conn
+			.query_row("SELECT 1 FROM blocks WHERE hash = ?1", params![hex(hash)], |_| Ok(()))
+			.optional()?;
+		Ok(found.is_some())
+	}
+
+	/// Deletes every unfinalized block above `number` together with its rows, or every
+	/// unfinalized block if `number` is `None`.
+	pub fn retract_above(&self, number: Option<u32>) -> Result<()> {
+		let number = number.map_or(-1, i64::from);
+		self.conn
+			.execute("DELETE FROM blocks WHERE finalized = 0 AND number > ?1", params![number])?;
+		Ok(())
+	}
+
+	/// Marks every block up to `number` as finalized.
+	///
+	/// Only blocks of the best chain are indexed, so all of them are ancestors of the finalized
+	/// block.
+	pub fn finalize(&self, number: u32) -> Result<()> {
+		self.conn
+			.execute("UPDATE blocks SET finalized = 1 WHERE number <= ?1", params![number])?;
+		Ok(())
+	}
+
+	pub fn insert_block(&mut self, hash: Hash, number: u32, events: &[EventRecord]) -> Result<()> {
+		let tx = self.conn.transaction()?;
+		let block = hex(hash);
+		tx.execute("INSERT INTO blocks (hash, number) VALUES (?1, ?2)", params![block, number])?;
+
+		for (index, record) in events.iter().enumerate() {
+			let index = index as u32;
+			match &record.event {
+				RuntimeEvent::Kitties(pallet_kitties::Event::KittyCreated {
+					who,
+					kitty_id,
+					kitty,
+				}) => {
+					tx.execute(
+						"INSERT INTO kitties (block_hash, event_index, kind, kitty_id, owner, dna)
+						VALUES (?1, ?2, 'created', ?3, ?4, ?5)",
+						params![
+							block,
+							index,
+							kitty_id,
+							who.to_ss58check(),
+							to_hex(&kitty.0, false)
+						],
+					)?;
+				},
+				RuntimeEvent::Kitties(pallet_kitties::Event::KittyBreed {
+					who,
+					kitty_id,
+					kitty,
+				}) => {
+					tx.execute(
+						"INSERT INTO kitties (block_hash, event_index, kind, kitty_id, owner, dna)
+						VALUES (?1, ?2, 'bred', ?3, ?4, ?5)",
+						params![
+							block,
+							index,
+							kitty_id,
+							who.to_ss58check(),
+							to_hex(&kitty.0, false)
+						],
+					)?;
+				},
+				RuntimeEvent::Kitties(pallet_kitties::Event::KittyTransferred {
+					from,
+					to,
+					kitty_id,
+				}) => {
+					tx.execute(
+						"INSERT INTO kitties
+						(block_hash, event_index, kind, kitty_id, owner, previous_owner)
+						VALUES (?1, ?2, 'transferred', ?3, ?4, ?5)",
+						params![block, index, kitty_id, to.to_ss58check(), from.to_ss58check()],
+					)?;
+				},
+				RuntimeEvent::PoeModule(pallet_poe::Event::ClaimCreated(who, claim)) => {
+					tx.execute(
+						"INSERT INTO claims (block_hash, event_index, kind, owner, claim)
+						VALUES (?1, ?2, 'created', ?3, ?4)",
+						params![block, index, who.to_ss58check(), to_hex(claim, false)],
+					)?;
+				},
+				RuntimeEvent::PoeModule(pallet_poe::Event::ClaimRevoked(who, claim)) => {
+					tx.execute(
+						"INSERT INTO claims (block_hash, event_index, kind, owner, claim)
+						VALUES (?1, ?2, 'revoked', ?3, ?4)",
+						params![block, index, who.to_ss58check(), to_hex(claim, false)],
+					)?;
+				},
+				RuntimeEvent::Balances(pallet_balances::Event::Transfer { from, to, amount }) => {
+					// Balances don't fit into SQLite's 64 bit integers, so they are stored as text.
+					tx.execute(
+						"INSERT INTO transfers (block_hash, event_index, sender, recipient, amount)
+						VALUES (?1, ?2, ?3, ?4, ?5)",
+						params![
+							block,
+							index,
+							from.to_ss58check(),
+							to.to_ss58check(),
+							amount.to_string()
+						],
+					)?;
+				},
+				RuntimeEvent::Balances(event) => {
+					let (kind, account, amount) = match event {
+						pallet_balances::Event::Endowed { account, free_balance } =>
+							("endowed", account, free_balance),
+						pallet_balances::Event::DustLost { account, amount } =>
+							("dust_lost", account, amount),
+						pallet_balances::Event::Reserved { who, amount } =>
+							("reserved", who, amount),
+						pallet_balances::Event::Unreserved { who, amount } =>
+							("unreserved", who, amount),
+						pallet_balances::Event::Deposit { who, amount } => ("deposit", who, amount),
+						pallet_balances::Event::Withdraw { who, amount } =>
+							("withdraw", who, amount),
+						pallet_balances::Event::Slashed { who, amount } => ("slashed", who, amount),
+						_ => continue,
+					};
+					tx.execute(
+						"INSERT INTO balances (block_hash, event_index, kind, account, amount)
+						VALUES (?1, ?2, ?3, ?4, ?5)",
+						params![block, index, kind, account.to_ss58check(), amount.to_string()],
+					)?;
+				},
+				_ => {},
+			}
+		}
+		tx.commit()?;
+		Ok(())
+	}
+}
+
+fn hex(hash: Hash) -> String {
+	to_hex(hash.as_bytes(), false)
+}
+
+/// Follows the client's best and finalized blocks and indexes their events into `db`.
+pub(crate) async fn run(client: Arc<FullClient>, mut db: Database) {
+	let best = client
+		.import_notification_stream()
+		.filter(|n| future::ready(n.is_new_best))
+		.map(|n| (false, n.hash, *n.header.number()));
+	let finalized = client
+		.finality_notification_stream()
+		.map(|n| (true, n.hash, *n.header.number()));
+	let mut notifications = futures::stream::select(best, finalized);
+
+	// Catch up with blocks imported while the indexer wasn't running. A new database starts at
+	// the finalized block rather than at genesis, whose state is likely pruned.
+	let info = client.info();
+	let first = match db.first_indexed() {
+		Ok(first) => first.unwrap_or(info.finalized_number),
+		Err(e) => {
+			log::error!(target: LOG_TARGET, "Failed to read the database: {}", e);
+			return
+		},
+	};
+	if let Err(e) = index_best(&*client, &mut db, first, info.best_hash, info.best_number)
+		.and_then(|_| db.finalize(info.finalized_number))
+	{
+		log::warn!(target: LOG_TARGET, "Failed to catch up with the chain: {}", e);
+	}
+
+	while let Some((is_finalized, hash, number)) = notifications.next().await {
+		let result = if is_finalized {
+			db.finalize(number)
+		} else {
+			index_best(&*client, &mut db, first, hash, number)
+		};
+		if let Err(e) = result {
+			log::warn!(target: LOG_TARGET, "Failed to index block #{} ({:?}): {}", number, hash, e);
+		}
+	}
+}
+
+/// What the indexer reads from the chain.
+trait Chain {
+	/// The parent of block `hash`, or `None` if its header is not known.
+	fn parent_hash(&self, hash: Hash) -> Result<Option<Hash>>;
+	/// The events of block `hash`.
+	fn events(&self, hash: Hash) -> Result<Vec<EventRecord>>;
+}
+
+impl Chain for FullClient {
+	fn parent_hash(&self, hash: Hash) -> Result<Option<Hash>> {
+		Ok(self.header(hash)?.map(|header| *header.parent_hash()))
+	}
+
+	fn events(&self, hash: Hash) -> Result<Vec<EventRecord>> {
+		let key = StorageKey(frame_system::Events::<Runtime>::hashed_key().to_vec());
+		match self.storage(hash, &key)? {
+			Some(data) => Ok(Decode::decode(&mut &data.0[..])?),
+			None => Ok(Vec::new()),
+		}
+	}
+}
+
+/// Indexes the new best block `hash` and every ancestor down to block `first` that is not
+/// indexed yet, after rolling back the unfinalized blocks a reorg retracted.
+fn index_best(
+	chain: &impl Chain,
+	db: &mut Database,
+	first: u32,
+	mut hash: Hash,
+	mut number: u32,
+) -> Result<()> {
+	let mut route = Vec::new();
+	let ancestor = loop {
+		if db.is_indexed(hash)? || number < first {
+			break Some(number)
+		}
+		route.push((hash, number));
+		if number == 0 {
+			break None
+		}
+		match chain.parent_hash(hash)? {
+			Some(parent) => hash = parent,
+			None => {
+				log::warn!(target: LOG_TARGET, "Missing header of block #{} ({:?})", number, hash);
+				break Some(number - 1)
+			},
+		}
+		number -= 1;
+	};
+
+	db.retract_above(ancestor)?;
+	for (hash, number) in route.into_iter().rev() {
+		let events = chain.events(hash).unwrap_or_else(|e| {
+			log::warn!(
+				target: LOG_TARGET,
+				"Indexing block #{} ({:?}) without its events, which can't be read: {}",
+				number,
+				hash,
+				e,
+			);
+			Vec::new()
+		});
+		db.insert_block(hash, number, &events)?;
+	}
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use frame_system::Phase;
+	use sp_keyring::AccountKeyring::{Alice, Bob};
+
+	fn transfer(amount: u128) -> EventRecord {
+		EventRecord {
+			phase: Phase::ApplyExtrinsic(1),
+			event: RuntimeEvent::Balances(pallet_balances::Event::Transfer {
+				from: Alice.to_account_id(),
+				to: Bob.to_account_id(),
+				amount,
+			}),
+			topics: vec![],
+		}
+	}
+
+	fn count(db: &Database, table: &str) -> u32 {
+		db.conn
+			.query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| row.get(0))
+			.unwrap()
+	}
+
+	#[test]
+	fn retracted_blocks_are_rolled_back() {
+		let mut db = Database::new(Connection::open_in_memory().unwrap()).unwrap();
+		db.insert_block(Hash::repeat_byte(1), 1, &[transfer(10)]).unwrap();
+		db.insert_block(Hash::repeat_byte(2), 2, &[transfer(20)]).unwrap();
+		assert_eq!(count(&db, "transfers"), 2);
+
+		db.retract_above(Some(1)).unwrap();
+		assert!(db.is_indexed(Hash::repeat_byte(1)).unwrap());
+		assert!(!db.is_indexed(Hash::repeat_byte(2)).unwrap());
+		assert_eq!(count(&db, "transfers"), 1);
+	}
+
+	/// A chain of blocks `0..events.len()`, with the hash of block `n` made of `n + 1`.
+	struct TestChain {
+		/// The events of each block, or `None` if its state was pruned.
+		events: Vec<Option<Vec<EventRecord>>>,
+	}
+
+	fn block_hash(number: u32) -> Hash {
+		Hash::repeat_byte(number as u8 + 1)
+	}
+
+	impl Chain for TestChain {
+		fn parent_hash(&self, hash: Hash) -> Result<Option<Hash>> {
+			let number = u32::from(hash[0] - 1);
+			Ok(number.checked_sub(1).map(block_hash))
+		}
+
+		fn events(&self, hash: Hash) -> Result<Vec<EventRecord>> {
+			let number = usize::from(hash[0] - 1);
+			Ok(self.events[number].clone().ok_or("State already discarded")?)
+		}
+	}
+
+	#[test]
+	fn blocks_with_pruned_state_are_skipped() {
+		let mut db = Database::new(Connection::open_in_memory().unwrap()).unwrap();
+		let chain = TestChain {
+			events: vec![None, None, Some(vec![transfer(10)]), Some(vec![transfer(20)])],
+		};
+
+		index_best(&chain, &mut db, 0, block_hash(3), 3).unwrap();
+
+		for number in 0..=3 {
+			assert!(db.is_indexed(block_hash(number)).unwrap());
+		}
+		assert_eq!(count(&db, "transfers"), 2);
+	}
+
+	#[test]
+	fn indexing_starts_at_the_first_block() {
+		let mut db = Database::new(Connection::open_in_memory().unwrap()).unwrap();
+		let chain = TestChain { events: vec![None, Some(vec![]), Some(vec![transfer(10)])] };
+
+		index_best(&chain, &mut db, 1, block_hash(2), 2).unwrap();
+
+		assert!(!db.is_indexed(block_hash(0)).unwrap());
+		assert_eq!(db.first_indexed().unwrap(), Some(1));
+		assert_eq!(count(&db, "blocks"), 2);
+	}
+
+	#[test]
+	fn finalized_blocks_are_kept() {
+		let mut db = Database::new(Connection::open_in_memory().unwrap()).unwrap();
+		db.insert_block(Hash::repeat_byte(1), 1, &[transfer(10)]).unwrap();
+		db.insert_block(Hash::repeat_byte(2), 2, &[transfer(20)]).unwrap();
+
+		db.finalize(1).unwrap();
+		db.retract_above(None).unwrap();
+		assert!(db.is_indexed(Hash::repeat_byte(1)).unwrap());
+		assert_eq!(count(&db, "blocks"), 1);
+		assert_eq!(count(&db, "transfers"), 1);
+	}
+}
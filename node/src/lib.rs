@@ -1,3 +1,5 @@
+pub mod benchmarking;
 pub mod chain_spec;
+pub mod indexer;
 pub mod rpc;
 pub mod service;
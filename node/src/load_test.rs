@@ -0,0 +1,535 @@
+//! The `load-test` subcommand, which sends a mix of kitty, PoE and transfer transactions to a
+//! running node at a fixed rate and reports how long they took to be included.
+//!
+//! Transactions are submitted without a subscription each. Their inclusion is instead seen from a
+//! single subscription to the blocks the node imports, as a subscription per transaction soon
+//! runs into the node's limits on concurrent requests and subscriptions.
+
+use crate::benchmarking::SigningContext;
+use codec::{Decode, Encode};
+use frame_support::storage::StorageValue;
+use frame_system::{EventRecord, Phase};
+use futures::{
+	channel::{mpsc, oneshot},
+	StreamExt,
+};
+use jsonrpsee::{
+	core::client::{ClientT, SubscriptionClientT},
+	rpc_params,
+	ws_client::{WsClient, WsClientBuilder},
+};
+use node_template_runtime::{
+	opaque::{Block, Header},
+	AccountId, Balance, BalancesCall, Hash, KittiesCall, PoeCall, Runtime, RuntimeCall,
+	RuntimeEvent, UtilityCall, EXISTENTIAL_DEPOSIT, UNIT,
+};
+use sc_cli::{Error, Result};
+use sp_core::{crypto::Ss58Codec, hashing::blake2_256, sr25519, Bytes, Pair};
+use sp_runtime::{generic::SignedBlock, traits::Header as _, DispatchError};
+use std::{
+	collections::{BTreeMap, HashMap},
+	str::FromStr,
+	sync::{Arc, Mutex, MutexGuard},
+	time::{Duration, Instant},
+};
+
+/// Balance every derived account is funded with.
+const FUNDING: Balance = 100 * UNIT;
+/// Number of accounts funded by a single batch.
+const FUNDING_BATCH: usize = 200;
+/// How often the transactions are signed against a new best block, well within the mortality
+/// period, and against the current runtime version.
+const CONTEXT_REFRESH: Duration = Duration::from_secs(60);
+
+/// A kind of transaction sent by the load test.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CallKind {
+	/// `Kitties::create`.
+	Kitties,
+	/// `PoeModule::create_claim` with a unique claim.
+	Poe,
+	/// `Balances::transfer_keep_alive` to another derived account.
+	Transfer,
+}
+
+impl FromStr for CallKind {
+	type Err = String;
+
+	fn from_str(s: &str) -> std::result::Result<Self, String> {
+		match s {
+			"kitties" => Ok(Self::Kitties),
+			"poe" => Ok(Self::Poe),
+			"transfer" => Ok(Self::Transfer),
+			_ => Err(format!("unknown call `{}`, expected kitties, poe or transfer", s)),
+		}
+	}
+}
+
+/// The share of each kind of transaction, e.g. `kitties:40,poe:40,transfer:20`.
+#[derive(Debug, Clone)]
+pub struct Mix(Vec<(CallKind, u32)>);
+
+impl FromStr for Mix {
+	type Err = String;
+
+	fn from_str(s: &str) -> std::result::Result<Self, String> {
+		let mix = s
+			.split(',')
+			.map(|part| {
+				let (kind, weight) = part
+					.split_once(':')
+					.ok_or_else(|| format!("expected `<call>:<weight>`, got `{}`", part))?;
+				let weight =
+					weight.parse().map_err(|e| format!("invalid weight `{}`: {}", weight, e))?;
+				Ok((kind.parse()?, weight))
+			})
+			.collect::<std::result::Result<Vec<_>, String>>()?;
+		if mix.iter().all(|(_, weight)| *weight == 0) {
+			return Err("the mix needs at least one non-zero weight".into())
+		}
+		Ok(Self(mix))
+	}
+}
+
+impl Mix {
+	/// The kind of the `index`th transaction, so that every window of `total weight`
+	/// transactions follows the mix exactly.
+	fn kind(&self, index: u64) -> CallKind {
+		let total: u64 = self.0.iter().map(|(_, weight)| u64::from(*weight)).sum();
+		let mut slot = index % total;
+		for (kind, weight) in &self.0 {
+			if slot < u64::from(*weight) {
+				return *kind
+			}
+			slot -= u64::from(*weight);
+		}
+		unreachable!("slot is below the total weight; qed")
+	}
+}
+
+/// Send a mix of transactions to a running node and report their inclusion latency.
+#[derive(Debug, Clone, clap::Parser)]
+pub struct LoadTestCmd {
+	/// WebSocket RPC endpoint of the node.
+	#[arg(long, default_value = "ws://127.0.0.1:9944")]
+	pub url: String,
+
+	/// Transactions sent per second.
+	#[arg(long, default_value_t = 10)]
+	pub tps: u32,
+
+	/// Share of each kind of transaction, out of `kitties`, `poe` and `transfer`.
+	#[arg(long, default_value = "kitties:40,poe:40,transfer:20")]
+	pub mix: Mix,
+
+	/// Number of accounts the transactions are sent from.
+	#[arg(long, default_value_t = 100)]
+	pub accounts: u32,
+
+	/// Seed the accounts are derived from, as `<seed>//<index>`.
+	#[arg(long, default_value = "//LoadTest")]
+	pub seed: String,
+
+	/// Account that funds the derived accounts before the test.
+	#[arg(long, default_value = "//Alice")]
+	pub funder: String,
+
+	/// Don't fund the derived accounts, e.g. because an earlier run already did.
+	#[arg(long)]
+	pub skip_funding: bool,
+
+	/// How long to send transactions for, in seconds.
+	#[arg(long, default_value_t = 60)]
+	pub duration: u64,
+
+	/// How long to wait for a transaction to be included before counting it as failed, in
+	/// seconds.
+	#[arg(long, default_value_t = 60)]
+	pub timeout: u64,
+}
+
+enum Outcome {
+	Included(Duration),
+	Failed(String),
+}
+
+#[derive(Default)]
+struct Stats {
+	latencies: Vec<Duration>,
+	failures: BTreeMap<String, u32>,
+}
+
+impl LoadTestCmd {
+	pub async fn run(&self) -> Result<()> {
+		if self.tps == 0 || self.accounts == 0 {
+			return Err("--tps and --accounts must be greater than zero".into())
+		}
+		let timeout = Duration::from_secs(self.timeout);
+		let client =
+			Arc::new(WsClientBuilder::default().build(&self.url).await.map_err(rpc_error)?);
+		let inclusions = Inclusions::start(client.clone()).await?;
+		let mut context = signing_context(&client).await?;
+		let mut context_age = Instant::now();
+		let accounts = (0..self.accounts)
+			.map(|i| pair(&format!("{}//{}", self.seed, i)))
+			.collect::<Result<Vec<_>>>()?;
+
+		if !self.skip_funding {
+			self.fund(&client, &inclusions, &context, &accounts).await?;
+		}
+		let mut nonces = Vec::with_capacity(accounts.len());
+		for account in &accounts {
+			nonces.push(next_nonce(&client, account).await?);
+		}
+
+		// Accounts whose transaction was rejected have to fetch their nonce again.
+		let (rejected_tx, mut rejected_rx) = mpsc::unbounded::<usize>();
+		let mut tasks = Vec::new();
+		let mut interval = tokio::time::interval(Duration::from_secs(1) / self.tps);
+		for index in 0..self.duration * u64::from(self.tps) {
+			interval.tick().await;
+			while let Ok(Some(account)) = rejected_rx.try_next() {
+				nonces[account] = next_nonce(&client, &accounts[account]).await?;
+			}
+			if context_age.elapsed() >= CONTEXT_REFRESH {
+				context = signing_context(&client).await?;
+				context_age = Instant::now();
+			}
+
+			let account = (index % accounts.len() as u64) as usize;
+			let kind = self.mix.kind(index);
+			let nonce = nonces[account];
+			let call = self.call(kind, &accounts, account, nonce);
+			let extrinsic = context.sign(&accounts[account], call, nonce).encode();
+			nonces[account] += 1;
+
+			let client = client.clone();
+			let inclusions = inclusions.clone();
+			let rejected_tx = rejected_tx.clone();
+			tasks.push(tokio::spawn(async move {
+				let outcome = watch(&client, &inclusions, extrinsic, timeout).await;
+				if matches!(outcome, Outcome::Failed(_)) {
+					let _ = rejected_tx.unbounded_send(account);
+				}
+				(kind, outcome)
+			}));
+		}
+
+		let mut stats = BTreeMap::<CallKind, Stats>::new();
+		for task in tasks {
+			let (kind, outcome) = task.await.map_err(|e| Error::Application(e.into()))?;
+			let stats = stats.entry(kind).or_default();
+			match outcome {
+				Outcome::Included(latency) => stats.latencies.push(latency),
+				Outcome::Failed(reason) => *stats.failures.entry(reason).or_default() += 1,
+			}
+		}
+		report(stats);
+		Ok(())
+	}
+
+	fn call(
+		&self,
+		kind: CallKind,
+		accounts: &[sr25519::Pair],
+		account: usize,
+		nonce: u32,
+	) -> RuntimeCall {
+		match kind {
+			CallKind::Kitties => KittiesCall::create {}.into(),
+			CallKind::Poe => {
+				let claim = blake2_256(&(accounts[account].public(), nonce).encode());
+				PoeCall::create_claim {
+					claim: claim.to_vec().try_into().expect("claims of 32 bytes fit; qed"),
+					metadata: None,
+				}
+				.into()
+			},
+			CallKind::Transfer => BalancesCall::transfer_keep_alive {
+				dest: AccountId::from(accounts[(account + 1) % accounts.len()].public()).into(),
+				value: EXISTENTIAL_DEPOSIT,
+			}
+			.into(),
+		}
+	}
+
+	/// Sends `FUNDING` to every account from `--funder` and waits until all of it is included.
+	async fn fund(
+		&self,
+		client: &WsClient,
+		inclusions: &Inclusions,
+		context: &SigningContext,
+		accounts: &[sr25519::Pair],
+	) -> Result<()> {
+		let funder = pair(&self.funder)?;
+		let mut nonce = next_nonce(client, &funder).await?;
+		let batches = accounts.chunks(FUNDING_BATCH).map(|chunk| {
+			let calls = chunk
+				.iter()
+				.map(|account| {
+					BalancesCall::transfer_keep_alive {
+						dest: AccountId::from(account.public()).into(),
+						value: FUNDING,
+					}
+					.into()
+				})
+				.collect();
+			let extrinsic =
+				context.sign(&funder, UtilityCall::batch_all { calls }.into(), nonce).encode();
+			nonce += 1;
+			watch(client, inclusions, extrinsic, Duration::from_secs(self.timeout))
+		});
+
+		for outcome in futures::future::join_all(batches).await {
+			if let Outcome::Failed(reason) = outcome {
+				return Err(format!("Funding the accounts failed: {}", reason).into())
+			}
+		}
+		Ok(())
+	}
+}
+
+/// The transactions waiting to be included, by hash.
+type Pending = HashMap<Hash, oneshot::Sender<(Hash, u32)>>;
+
+/// Tells submitted transactions the block they are included in and their index in it, from a
+/// single subscription to all imported blocks.
+#[derive(Clone)]
+struct Inclusions {
+	pending: Arc<Mutex<Pending>>,
+}
+
+impl Inclusions {
+	/// Subscribes to the blocks `client` imports.
+	async fn start(client: Arc<WsClient>) -> Result<Self> {
+		let mut heads = client
+			.subscribe::<Header, _>(
+				"chain_subscribeAllHeads",
+				rpc_params![],
+				"chain_unsubscribeAllHeads",
+			)
+			.await
+			.map_err(rpc_error)?;
+		let inclusions = Self { pending: Default::default() };
+
+		let tracker = inclusions.clone();
+		tokio::spawn(async move {
+			while let Some(Ok(header)) = heads.next().await {
+				let block = header.hash();
+				let signed_block: Option<SignedBlock<Block>> =
+					match client.request("chain_getBlock", rpc_params![block]).await {
+						Ok(signed_block) => signed_block,
+						Err(e) => {
+							log::warn!("Failed to fetch block {:?}: {}", block, e);
+							continue
+						},
+					};
+				let Some(signed_block) = signed_block else { continue };
+
+				let mut pending = tracker.pending();
+				for (index, extrinsic) in signed_block.block.extrinsics.iter().enumerate() {
+					if let Some(included) = pending.remove(&blake2_256(&extrinsic.encode()).into())
+					{
+						let _ = included.send((block, index as u32));
+					}
+				}
+			}
+			// Dropping the senders fails the transactions still waiting.
+			tracker.pending().clear();
+		});
+		Ok(inclusions)
+	}
+
+	/// Submits `extrinsic` and returns the block it is included in and its index in it.
+	async fn submit(
+		&self,
+		client: &WsClient,
+		extrinsic: &[u8],
+	) -> std::result::Result<(Hash, u32), String> {
+		let hash = Hash::from(blake2_256(extrinsic));
+		let (included, inclusion) = oneshot::channel();
+		// Registered before submitting, as the extrinsic may be included before the node answers.
+		self.pending().insert(hash, included);
+
+		let submitted: std::result::Result<Hash, _> = client
+			.request("author_submitExtrinsic", rpc_params![Bytes(extrinsic.to_vec())])
+			.await;
+		if let Err(e) = submitted {
+			self.forget(hash);
+			return Err(e.to_string())
+		}
+		inclusion.await.map_err(|_| "block subscription closed".into())
+	}
+
+	/// Stops waiting for the extrinsic with `hash`.
+	fn forget(&self, hash: Hash) {
+		self.pending().remove(&hash);
+	}
+
+	fn pending(&self) -> MutexGuard<'_, Pending> {
+		self.pending.lock().expect("the lock is never poisoned; qed")
+	}
+}
+
+/// Submits `extrinsic` and waits until it is included in a block, for at most `timeout`.
+///
+/// An extrinsic that is included but fails to dispatch counts as failed.
+async fn watch(
+	client: &WsClient,
+	inclusions: &Inclusions,
+	extrinsic: Vec<u8>,
+	timeout: Duration,
+) -> Outcome {
+	let start = Instant::now();
+	let (block, index) =
+		match tokio::time::timeout(timeout, inclusions.submit(client, &extrinsic)).await {
+			Ok(Ok(inclusion)) => inclusion,
+			Ok(Err(reason)) => return Outcome::Failed(reason),
+			Err(_) => {
+				inclusions.forget(blake2_256(&extrinsic).into());
+				return Outcome::Failed("timed out".into())
+			},
+		};
+	let latency = start.elapsed();
+
+	match dispatch_error(client, block, index).await {
+		Ok(None) => Outcome::Included(latency),
+		Ok(Some(error)) => Outcome::Failed(format!("ExtrinsicFailed: {:?}", error)),
+		Err(reason) => Outcome::Failed(reason),
+	}
+}
+
+/// The error the `index`th extrinsic of `block` failed with, or `None` if it was dispatched
+/// successfully.
+async fn dispatch_error(
+	client: &WsClient,
+	block: Hash,
+	index: u32,
+) -> std::result::Result<Option<DispatchError>, String> {
+	let key = Bytes(frame_system::Events::<Runtime>::hashed_key().to_vec());
+	let events: Option<Bytes> = client
+		.request("state_getStorage", rpc_params![key, block])
+		.await
+		.map_err(|e| e.to_string())?;
+	let events = match events {
+		Some(events) => Vec::<EventRecord<RuntimeEvent, Hash>>::decode(&mut &events[..])
+			.map_err(|e| format!("decoding events: {}", e))?,
+		None => Vec::new(),
+	};
+
+	Ok(events.into_iter().find_map(|record| match record.event {
+		RuntimeEvent::System(frame_system::Event::ExtrinsicFailed { dispatch_error, .. })
+			if record.phase == Phase::ApplyExtrinsic(index) =>
+			Some(dispatch_error),
+		_ => None,
+	}))
+}
+
+fn report(stats: BTreeMap<CallKind, Stats>) {
+	for (kind, mut stats) in stats {
+		stats.latencies.sort();
+		let included = stats.latencies.len();
+		let failed: u32 = stats.failures.values().sum();
+		println!("{:?}: {} included, {} failed", kind, included, failed);
+
+		if included > 0 {
+			let percentile = |p: usize| stats.latencies[(included - 1) * p / 100];
+			let average = stats.latencies.iter().sum::<Duration>() / included as u32;
+			println!(
+				"  latency: avg {:?}, p50 {:?}, p95 {:?}, max {:?}",
+				average,
+				percentile(50),
+				percentile(95),
+				percentile(100)
+			);
+		}
+		for (reason, count) in &stats.failures {
+			println!("  {} x {}", count, reason);
+		}
+	}
+}
+
+async fn signing_context(client: &WsClient) -> Result<SigningContext> {
+	let genesis_hash: Option<Hash> = client
+		.request("chain_getBlockHash", rpc_params![0u32])
+		.await
+		.map_err(rpc_error)?;
+	let header: Option<Header> =
+		client.request("chain_getHeader", rpc_params![]).await.map_err(rpc_error)?;
+	let header = header.ok_or("The node returned no best block")?;
+	let version: serde_json::Value = client
+		.request("state_getRuntimeVersion", rpc_params![])
+		.await
+		.map_err(rpc_error)?;
+	let version = |field: &str| {
+		version[field]
+			.as_u64()
+			.map(|v| v as u32)
+			.ok_or_else(|| format!("The runtime version has no {}", field))
+	};
+
+	Ok(SigningContext {
+		genesis_hash: genesis_hash.ok_or("The node returned no genesis hash")?,
+		best_hash: header.hash(),
+		best_block: header.number,
+		spec_version: version("specVersion")?,
+		transaction_version: version("transactionVersion")?,
+	})
+}
+
+async fn next_nonce(client: &WsClient, account: &sr25519::Pair) -> Result<u32> {
+	let account = AccountId::from(account.public()).to_ss58check();
+	client
+		.request("system_accountNextIndex", rpc_params![account])
+		.await
+		.map_err(rpc_error)
+}
+
+fn pair(suri: &str) -> Result<sr25519::Pair> {
+	sr25519::Pair::from_string(suri, None)
+		.map_err(|e| format!("Invalid secret URI `{}`: {:?}", suri, e).into())
+}
+
+fn rpc_error(e: jsonrpsee::core::Error) -> Error {
+	Error::Application(e.into())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn mixes_are_parsed() {
+		let mix: Mix = "kitties:40,poe:0,transfer:20".parse().unwrap();
+		assert_eq!(
+			mix.0,
+			vec![(CallKind::Kitties, 40), (CallKind::Poe, 0), (CallKind::Transfer, 20)]
+		);
+		assert_eq!("poe:1".parse::<Mix>().unwrap().0, vec![(CallKind::Poe, 1)]);
+
+		assert!("kitties".parse::<Mix>().is_err());
+		assert!("kitties:-1".parse::<Mix>().is_err());
+		assert!("kitties:many".parse::<Mix>().is_err());
+		assert!("dogs:1".parse::<Mix>().is_err());
+		assert!("kitties:1,".parse::<Mix>().is_err());
+		assert!("kitties:0,poe:0".parse::<Mix>().is_err());
+	}
+
+	#[test]
+	fn every_window_follows_the_mix() {
+		let mix: Mix = "kitties:2,poe:0,transfer:1".parse().unwrap();
+		let kinds = (0..6).map(|index| mix.kind(index)).collect::<Vec<_>>();
+		assert_eq!(
+			kinds,
+			vec![
+				CallKind::Kitties,
+				CallKind::Kitties,
+				CallKind::Transfer,
+				CallKind::Kitties,
+				CallKind::Kitties,
+				CallKind::Transfer,
+			]
+		);
+		assert_eq!(mix.kind(u64::MAX), mix.kind(u64::MAX % 3));
+	}
+}
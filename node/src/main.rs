@@ -1,12 +1,15 @@
 //! Substrate Node Template CLI library.
 #![warn(missing_docs)]
 
+mod app_state;
 mod chain_spec;
 #[macro_use]
 mod service;
 mod benchmarking;
 mod cli;
 mod command;
+mod indexer;
+mod load_test;
 mod rpc;
 
 fn main() -> sc_cli::Result<()> {
@@ -7,46 +7,64 @@
 
 use std::sync::Arc;
 
-use jsonrpsee::RpcModule;
-use node_template_runtime::{opaque::Block, AccountId, Balance, Index};
+use codec::Decode;
+use frame_support::storage::StoragePrefixedMap;
+use jsonrpsee::{
+	core::{Error as JsonRpseeError, RpcResult},
+	proc_macros::rpc,
+	types::error::{CallError, ErrorObject},
+	RpcModule,
+};
+use node_template_runtime::{opaque::Block, AccountId, Balance, Index, Runtime};
+use sc_client_api::{Backend, StorageProvider};
 use sc_transaction_pool_api::TransactionPool;
+use serde::{Deserialize, Serialize};
 use sp_api::ProvideRuntimeApi;
 use sp_block_builder::BlockBuilder;
 use sp_blockchain::{Error as BlockChainError, HeaderBackend, HeaderMetadata};
+use sp_core::{offchain::OffchainStorage, storage::StorageKey, Bytes};
 
 pub use sc_rpc_api::DenyUnsafe;
 
 /// Full client dependencies.
-pub struct FullDeps<C, P> {
+pub struct FullDeps<C, P, B> {
 	/// The client instance to use.
 	pub client: Arc<C>,
 	/// Transaction pool instance.
 	pub pool: Arc<P>,
+	/// The backend the client reads from.
+	pub backend: Arc<B>,
+	/// Whether the node runs with `--enable-offchain-indexing true`.
+	pub offchain_indexing: bool,
 	/// Whether to deny unsafe calls
 	pub deny_unsafe: DenyUnsafe,
 }
 
 /// Instantiate all full RPC extensions.
-pub fn create_full<C, P>(
-	deps: FullDeps<C, P>,
+pub fn create_full<C, P, B>(
+	deps: FullDeps<C, P, B>,
 ) -> Result<RpcModule<()>, Box<dyn std::error::Error + Send + Sync>>
 where
 	C: ProvideRuntimeApi<Block>,
 	C: HeaderBackend<Block> + HeaderMetadata<Block, Error = BlockChainError> + 'static,
+	C: StorageProvider<Block, B>,
 	C: Send + Sync + 'static,
 	C::Api: substrate_frame_rpc_system::AccountNonceApi<Block, AccountId, Index>,
 	C::Api: pallet_transaction_payment_rpc::TransactionPaymentRuntimeApi<Block, Balance>,
 	C::Api: BlockBuilder<Block>,
 	P: TransactionPool + 'static,
+	B: Backend<Block> + Send + Sync + 'static,
 {
 	use pallet_transaction_payment_rpc::{TransactionPayment, TransactionPaymentApiServer};
 	use substrate_frame_rpc_system::{System, SystemApiServer};
 
 	let mut module = RpcModule::new(());
-	let FullDeps { client, pool, deny_unsafe } = deps;
+	let FullDeps { client, pool, backend, offchain_indexing, deny_unsafe } = deps;
 
 	module.merge(System::new(client.clone(), pool.clone(), deny_unsafe).into_rpc())?;
-	module.merge(TransactionPayment::new(client).into_rpc())?;
+	module.merge(TransactionPayment::new(client.clone()).into_rpc())?;
+	let offchain_storage = backend.offchain_storage().filter(|_| offchain_indexing);
+	module.merge(Poe::<C, B>::new(client, offchain_storage, deny_unsafe).into_rpc())?;
 
 	// Extend this RPC with a custom API by using the following syntax.
 	// `YourRpcStruct` should have a reference to a client, which is needed
@@ -55,3 +73,109 @@ where
 
 	Ok(module)
 }
+
+/// Metadata of a PoE claim, see `pallet_poe::ClaimMetadata`.
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClaimMetadata {
+	/// Name of the claimed document.
+	pub filename: String,
+	/// MIME type of the claimed document.
+	pub mime_type: String,
+}
+
+/// PoE claim metadata, served from the offchain database.
+#[rpc(server)]
+pub trait PoeApi {
+	/// The metadata `claim` was created with, if any.
+	#[method(name = "poe_claimMetadata")]
+	fn claim_metadata(&self, claim: Bytes) -> RpcResult<Option<ClaimMetadata>>;
+
+	/// Claims whose metadata matches every given filter.
+	///
+	/// Reads every claim, so it is only served to trusted clients, see `--rpc-methods`.
+	#[method(name = "poe_searchClaims")]
+	fn search_claims(
+		&self,
+		filename: Option<String>,
+		mime_type: Option<String>,
+	) -> RpcResult<Vec<Bytes>>;
+}
+
+/// Implements [`PoeApiServer`].
+pub struct Poe<C, B: Backend<Block>> {
+	client: Arc<C>,
+	/// `None` unless offchain indexing is enabled.
+	offchain_storage: Option<B::OffchainStorage>,
+	deny_unsafe: DenyUnsafe,
+}
+
+impl<C, B: Backend<Block>> Poe<C, B> {
+	/// Creates a new instance of the PoE RPC handler.
+	pub fn new(
+		client: Arc<C>,
+		offchain_storage: Option<B::OffchainStorage>,
+		deny_unsafe: DenyUnsafe,
+	) -> Self {
+		Self { client, offchain_storage, deny_unsafe }
+	}
+
+	fn metadata(&self, claim: &[u8]) -> RpcResult<Option<ClaimMetadata>> {
+		let storage = self.offchain_storage.as_ref().ok_or_else(|| {
+			rpc_error("Offchain indexing is disabled, run the node with `--enable-offchain-indexing true`")
+		})?;
+		let key = pallet_poe::metadata_key(claim);
+		let Some(encoded) = storage.get(sp_offchain::STORAGE_PREFIX, &key) else { return Ok(None) };
+
+		type RuntimeClaimMetadata =
+			pallet_poe::ClaimMetadata<<Runtime as pallet_poe::Config>::MaxMetadataLength>;
+		let metadata = RuntimeClaimMetadata::decode(&mut &encoded[..])
+			.map_err(|e| rpc_error(format!("Invalid claim metadata: {}", e)))?;
+		Ok(Some(ClaimMetadata {
+			filename: String::from_utf8_lossy(&metadata.filename).into_owned(),
+			mime_type: String::from_utf8_lossy(&metadata.mime_type).into_owned(),
+		}))
+	}
+}
+
+impl<C, B> PoeApiServer for Poe<C, B>
+where
+	C: HeaderBackend<Block> + StorageProvider<Block, B> + Send + Sync + 'static,
+	B: Backend<Block> + Send + Sync + 'static,
+{
+	fn claim_metadata(&self, claim: Bytes) -> RpcResult<Option<ClaimMetadata>> {
+		self.metadata(&claim)
+	}
+
+	fn search_claims(
+		&self,
+		filename: Option<String>,
+		mime_type: Option<String>,
+	) -> RpcResult<Vec<Bytes>> {
+		self.deny_unsafe.check_if_safe()?;
+
+		let best_hash = self.client.info().best_hash;
+		let prefix = StorageKey(pallet_poe::Proofs::<Runtime>::final_prefix().to_vec());
+		let keys = self
+			.client
+			.storage_keys(best_hash, Some(&prefix), None)
+			.map_err(|e| rpc_error(e.to_string()))?;
+
+		let mut claims = Vec::new();
+		for key in keys {
+			// `Proofs` is a `Blake2_128Concat` map, so its keys end with the encoded claim.
+			let Ok(claim) = Vec::<u8>::decode(&mut &key.0[prefix.0.len() + 16..]) else { continue };
+			let Some(metadata) = self.metadata(&claim)? else { continue };
+			if filename.as_ref().map_or(true, |f| *f == metadata.filename) &&
+				mime_type.as_ref().map_or(true, |m| *m == metadata.mime_type)
+			{
+				claims.push(claim.into());
+			}
+		}
+		Ok(claims)
+	}
+}
+
+fn rpc_error(message: impl Into<String>) -> JsonRpseeError {
+	CallError::Custom(ErrorObject::owned(1, message.into(), None::<()>)).into()
+}
@@ -1,14 +1,16 @@
 //! Service and ServiceFactory implementation. Specialized wrapper over substrate service.
 
 use node_template_runtime::{self, opaque::Block, RuntimeApi};
-use sc_client_api::BlockBackend;
+use sc_client_api::{Backend, BlockBackend};
 use sc_consensus_aura::{ImportQueueParams, SlotProportion, StartAuraParams};
 use sc_consensus_grandpa::SharedVoterState;
 pub use sc_executor::NativeElseWasmExecutor;
 use sc_service::{error::Error as ServiceError, Configuration, TaskManager, WarpSyncParams};
 use sc_telemetry::{Telemetry, TelemetryWorker};
 use sp_consensus_aura::sr25519::AuthorityPair as AuraPair;
-use std::{sync::Arc, time::Duration};
+use sp_core::offchain::OffchainStorage;
+use sp_keystore::Keystore;
+use std::{path::PathBuf, sync::Arc, time::Duration};
 
 // Our native executor instance.
 pub struct ExecutorDispatch;
@@ -138,7 +140,20 @@ pub fn new_partial(
 }
 
 /// Builds a new service for a full client.
-pub fn new_full(mut config: Configuration) -> Result<TaskManager, ServiceError> {
+///
+/// `poe_tsa_endpoint` is the timestamp authority the PoE offchain worker sends claims to, and
+/// `index_events` the SQLite database the event indexer writes to, if any.
+pub fn new_full(
+	mut config: Configuration,
+	poe_tsa_endpoint: Option<String>,
+	index_events: Option<PathBuf>,
+) -> Result<TaskManager, ServiceError> {
+	// PoE claim metadata is only kept by nodes indexing offchain, which dev chains do by default.
+	if config.chain_spec.id() == "dev" {
+		config.offchain_worker.indexing_enabled = true;
+	}
+	let offchain_indexing = config.offchain_worker.indexing_enabled;
+
 	let sc_service::PartialComponents {
 		client,
 		backend,
@@ -183,6 +198,35 @@ pub fn new_full(mut config: Configuration) -> Result<TaskManager, ServiceError>
 			client.clone(),
 			network.clone(),
 		);
+
+		if let Some(endpoint) = poe_tsa_endpoint {
+			let mut storage = backend
+				.offchain_storage()
+				.ok_or_else(|| ServiceError::Other("Offchain storage is not available".into()))?;
+			storage.set(
+				sp_offchain::STORAGE_PREFIX,
+				pallet_poe::TSA_ENDPOINT_KEY,
+				endpoint.as_bytes(),
+			);
+		}
+		// Dev chains submit PoE attestations with Alice's account.
+		if config.chain_spec.id() == "dev" {
+			keystore_container
+				.keystore()
+				.sr25519_generate_new(pallet_poe::KEY_TYPE, Some("//Alice"))
+				.map_err(|e| ServiceError::Other(e.to_string()))?;
+		}
+	}
+
+	if let Some(path) = index_events {
+		let db = crate::indexer::Database::open(&path).map_err(|e| {
+			ServiceError::Other(format!("Failed to open {}: {}", path.display(), e))
+		})?;
+		task_manager.spawn_handle().spawn_blocking(
+			"event-indexer",
+			None,
+			crate::indexer::run(client.clone(), db),
+		);
 	}
 
 	let role = config.role.clone();
@@ -195,10 +239,16 @@ pub fn new_full(mut config: Configuration) -> Result<TaskManager, ServiceError>
 	let rpc_extensions_builder = {
 		let client = client.clone();
 		let pool = transaction_pool.clone();
+		let backend = backend.clone();
 
 		Box::new(move |deny_unsafe, _| {
-			let deps =
-				crate::rpc::FullDeps { client: client.clone(), pool: pool.clone(), deny_unsafe };
+			let deps = crate::rpc::FullDeps {
+				client: client.clone(),
+				pool: pool.clone(),
+				backend: backend.clone(),
+				offchain_indexing,
+				deny_unsafe,
+			};
 			crate::rpc::create_full(deps).map_err(Into::into)
 		})
 	};
@@ -0,0 +1,213 @@
+//! Helpers to run a local testnet of `node-template` processes and talk to them over RPC.
+
+use codec::Encode;
+use futures::{Future, StreamExt};
+use jsonrpsee::{
+	core::client::{ClientT, SubscriptionClientT},
+	rpc_params,
+	ws_client::{WsClient, WsClientBuilder},
+};
+use nix::{
+	sys::signal::{kill, Signal},
+	unistd::Pid,
+};
+use node_template::benchmarking::SigningContext;
+use node_template_runtime::{opaque::Header, BlockNumber, Hash, RuntimeCall};
+use sc_transaction_pool_api::TransactionStatus;
+use sp_core::{
+	crypto::Ss58Codec,
+	storage::{StorageData, StorageKey},
+	Bytes,
+};
+use sp_keyring::Sr25519Keyring;
+use sp_runtime::traits::Header as _;
+use std::{
+	fs::File,
+	process::{Child, Command, Stdio},
+	time::Duration,
+};
+use tempfile::TempDir;
+
+/// Node key of the first node of a network, so that the others can use it as their bootnode.
+const BOOTNODE_KEY: &str = "0000000000000000000000000000000000000000000000000000000000000001";
+/// The peer id belonging to `BOOTNODE_KEY`.
+const BOOTNODE_PEER_ID: &str = "12D3KooWEyoppNCUx8Yx66oV9fJnriXwCcXwDDUA2kj6vnc6iDEp";
+
+/// A `node-template --chain local` process, which is stopped when dropped.
+pub struct Node {
+	child: Option<Child>,
+	args: Vec<String>,
+	base_path: TempDir,
+	rpc_port: u16,
+}
+
+impl Node {
+	/// Starts the `index`th node of a network whose p2p ports start at `port`, and whose RPC
+	/// ports start at `port + 1000`.
+	///
+	/// Networks that run at the same time need `port`s at least 100 apart, so that neither their
+	/// p2p nor their RPC ports overlap.
+	///
+	/// `key` names the dev account the node authors blocks with, e.g. `alice`, or is `None` for a
+	/// full node.
+	pub fn start(index: u16, port: u16, key: Option<&str>) -> Self {
+		let rpc_port = port + 1000 + index;
+		let mut args = vec![
+			"--chain=local".to_string(),
+			format!("--port={}", port + index),
+			format!("--rpc-port={}", rpc_port),
+			"--no-mdns".into(),
+			"--no-prometheus".into(),
+			"--no-telemetry".into(),
+		];
+		match key {
+			Some(key) => args.push(format!("--{}", key)),
+			None => args.push(format!("--name=node-{}", index)),
+		}
+		if index == 0 {
+			args.push(format!("--node-key={}", BOOTNODE_KEY));
+		} else {
+			args.push(format!("--bootnodes=/ip4/127.0.0.1/tcp/{}/p2p/{}", port, BOOTNODE_PEER_ID));
+		}
+
+		let base_path = tempfile::tempdir().expect("Creates a temporary directory");
+		let mut node = Self { child: None, args, base_path, rpc_port };
+		node.spawn();
+		node
+	}
+
+	fn spawn(&mut self) {
+		let log = File::options()
+			.create(true)
+			.append(true)
+			.open(self.base_path.path().join("node.log"))
+			.expect("Opens the node's log file");
+		let child = Command::new(env!("CARGO_BIN_EXE_node-template"))
+			.args(&self.args)
+			.arg(format!("--base-path={}", self.base_path.path().display()))
+			.stdout(Stdio::null())
+			.stderr(log)
+			.spawn()
+			.expect("Starts the node");
+		self.child = Some(child);
+	}
+
+	/// Stops the node gracefully, keeping its database.
+	pub fn stop(&mut self) {
+		if let Some(mut child) = self.child.take() {
+			let _ = kill(Pid::from_raw(child.id() as i32), Signal::SIGINT);
+			let _ = child.wait();
+		}
+	}
+
+	/// Starts the node again from the database it left behind.
+	pub fn restart(&mut self) {
+		self.stop();
+		self.spawn();
+	}
+
+	/// Connects to the node's RPC server, waiting for it to come up.
+	pub async fn client(&self) -> WsClient {
+		let url = format!("ws://127.0.0.1:{}", self.rpc_port);
+		for _ in 0..60 {
+			if let Ok(client) = WsClientBuilder::default().build(&url).await {
+				return client
+			}
+			tokio::time::sleep(Duration::from_secs(1)).await;
+		}
+		panic!("The RPC server at {} did not come up", url)
+	}
+}
+
+impl Drop for Node {
+	fn drop(&mut self) {
+		self.stop();
+	}
+}
+
+/// Polls `condition` every second until it holds, failing the test after `timeout` seconds.
+pub async fn wait_until<F, Fut>(what: &str, timeout: u64, mut condition: F)
+where
+	F: FnMut() -> Fut,
+	Fut: Future<Output = bool>,
+{
+	for _ in 0..timeout {
+		if condition().await {
+			return
+		}
+		tokio::time::sleep(Duration::from_secs(1)).await;
+	}
+	panic!("Timed out waiting until {}", what)
+}
+
+pub async fn best_header(client: &WsClient) -> Header {
+	let header: Option<Header> = client.request("chain_getHeader", rpc_params![]).await.unwrap();
+	header.expect("The best block has a header")
+}
+
+pub async fn header(client: &WsClient, number: BlockNumber) -> Header {
+	let hash: Option<Hash> =
+		client.request("chain_getBlockHash", rpc_params![number]).await.unwrap();
+	let header: Option<Header> = client
+		.request("chain_getHeader", rpc_params![hash.expect("The block exists")])
+		.await
+		.unwrap();
+	header.expect("The block has a header")
+}
+
+pub async fn best_number(client: &WsClient) -> BlockNumber {
+	best_header(client).await.number
+}
+
+pub async fn finalized_number(client: &WsClient) -> BlockNumber {
+	let hash: Hash = client.request("chain_getFinalizedHead", rpc_params![]).await.unwrap();
+	let header: Option<Header> =
+		client.request("chain_getHeader", rpc_params![hash]).await.unwrap();
+	header.expect("The finalized block has a header").number
+}
+
+pub async fn storage(client: &WsClient, key: Vec<u8>) -> Option<Vec<u8>> {
+	let data: Option<StorageData> =
+		client.request("state_getStorage", rpc_params![StorageKey(key)]).await.unwrap();
+	data.map(|data| data.0)
+}
+
+/// Signs `call` as `signer` and waits until it is included in a block.
+pub async fn submit(client: &WsClient, signer: Sr25519Keyring, call: RuntimeCall) {
+	let genesis_hash: Option<Hash> =
+		client.request("chain_getBlockHash", rpc_params![0u32]).await.unwrap();
+	let best = best_header(client).await;
+	let version: serde_json::Value =
+		client.request("state_getRuntimeVersion", rpc_params![]).await.unwrap();
+	let context = SigningContext {
+		genesis_hash: genesis_hash.expect("The genesis block exists"),
+		best_hash: best.hash(),
+		best_block: best.number,
+		spec_version: version["specVersion"].as_u64().unwrap() as u32,
+		transaction_version: version["transactionVersion"].as_u64().unwrap() as u32,
+	};
+	let nonce: u32 = client
+		.request("system_accountNextIndex", rpc_params![signer.to_account_id().to_ss58check()])
+		.await
+		.unwrap();
+	let extrinsic = context.sign(&signer.pair(), call, nonce);
+
+	let mut subscription = client
+		.subscribe::<TransactionStatus<Hash, Hash>, _>(
+			"author_submitAndWatchExtrinsic",
+			rpc_params![Bytes(extrinsic.encode())],
+			"author_unwatchExtrinsic",
+		)
+		.await
+		.unwrap();
+	while let Some(status) = subscription.next().await {
+		match status.unwrap() {
+			TransactionStatus::InBlock(_) => return,
+			TransactionStatus::Future |
+			TransactionStatus::Ready |
+			TransactionStatus::Broadcast(_) => continue,
+			status => panic!("The transaction was not included: {:?}", status),
+		}
+	}
+	panic!("The transaction subscription closed")
+}
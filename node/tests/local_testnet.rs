@@ -0,0 +1,87 @@
+//! Runs `node-template --chain local` networks on loopback and checks them end to end.
+//!
+//! The tests spawn node processes and take minutes, so they are ignored by default. Run them
+//! with `cargo test -p node-template --test local_testnet -- --ignored`.
+
+mod common;
+
+use codec::{Decode, Encode};
+use common::*;
+use frame_support::storage::{StorageMap, StorageValue};
+use node_template_runtime::{KittiesCall, Runtime};
+use sp_consensus_aura::{Slot, AURA_ENGINE_ID};
+use sp_keyring::Sr25519Keyring::{Alice, Bob};
+use sp_runtime::DigestItem;
+use std::collections::BTreeSet;
+
+#[tokio::test]
+#[ignore]
+async fn validators_author_and_finalize_blocks() {
+	let alice = Node::start(0, 30500, Some("alice"));
+	let _bob = Node::start(1, 30500, Some("bob"));
+	let client = &alice.client().await;
+
+	// GRANDPA needs both validators' votes to finalize anything.
+	wait_until("blocks are finalized", 120, || async move { finalized_number(client).await >= 3 })
+		.await;
+
+	// Aura assigns slot `n` to the authority `n % 2`, Alice being the first one.
+	let mut authors = BTreeSet::new();
+	for number in 1..=best_number(client).await {
+		let slot = header(client, number)
+			.await
+			.digest
+			.logs
+			.iter()
+			.find_map(|log| match log {
+				DigestItem::PreRuntime(AURA_ENGINE_ID, data) => Slot::decode(&mut &data[..]).ok(),
+				_ => None,
+			})
+			.expect("Every block has an Aura pre-digest");
+		authors.insert(*slot % 2);
+	}
+	assert_eq!(authors.len(), 2, "Not every validator authored a block");
+}
+
+#[tokio::test]
+#[ignore]
+async fn transfers_propagate_and_restarted_nodes_catch_up() {
+	let alice = Node::start(0, 30600, Some("alice"));
+	let bob = Node::start(1, 30600, Some("bob"));
+	let mut full_node = Node::start(2, 30600, None);
+	let alice_client = &alice.client().await;
+	let bob_client = &bob.client().await;
+	wait_until("the chain starts", 60, || async move { best_number(alice_client).await >= 1 })
+		.await;
+
+	// A kitty sent to Bob through Alice's node shows up on Bob's node.
+	let kitty_id =
+		storage(alice_client, pallet_kitties::NextKittyId::<Runtime>::hashed_key().to_vec())
+			.await
+			.map_or(0, |data| u32::decode(&mut &data[..]).unwrap());
+	submit(alice_client, Alice, KittiesCall::create {}.into()).await;
+	submit(alice_client, Alice, KittiesCall::transfer { to: Bob.to_account_id(), kitty_id }.into())
+		.await;
+	let owner_key = pallet_kitties::KittyOwner::<Runtime>::hashed_key_for(kitty_id);
+	let bob_account = Bob.to_account_id().encode();
+	wait_until("Bob's node sees the transfer", 60, || {
+		let (owner_key, bob_account) = (owner_key.clone(), bob_account.clone());
+		async move { storage(bob_client, owner_key).await == Some(bob_account) }
+	})
+	.await;
+
+	// A node restarted from its database catches up with the blocks it missed.
+	full_node.stop();
+	let stopped_at = best_number(alice_client).await;
+	wait_until("the chain grows", 60, || async move {
+		best_number(alice_client).await >= stopped_at + 3
+	})
+	.await;
+	full_node.restart();
+	let full_node_client = &full_node.client().await;
+	let target = best_number(alice_client).await;
+	wait_until("the restarted node catches up", 120, || async move {
+		best_number(full_node_client).await >= target
+	})
+	.await;
+}
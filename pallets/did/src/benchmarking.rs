@@ -0,0 +1,191 @@
+//! Benchmarking setup for pallet-did
+#![cfg(feature = "runtime-benchmarks")]
+use super::*;
+
+#[allow(unused)]
+use crate::Pallet as Did;
+use frame_benchmarking::v2::*;
+use frame_system::RawOrigin;
+use sp_core::{ecdsa, ed25519};
+use sp_runtime::MultiSigner;
+use sp_std::{vec, vec::Vec};
+
+/// An id of the maximum length, distinct for each `i`.
+fn id<T: Config>(i: u8) -> IdOf<T> {
+	BoundedVec::truncate_from(vec![i; T::MaxIdLength::get() as usize])
+}
+
+/// ECDSA keys are the largest a document holds.
+fn public(i: u8) -> MultiSigner {
+	MultiSigner::Ecdsa(ecdsa::Public::from_raw([i; 33]))
+}
+
+/// Creates the DID of `did` with all but one of the controllers, keys and services it can
+/// have, so that there is room to add one more of each. Key `id(0)` is always there.
+fn create_full_did<T: Config>(did: &T::AccountId) {
+	let mut controllers = vec![did.clone()];
+	let others = 1..T::MaxControllers::get().saturating_sub(1);
+	controllers.extend(others.map(|i| account("controller", i, 0)));
+	let keys = (0..T::MaxKeys::get().saturating_sub(1).max(1) as u8)
+		.map(|i| VerificationKey { id: id::<T>(i), public: public(i) })
+		.collect::<Vec<_>>();
+	let services = (0..T::MaxServices::get().saturating_sub(1) as u8)
+		.map(|i| ServiceEndpoint {
+			id: id::<T>(i),
+			url: BoundedVec::truncate_from(vec![0; T::MaxUrlLength::get() as usize]),
+		})
+		.collect::<Vec<_>>();
+	Dids::<T>::insert(
+		did,
+		DidDocument {
+			controllers: BoundedVec::truncate_from(controllers),
+			keys: BoundedVec::truncate_from(keys),
+			services: BoundedVec::truncate_from(services),
+		},
+	);
+}
+
+/// Makes `do_create_claim` queue the anchored claims for attestation too.
+fn add_tsa_key<T: Config>() {
+	pallet_poe::TsaKeys::<T>::put(BoundedVec::truncate_from(vec![ed25519::Public::from_raw(
+		[0; 32],
+	)]));
+}
+
+#[benchmarks]
+mod benchmarks {
+	use super::*;
+
+	#[benchmark]
+	fn create_did() {
+		let caller: T::AccountId = whitelisted_caller();
+		#[extrinsic_call]
+		create_did(RawOrigin::Signed(caller.clone()));
+
+		assert!(Dids::<T>::contains_key(&caller));
+	}
+
+	#[benchmark]
+	fn remove_did() {
+		let caller: T::AccountId = whitelisted_caller();
+		create_full_did::<T>(&caller);
+		#[extrinsic_call]
+		remove_did(RawOrigin::Signed(caller.clone()), caller.clone());
+
+		assert!(!Dids::<T>::contains_key(&caller));
+	}
+
+	#[benchmark]
+	fn add_controller() {
+		let caller: T::AccountId = whitelisted_caller();
+		create_full_did::<T>(&caller);
+		let controller: T::AccountId = account("new", 0, 0);
+		#[extrinsic_call]
+		add_controller(RawOrigin::Signed(caller.clone()), caller.clone(), controller.clone());
+
+		assert!(Dids::<T>::get(&caller).unwrap().controllers.contains(&controller));
+	}
+
+	#[benchmark]
+	fn remove_controller() {
+		let caller: T::AccountId = whitelisted_caller();
+		create_full_did::<T>(&caller);
+		let controller: T::AccountId = account("new", 0, 0);
+		Dids::<T>::mutate(&caller, |document| {
+			document.as_mut().unwrap().controllers.try_push(controller.clone()).unwrap()
+		});
+		#[extrinsic_call]
+		remove_controller(RawOrigin::Signed(caller.clone()), caller.clone(), controller.clone());
+
+		assert!(!Dids::<T>::get(&caller).unwrap().controllers.contains(&controller));
+	}
+
+	#[benchmark]
+	fn add_key() {
+		let caller: T::AccountId = whitelisted_caller();
+		create_full_did::<T>(&caller);
+		let key_id = id::<T>(u8::MAX);
+		#[extrinsic_call]
+		add_key(RawOrigin::Signed(caller.clone()), caller.clone(), key_id.clone(), public(0));
+
+		assert!(Dids::<T>::get(&caller).unwrap().keys.iter().any(|key| key.id == key_id));
+	}
+
+	#[benchmark]
+	fn remove_key() {
+		let caller: T::AccountId = whitelisted_caller();
+		create_full_did::<T>(&caller);
+		let key_id = id::<T>(u8::MAX);
+		Dids::<T>::mutate(&caller, |document| {
+			let key = VerificationKey { id: key_id.clone(), public: public(0) };
+			document.as_mut().unwrap().keys.try_push(key).unwrap()
+		});
+		#[extrinsic_call]
+		remove_key(RawOrigin::Signed(caller.clone()), caller.clone(), key_id.clone());
+
+		assert!(!Dids::<T>::get(&caller).unwrap().keys.iter().any(|key| key.id == key_id));
+	}
+
+	#[benchmark]
+	fn add_service() {
+		let caller: T::AccountId = whitelisted_caller();
+		create_full_did::<T>(&caller);
+		let service_id = id::<T>(u8::MAX);
+		let url = BoundedVec::truncate_from(vec![0; T::MaxUrlLength::get() as usize]);
+		#[extrinsic_call]
+		add_service(RawOrigin::Signed(caller.clone()), caller.clone(), service_id.clone(), url);
+
+		let document = Dids::<T>::get(&caller).unwrap();
+		assert!(document.services.iter().any(|service| service.id == service_id));
+	}
+
+	#[benchmark]
+	fn remove_service() {
+		let caller: T::AccountId = whitelisted_caller();
+		create_full_did::<T>(&caller);
+		let service_id = id::<T>(u8::MAX);
+		Dids::<T>::mutate(&caller, |document| {
+			let url = BoundedVec::truncate_from(vec![0; T::MaxUrlLength::get() as usize]);
+			let service = ServiceEndpoint { id: service_id.clone(), url };
+			document.as_mut().unwrap().services.try_push(service).unwrap()
+		});
+		#[extrinsic_call]
+		remove_service(RawOrigin::Signed(caller.clone()), caller.clone(), service_id.clone());
+
+		let document = Dids::<T>::get(&caller).unwrap();
+		assert!(!document.services.iter().any(|service| service.id == service_id));
+	}
+
+	#[benchmark]
+	fn issue_credential() {
+		let caller: T::AccountId = whitelisted_caller();
+		create_full_did::<T>(&caller);
+		add_tsa_key::<T>();
+		let credential = H256::repeat_byte(1);
+		#[extrinsic_call]
+		issue_credential(RawOrigin::Signed(caller.clone()), caller.clone(), credential, id::<T>(0));
+
+		assert!(Credentials::<T>::contains_key(credential));
+	}
+
+	#[benchmark]
+	fn revoke_credential() {
+		let caller: T::AccountId = whitelisted_caller();
+		create_full_did::<T>(&caller);
+		add_tsa_key::<T>();
+		let credential = H256::repeat_byte(1);
+		Did::<T>::issue_credential(
+			RawOrigin::Signed(caller.clone()).into(),
+			caller.clone(),
+			credential,
+			id::<T>(0),
+		)
+		.unwrap();
+		#[extrinsic_call]
+		revoke_credential(RawOrigin::Signed(caller.clone()), credential);
+
+		assert!(Credentials::<T>::get(credential).unwrap().revoked_at.is_some());
+	}
+
+	impl_benchmark_test_suite!(Did, crate::mock::new_test_ext(), crate::mock::Test);
+}
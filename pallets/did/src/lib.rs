@@ -0,0 +1,534 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! A registry of DID documents, and verifiable credentials issued by them.
+//!
+//! Every account can create one DID document, identified by the account. The document lists the
+//! controllers allowed to change it, the verification keys its subject signs credentials with,
+//! and the service endpoints it can be reached at.
+//!
+//! Issuing a credential records its hash and the key it is signed with, and anchors
+//! [`issuance_claim`] as a PoE claim owned by the issuer's DID. Revoking it anchors
+//! [`revocation_claim`] as a second PoE claim, so both when a credential was issued and when it
+//! was revoked can be proven. The [`DidApi`] runtime API checks a credential's signature against
+//! its issuer's on-chain key.
+//!
+//! Both claims start with [`ANCHOR_PREFIX`]. The runtime must reserve them by setting the PoE
+//! pallet's `ReservedClaims` to [`AnchoredClaims`], or anybody could claim them first and block
+//! issuing or revoking the credential. The credentials themselves are kept in this pallet's
+//! storage, so the anchors are only evidence and never decide whether a credential is valid.
+
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+pub mod weights;
+pub use weights::*;
+
+use codec::{Decode, Encode};
+use frame_support::{
+	traits::{Contains, Get},
+	BoundedVec,
+};
+use scale_info::TypeInfo;
+use sp_core::H256;
+use sp_runtime::{MultiSignature, RuntimeDebug};
+
+/// The prefix of the PoE claims credentials are anchored as.
+pub const ANCHOR_PREFIX: &[u8; 4] = b"did:";
+
+/// The PoE claim anchoring the issuance of `credential`.
+pub fn issuance_claim(credential: &H256) -> H256 {
+	anchored_claim(b"did::issued", credential)
+}
+
+/// The PoE claim anchoring the revocation of `credential`.
+pub fn revocation_claim(credential: &H256) -> H256 {
+	anchored_claim(b"did::revoked", credential)
+}
+
+/// [`ANCHOR_PREFIX`] followed by the first 28 bytes of the hash of `kind` and `credential`, so
+/// that the claim fits in 32 bytes.
+fn anchored_claim(kind: &[u8], credential: &H256) -> H256 {
+	let hash = sp_io::hashing::blake2_256(&(kind, credential).encode());
+	let mut claim = H256::zero();
+	claim[..ANCHOR_PREFIX.len()].copy_from_slice(ANCHOR_PREFIX);
+	claim[ANCHOR_PREFIX.len()..].copy_from_slice(&hash[..32 - ANCHOR_PREFIX.len()]);
+	claim
+}
+
+/// The PoE claims this pallet anchors credentials as, for the PoE pallet's `ReservedClaims`.
+pub struct AnchoredClaims;
+
+impl<S: Get<u32>> Contains<BoundedVec<u8, S>> for AnchoredClaims {
+	fn contains(claim: &BoundedVec<u8, S>) -> bool {
+		claim.starts_with(ANCHOR_PREFIX)
+	}
+}
+
+/// The result of verifying a credential.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub enum CredentialStatus<AccountId, BlockNumber> {
+	/// The credential was issued by `issuer` at `issued_at`, is signed with the issuer's key and
+	/// is not revoked.
+	Valid { issuer: AccountId, issued_at: BlockNumber },
+	/// The credential was revoked by its issuer at `revoked_at`.
+	Revoked { issuer: AccountId, revoked_at: BlockNumber },
+	/// The credential was never issued.
+	Unknown,
+	/// The key the credential was issued with is no longer in the issuer's DID document.
+	KeyRemoved,
+	/// The signature doesn't match the issuer's key.
+	BadSignature,
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+	use sp_core::ecdsa;
+	use sp_runtime::{
+		traits::{IdentifyAccount, Verify},
+		MultiSigner,
+	};
+	use sp_std::vec;
+
+	/// The id of a verification key or service endpoint within a DID document.
+	pub type IdOf<T> = BoundedVec<u8, <T as Config>::MaxIdLength>;
+
+	/// A key the DID subject signs credentials with.
+	#[derive(
+		Encode, Decode, CloneNoBound, PartialEqNoBound, EqNoBound, RuntimeDebugNoBound, TypeInfo,
+	)]
+	#[scale_info(skip_type_params(T))]
+	pub struct VerificationKey<T: Config> {
+		pub id: IdOf<T>,
+		pub public: MultiSigner,
+	}
+
+	// `MultiSigner` doesn't implement `MaxEncodedLen`. Its largest variant is an ECDSA key.
+	impl<T: Config> MaxEncodedLen for VerificationKey<T> {
+		fn max_encoded_len() -> usize {
+			IdOf::<T>::max_encoded_len()
+				.saturating_add(1)
+				.saturating_add(ecdsa::Public::max_encoded_len())
+		}
+	}
+
+	/// Where the DID subject can be reached, e.g. an HTTPS URL.
+	#[derive(
+		Encode,
+		Decode,
+		CloneNoBound,
+		PartialEqNoBound,
+		EqNoBound,
+		RuntimeDebugNoBound,
+		TypeInfo,
+		MaxEncodedLen,
+	)]
+	#[scale_info(skip_type_params(T))]
+	pub struct ServiceEndpoint<T: Config> {
+		pub id: IdOf<T>,
+		pub url: BoundedVec<u8, T::MaxUrlLength>,
+	}
+
+	#[derive(
+		Encode,
+		Decode,
+		CloneNoBound,
+		PartialEqNoBound,
+		EqNoBound,
+		RuntimeDebugNoBound,
+		TypeInfo,
+		MaxEncodedLen,
+	)]
+	#[scale_info(skip_type_params(T))]
+	pub struct DidDocument<T: Config> {
+		/// Accounts allowed to change the document and issue credentials in its name.
+		pub controllers: BoundedVec<T::AccountId, T::MaxControllers>,
+		pub keys: BoundedVec<VerificationKey<T>, T::MaxKeys>,
+		pub services: BoundedVec<ServiceEndpoint<T>, T::MaxServices>,
+	}
+
+	#[derive(
+		Encode,
+		Decode,
+		CloneNoBound,
+		PartialEqNoBound,
+		EqNoBound,
+		RuntimeDebugNoBound,
+		TypeInfo,
+		MaxEncodedLen,
+	)]
+	#[scale_info(skip_type_params(T))]
+	pub struct Credential<T: Config> {
+		/// The DID that issued the credential.
+		pub issuer: T::AccountId,
+		/// The key of the issuer the credential is signed with.
+		pub key_id: IdOf<T>,
+		pub issued_at: T::BlockNumber,
+		pub revoked_at: Option<T::BlockNumber>,
+	}
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config + pallet_poe::Config {
+		/// Because this pallet emits events, it depends on the runtime's definition of an event.
+		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+		/// Maximum number of controllers of a DID document.
+		#[pallet::constant]
+		type MaxControllers: Get<u32>;
+		/// Maximum number of verification keys of a DID document.
+		#[pallet::constant]
+		type MaxKeys: Get<u32>;
+		/// Maximum number of service endpoints of a DID document.
+		#[pallet::constant]
+		type MaxServices: Get<u32>;
+		/// Maximum length of the id of a key or service endpoint.
+		#[pallet::constant]
+		type MaxIdLength: Get<u32>;
+		/// Maximum length of a service endpoint's URL.
+		#[pallet::constant]
+		type MaxUrlLength: Get<u32>;
+		/// Weight information for extrinsics in this pallet.
+		type WeightInfo: WeightInfo;
+	}
+
+	/// The DID document of each DID, keyed by the account the DID belongs to.
+	#[pallet::storage]
+	#[pallet::getter(fn did)]
+	pub type Dids<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, DidDocument<T>>;
+
+	/// The issued credentials, keyed by their hash.
+	#[pallet::storage]
+	#[pallet::getter(fn credential)]
+	pub type Credentials<T: Config> = StorageMap<_, Blake2_128Concat, H256, Credential<T>>;
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		DidCreated { did: T::AccountId },
+		DidRemoved { did: T::AccountId },
+		ControllerAdded { did: T::AccountId, controller: T::AccountId },
+		ControllerRemoved { did: T::AccountId, controller: T::AccountId },
+		KeyAdded { did: T::AccountId, key_id: IdOf<T> },
+		KeyRemoved { did: T::AccountId, key_id: IdOf<T> },
+		ServiceAdded { did: T::AccountId, service_id: IdOf<T> },
+		ServiceRemoved { did: T::AccountId, service_id: IdOf<T> },
+		CredentialIssued { issuer: T::AccountId, credential: H256 },
+		CredentialRevoked { issuer: T::AccountId, credential: H256 },
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The account already has a DID document.
+		DidAlreadyExists,
+		/// The DID has no document.
+		DidNotFound,
+		/// The caller is not a controller of the DID.
+		NotController,
+		ControllerAlreadyAdded,
+		ControllerNotFound,
+		/// A DID document needs at least one controller.
+		LastController,
+		TooManyControllers,
+		KeyAlreadyAdded,
+		KeyNotFound,
+		TooManyKeys,
+		ServiceAlreadyAdded,
+		ServiceNotFound,
+		TooManyServices,
+		CredentialAlreadyIssued,
+		CredentialNotFound,
+		CredentialAlreadyRevoked,
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Create the DID document of the caller, with the caller as its only controller.
+		#[pallet::call_index(0)]
+		#[pallet::weight(<T as Config>::WeightInfo::create_did())]
+		pub fn create_did(origin: OriginFor<T>) -> DispatchResult {
+			let did = ensure_signed(origin)?;
+			ensure!(!Dids::<T>::contains_key(&did), Error::<T>::DidAlreadyExists);
+
+			let document = DidDocument::<T> {
+				controllers: BoundedVec::truncate_from(vec![did.clone()]),
+				keys: Default::default(),
+				services: Default::default(),
+			};
+			Dids::<T>::insert(&did, document);
+			Self::deposit_event(Event::DidCreated { did });
+			Ok(())
+		}
+
+		/// Remove the document of `did`. Credentials it issued no longer verify afterwards.
+		#[pallet::call_index(1)]
+		#[pallet::weight(<T as Config>::WeightInfo::remove_did())]
+		pub fn remove_did(origin: OriginFor<T>, did: T::AccountId) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::ensure_controller(&did, &who)?;
+
+			Dids::<T>::remove(&did);
+			Self::deposit_event(Event::DidRemoved { did });
+			Ok(())
+		}
+
+		#[pallet::call_index(2)]
+		#[pallet::weight(<T as Config>::WeightInfo::add_controller())]
+		pub fn add_controller(
+			origin: OriginFor<T>,
+			did: T::AccountId,
+			controller: T::AccountId,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::mutate_document(&did, &who, |document| {
+				ensure!(
+					!document.controllers.contains(&controller),
+					Error::<T>::ControllerAlreadyAdded
+				);
+				document
+					.controllers
+					.try_push(controller.clone())
+					.map_err(|_| Error::<T>::TooManyControllers)?;
+				Ok(())
+			})?;
+			Self::deposit_event(Event::ControllerAdded { did, controller });
+			Ok(())
+		}
+
+		#[pallet::call_index(3)]
+		#[pallet::weight(<T as Config>::WeightInfo::remove_controller())]
+		pub fn remove_controller(
+			origin: OriginFor<T>,
+			did: T::AccountId,
+			controller: T::AccountId,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::mutate_document(&did, &who, |document| {
+				let index = document
+					.controllers
+					.iter()
+					.position(|c| *c == controller)
+					.ok_or(Error::<T>::ControllerNotFound)?;
+				ensure!(document.controllers.len() > 1, Error::<T>::LastController);
+				document.controllers.remove(index);
+				Ok(())
+			})?;
+			Self::deposit_event(Event::ControllerRemoved { did, controller });
+			Ok(())
+		}
+
+		#[pallet::call_index(4)]
+		#[pallet::weight(<T as Config>::WeightInfo::add_key())]
+		pub fn add_key(
+			origin: OriginFor<T>,
+			did: T::AccountId,
+			key_id: IdOf<T>,
+			public: MultiSigner,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::mutate_document(&did, &who, |document| {
+				ensure!(
+					!document.keys.iter().any(|key| key.id == key_id),
+					Error::<T>::KeyAlreadyAdded
+				);
+				document
+					.keys
+					.try_push(VerificationKey { id: key_id.clone(), public })
+					.map_err(|_| Error::<T>::TooManyKeys)?;
+				Ok(())
+			})?;
+			Self::deposit_event(Event::KeyAdded { did, key_id });
+			Ok(())
+		}
+
+		/// Remove a verification key. Credentials issued with it no longer verify afterwards.
+		#[pallet::call_index(5)]
+		#[pallet::weight(<T as Config>::WeightInfo::remove_key())]
+		pub fn remove_key(
+			origin: OriginFor<T>,
+			did: T::AccountId,
+			key_id: IdOf<T>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::mutate_document(&did, &who, |document| {
+				let index = document
+					.keys
+					.iter()
+					.position(|key| key.id == key_id)
+					.ok_or(Error::<T>::KeyNotFound)?;
+				document.keys.remove(index);
+				Ok(())
+			})?;
+			Self::deposit_event(Event::KeyRemoved { did, key_id });
+			Ok(())
+		}
+
+		#[pallet::call_index(6)]
+		#[pallet::weight(<T as Config>::WeightInfo::add_service())]
+		pub fn add_service(
+			origin: OriginFor<T>,
+			did: T::AccountId,
+			service_id: IdOf<T>,
+			url: BoundedVec<u8, T::MaxUrlLength>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::mutate_document(&did, &who, |document| {
+				ensure!(
+					!document.services.iter().any(|service| service.id == service_id),
+					Error::<T>::ServiceAlreadyAdded
+				);
+				document
+					.services
+					.try_push(ServiceEndpoint { id: service_id.clone(), url })
+					.map_err(|_| Error::<T>::TooManyServices)?;
+				Ok(())
+			})?;
+			Self::deposit_event(Event::ServiceAdded { did, service_id });
+			Ok(())
+		}
+
+		#[pallet::call_index(7)]
+		#[pallet::weight(<T as Config>::WeightInfo::remove_service())]
+		pub fn remove_service(
+			origin: OriginFor<T>,
+			did: T::AccountId,
+			service_id: IdOf<T>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::mutate_document(&did, &who, |document| {
+				let index = document
+					.services
+					.iter()
+					.position(|service| service.id == service_id)
+					.ok_or(Error::<T>::ServiceNotFound)?;
+				document.services.remove(index);
+				Ok(())
+			})?;
+			Self::deposit_event(Event::ServiceRemoved { did, service_id });
+			Ok(())
+		}
+
+		/// Issue the credential with hash `credential` in the name of `did`, signed with the
+		/// DID's key `key_id`, and anchor [`issuance_claim`] as a PoE claim of `did`.
+		#[pallet::call_index(8)]
+		#[pallet::weight(<T as Config>::WeightInfo::issue_credential())]
+		pub fn issue_credential(
+			origin: OriginFor<T>,
+			did: T::AccountId,
+			credential: H256,
+			key_id: IdOf<T>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let document = Self::ensure_controller(&did, &who)?;
+			ensure!(document.keys.iter().any(|key| key.id == key_id), Error::<T>::KeyNotFound);
+			ensure!(
+				!Credentials::<T>::contains_key(credential),
+				Error::<T>::CredentialAlreadyIssued
+			);
+
+			Self::anchor(&did, &issuance_claim(&credential))?;
+			let issued_at = frame_system::Pallet::<T>::block_number();
+			Credentials::<T>::insert(
+				credential,
+				Credential { issuer: did.clone(), key_id, issued_at, revoked_at: None },
+			);
+			Self::deposit_event(Event::CredentialIssued { issuer: did, credential });
+			Ok(())
+		}
+
+		/// Revoke a credential, anchoring [`revocation_claim`] as a PoE claim of its issuer.
+		#[pallet::call_index(9)]
+		#[pallet::weight(<T as Config>::WeightInfo::revoke_credential())]
+		pub fn revoke_credential(origin: OriginFor<T>, credential: H256) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let mut info =
+				Credentials::<T>::get(credential).ok_or(Error::<T>::CredentialNotFound)?;
+			Self::ensure_controller(&info.issuer, &who)?;
+			ensure!(info.revoked_at.is_none(), Error::<T>::CredentialAlreadyRevoked);
+
+			Self::anchor(&info.issuer, &revocation_claim(&credential))?;
+			info.revoked_at = Some(frame_system::Pallet::<T>::block_number());
+			let issuer = info.issuer.clone();
+			Credentials::<T>::insert(credential, info);
+			Self::deposit_event(Event::CredentialRevoked { issuer, credential });
+			Ok(())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// Returns the document of `did` if `who` controls it.
+		fn ensure_controller(
+			did: &T::AccountId,
+			who: &T::AccountId,
+		) -> Result<DidDocument<T>, DispatchError> {
+			let document = Dids::<T>::get(did).ok_or(Error::<T>::DidNotFound)?;
+			ensure!(document.controllers.contains(who), Error::<T>::NotController);
+			Ok(document)
+		}
+
+		/// Applies `f` to the document of `did` if `who` controls it.
+		fn mutate_document(
+			did: &T::AccountId,
+			who: &T::AccountId,
+			f: impl FnOnce(&mut DidDocument<T>) -> DispatchResult,
+		) -> DispatchResult {
+			let mut document = Self::ensure_controller(did, who)?;
+			f(&mut document)?;
+			Dids::<T>::insert(did, document);
+			Ok(())
+		}
+
+		/// Anchors `claim` as a PoE claim owned by `did`.
+		fn anchor(did: &T::AccountId, claim: &H256) -> DispatchResult {
+			let claim = BoundedVec::try_from(claim.as_bytes().to_vec())
+				.map_err(|_| pallet_poe::Error::<T>::ClaimTooLong)?;
+			pallet_poe::Pallet::<T>::do_create_claim(did.clone(), claim, None)
+		}
+
+		/// Checks `signature` over the hash `credential` against the key it was issued with.
+		pub fn verify_credential(
+			credential: H256,
+			signature: &MultiSignature,
+		) -> CredentialStatus<T::AccountId, T::BlockNumber> {
+			let Some(info) = Credentials::<T>::get(credential) else {
+				return CredentialStatus::Unknown
+			};
+			if let Some(revoked_at) = info.revoked_at {
+				return CredentialStatus::Revoked { issuer: info.issuer, revoked_at }
+			}
+			let key = Dids::<T>::get(&info.issuer)
+				.and_then(|document| document.keys.into_iter().find(|key| key.id == info.key_id));
+			let Some(key) = key else { return CredentialStatus::KeyRemoved };
+			if !signature.verify(credential.as_bytes(), &key.public.into_account()) {
+				return CredentialStatus::BadSignature
+			}
+			CredentialStatus::Valid { issuer: info.issuer, issued_at: info.issued_at }
+		}
+	}
+}
+
+sp_api::decl_runtime_apis! {
+	/// Lets verifiers check credentials against the keys of their issuers.
+	pub trait DidApi<AccountId, BlockNumber>
+	where
+		AccountId: codec::Codec,
+		BlockNumber: codec::Codec,
+	{
+		/// Checks `signature` over the hash `credential` against the key its issuer issued it
+		/// with, and whether it was revoked.
+		fn verify_credential(
+			credential: H256,
+			signature: MultiSignature,
+		) -> CredentialStatus<AccountId, BlockNumber>;
+	}
+}
@@ -0,0 +1,143 @@
+use crate as pallet_did;
+use frame_support::traits::{ConstU16, ConstU32, ConstU64, UnixTime};
+use frame_system::EnsureRoot;
+use sp_core::H256;
+use sp_runtime::{
+	testing::{Header, TestSignature, TestXt, UintAuthorityId},
+	traits::{BlakeTwo256, Extrinsic as ExtrinsicT, IdentityLookup},
+};
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+// Configure a mock runtime to test the pallet.
+frame_support::construct_runtime!(
+	pub enum Test where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system,
+		PoeModule: pallet_poe,
+		Did: pallet_did,
+	}
+);
+
+impl frame_system::Config for Test {
+	type BaseCallFilter = frame_support::traits::Everything;
+	type BlockWeights = ();
+	type BlockLength = ();
+	type DbWeight = ();
+	type RuntimeOrigin = RuntimeOrigin;
+	type RuntimeCall = RuntimeCall;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type RuntimeEvent = RuntimeEvent;
+	type BlockHashCount = ConstU64<250>;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = ();
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = ConstU16<42>;
+	type OnSetCode = ();
+	type MaxConsumers = frame_support::traits::ConstU32<16>;
+}
+
+pub type Extrinsic = TestXt<RuntimeCall, ()>;
+
+impl frame_system::offchain::SigningTypes for Test {
+	type Public = UintAuthorityId;
+	type Signature = TestSignature;
+}
+
+impl<LocalCall> frame_system::offchain::SendTransactionTypes<LocalCall> for Test
+where
+	RuntimeCall: From<LocalCall>,
+{
+	type OverarchingCall = RuntimeCall;
+	type Extrinsic = Extrinsic;
+}
+
+impl<LocalCall> frame_system::offchain::CreateSignedTransaction<LocalCall> for Test
+where
+	RuntimeCall: From<LocalCall>,
+{
+	fn create_transaction<C: frame_system::offchain::AppCrypto<Self::Public, Self::Signature>>(
+		call: RuntimeCall,
+		_public: UintAuthorityId,
+		_account: u64,
+		nonce: u64,
+	) -> Option<(RuntimeCall, <Extrinsic as ExtrinsicT>::SignaturePayload)> {
+		Some((call, (nonce, ())))
+	}
+}
+
+/// Signs with the `UintAuthorityId` keys set through `UintAuthorityId::set_all_keys`.
+pub struct TestAuthId;
+
+impl frame_system::offchain::AppCrypto<UintAuthorityId, TestSignature> for TestAuthId {
+	type RuntimeAppPublic = UintAuthorityId;
+	type GenericPublic = UintAuthorityId;
+	type GenericSignature = TestSignature;
+}
+
+impl pallet_poe::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type MaxClaimLength = ConstU32<32>;
+	type WeightInfo = ();
+	type MaxMetadataLength = ConstU32<16>;
+	type AuthorityId = TestAuthId;
+	type TsaOrigin = EnsureRoot<u64>;
+	type MaxTsaKeys = ConstU32<2>;
+	type OffchainSignature = TestSignature;
+	type OffchainPublic = UintAuthorityId;
+	type MaxCosigners = ConstU32<3>;
+	type UnixTime = MockTime;
+	type AttestationWindow = ConstU64<60_000>;
+	type ReservedClaims = pallet_did::AnchoredClaims;
+	#[cfg(feature = "runtime-benchmarks")]
+	type BenchmarkHelper = MockBenchmarkHelper;
+}
+
+#[cfg(feature = "runtime-benchmarks")]
+pub struct MockBenchmarkHelper;
+
+#[cfg(feature = "runtime-benchmarks")]
+impl pallet_poe::BenchmarkHelper<UintAuthorityId, TestSignature> for MockBenchmarkHelper {
+	fn sign(message: &[u8]) -> (UintAuthorityId, TestSignature) {
+		(UintAuthorityId(1), TestSignature(1, message.to_vec()))
+	}
+}
+
+pub struct MockTime;
+
+impl UnixTime for MockTime {
+	fn now() -> core::time::Duration {
+		core::time::Duration::ZERO
+	}
+}
+
+impl pallet_did::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type MaxControllers = ConstU32<2>;
+	type MaxKeys = ConstU32<2>;
+	type MaxServices = ConstU32<2>;
+	type MaxIdLength = ConstU32<8>;
+	type MaxUrlLength = ConstU32<32>;
+	type WeightInfo = ();
+}
+
+// Build genesis storage according to the mock runtime.
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let mut ext: sp_io::TestExternalities =
+		frame_system::GenesisConfig::default().build_storage::<Test>().unwrap().into();
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
@@ -0,0 +1,189 @@
+use crate::{issuance_claim, mock::*, revocation_claim, CredentialStatus, Error, Event};
+use frame_support::{assert_noop, assert_ok, BoundedVec};
+use sp_core::{sr25519, Pair, H256};
+use sp_runtime::{MultiSignature, MultiSigner};
+
+const ISSUER: u64 = 1;
+const DELEGATE: u64 = 2;
+const STRANGER: u64 = 3;
+
+fn id(id: &[u8]) -> BoundedVec<u8, <Test as crate::Config>::MaxIdLength> {
+	id.to_vec().try_into().unwrap()
+}
+
+fn key() -> sr25519::Pair {
+	sr25519::Pair::from_seed(&[1; 32])
+}
+
+fn sign(pair: &sr25519::Pair, credential: &H256) -> MultiSignature {
+	pair.sign(credential.as_bytes()).into()
+}
+
+fn claim(hash: &H256) -> BoundedVec<u8, <Test as pallet_poe::Config>::MaxClaimLength> {
+	hash.as_bytes().to_vec().try_into().unwrap()
+}
+
+/// Creates the DID of `ISSUER` with `key()` as its key `"sig"`.
+fn create_issuer() {
+	assert_ok!(Did::create_did(RuntimeOrigin::signed(ISSUER)));
+	assert_ok!(Did::add_key(
+		RuntimeOrigin::signed(ISSUER),
+		ISSUER,
+		id(b"sig"),
+		MultiSigner::from(key().public())
+	));
+}
+
+#[test]
+fn controllers_manage_the_did_document() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Did::create_did(RuntimeOrigin::signed(ISSUER)));
+		assert_noop!(
+			Did::create_did(RuntimeOrigin::signed(ISSUER)),
+			Error::<Test>::DidAlreadyExists
+		);
+		System::assert_last_event(Event::DidCreated { did: ISSUER }.into());
+
+		assert_noop!(
+			Did::add_controller(RuntimeOrigin::signed(DELEGATE), ISSUER, DELEGATE),
+			Error::<Test>::NotController
+		);
+		assert_ok!(Did::add_controller(RuntimeOrigin::signed(ISSUER), ISSUER, DELEGATE));
+		assert_noop!(
+			Did::add_controller(RuntimeOrigin::signed(ISSUER), ISSUER, 3),
+			Error::<Test>::TooManyControllers
+		);
+
+		// The delegate can change the document, even remove the DID's own account from it.
+		let url: BoundedVec<_, _> = b"https://example.com".to_vec().try_into().unwrap();
+		assert_ok!(Did::add_service(RuntimeOrigin::signed(DELEGATE), ISSUER, id(b"web"), url));
+		assert_ok!(Did::remove_controller(RuntimeOrigin::signed(DELEGATE), ISSUER, ISSUER));
+		assert_noop!(
+			Did::remove_controller(RuntimeOrigin::signed(DELEGATE), ISSUER, DELEGATE),
+			Error::<Test>::LastController
+		);
+
+		let document = Did::did(ISSUER).unwrap();
+		assert_eq!(document.controllers.into_inner(), vec![DELEGATE]);
+		assert_eq!(document.services[0].id, id(b"web"));
+
+		assert_ok!(Did::remove_did(RuntimeOrigin::signed(DELEGATE), ISSUER));
+		assert_eq!(Did::did(ISSUER), None);
+		assert_noop!(
+			Did::remove_did(RuntimeOrigin::signed(DELEGATE), ISSUER),
+			Error::<Test>::DidNotFound
+		);
+	});
+}
+
+#[test]
+fn issued_credentials_are_anchored_and_verifiable() {
+	new_test_ext().execute_with(|| {
+		create_issuer();
+		let credential = H256::repeat_byte(7);
+		assert_eq!(
+			Did::verify_credential(credential, &sign(&key(), &credential)),
+			CredentialStatus::Unknown
+		);
+
+		assert_noop!(
+			Did::issue_credential(RuntimeOrigin::signed(ISSUER), ISSUER, credential, id(b"nope")),
+			Error::<Test>::KeyNotFound
+		);
+		assert_ok!(Did::issue_credential(
+			RuntimeOrigin::signed(ISSUER),
+			ISSUER,
+			credential,
+			id(b"sig")
+		));
+		System::assert_last_event(Event::CredentialIssued { issuer: ISSUER, credential }.into());
+		assert_noop!(
+			Did::issue_credential(RuntimeOrigin::signed(ISSUER), ISSUER, credential, id(b"sig")),
+			Error::<Test>::CredentialAlreadyIssued
+		);
+
+		// The credential is anchored as a PoE claim of the issuer.
+		assert_eq!(PoeModule::something(claim(&issuance_claim(&credential))), Some((ISSUER, 1)));
+
+		assert_eq!(
+			Did::verify_credential(credential, &sign(&key(), &credential)),
+			CredentialStatus::Valid { issuer: ISSUER, issued_at: 1 }
+		);
+		let impostor = sr25519::Pair::from_seed(&[2; 32]);
+		assert_eq!(
+			Did::verify_credential(credential, &sign(&impostor, &credential)),
+			CredentialStatus::BadSignature
+		);
+
+		assert_ok!(Did::remove_key(RuntimeOrigin::signed(ISSUER), ISSUER, id(b"sig")));
+		assert_eq!(
+			Did::verify_credential(credential, &sign(&key(), &credential)),
+			CredentialStatus::KeyRemoved
+		);
+	});
+}
+
+#[test]
+fn revoked_credentials_no_longer_verify() {
+	new_test_ext().execute_with(|| {
+		create_issuer();
+		let credential = H256::repeat_byte(7);
+		assert_ok!(Did::issue_credential(
+			RuntimeOrigin::signed(ISSUER),
+			ISSUER,
+			credential,
+			id(b"sig")
+		));
+
+		System::set_block_number(5);
+		assert_noop!(
+			Did::revoke_credential(RuntimeOrigin::signed(DELEGATE), credential),
+			Error::<Test>::NotController
+		);
+		assert_ok!(Did::revoke_credential(RuntimeOrigin::signed(ISSUER), credential));
+		System::assert_last_event(Event::CredentialRevoked { issuer: ISSUER, credential }.into());
+		assert_noop!(
+			Did::revoke_credential(RuntimeOrigin::signed(ISSUER), credential),
+			Error::<Test>::CredentialAlreadyRevoked
+		);
+
+		assert_eq!(PoeModule::something(claim(&revocation_claim(&credential))), Some((ISSUER, 5)));
+		assert_eq!(
+			Did::verify_credential(credential, &sign(&key(), &credential)),
+			CredentialStatus::Revoked { issuer: ISSUER, revoked_at: 5 }
+		);
+	});
+}
+
+#[test]
+fn anchors_can_not_be_claimed_by_others_first() {
+	new_test_ext().execute_with(|| {
+		create_issuer();
+		let credential = H256::repeat_byte(7);
+		for anchor in [issuance_claim(&credential), revocation_claim(&credential)] {
+			assert_noop!(
+				PoeModule::create_claim(RuntimeOrigin::signed(STRANGER), claim(&anchor), None),
+				pallet_poe::Error::<Test>::ClaimReserved
+			);
+		}
+		// Claiming the bare hash of the credential doesn't get in the way either.
+		assert_ok!(PoeModule::create_claim(
+			RuntimeOrigin::signed(STRANGER),
+			claim(&credential),
+			None
+		));
+
+		assert_ok!(Did::issue_credential(
+			RuntimeOrigin::signed(ISSUER),
+			ISSUER,
+			credential,
+			id(b"sig")
+		));
+		assert_ok!(Did::revoke_credential(RuntimeOrigin::signed(ISSUER), credential));
+		assert_eq!(PoeModule::something(claim(&revocation_claim(&credential))), Some((ISSUER, 1)));
+		assert_eq!(
+			Did::verify_credential(credential, &sign(&key(), &credential)),
+			CredentialStatus::Revoked { issuer: ISSUER, revoked_at: 1 }
+		);
+	});
+}
@@ -0,0 +1,175 @@
+
+//! Weights for pallet_did
+//!
+//! These are estimates from the storage each call accesses, not benchmark results. Regenerate
+//! them on reference hardware with:
+//!
+//! ./target/release/node-template benchmark pallet --chain dev --pallet pallet_did \
+//!     --extrinsic '*' --steps=50 --repeat=20 --output pallets/did/src/weights.rs \
+//!     --template .maintain/frame-weight-template.hbs
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use core::marker::PhantomData;
+
+/// Weight functions needed for pallet_did.
+pub trait WeightInfo {
+	fn create_did() -> Weight;
+	fn remove_did() -> Weight;
+	fn add_controller() -> Weight;
+	fn remove_controller() -> Weight;
+	fn add_key() -> Weight;
+	fn remove_key() -> Weight;
+	fn add_service() -> Weight;
+	fn remove_service() -> Weight;
+	fn issue_credential() -> Weight;
+	fn revoke_credential() -> Weight;
+}
+
+/// Weights for pallet_did using the Substrate node and recommended hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	/// Storage: Did Dids (r:1 w:1)
+	fn create_did() -> Weight {
+		Weight::from_parts(15_000_000, 0)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: Did Dids (r:1 w:1)
+	fn remove_did() -> Weight {
+		Weight::from_parts(16_000_000, 0)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: Did Dids (r:1 w:1)
+	fn add_controller() -> Weight {
+		Weight::from_parts(19_000_000, 0)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: Did Dids (r:1 w:1)
+	fn remove_controller() -> Weight {
+		Weight::from_parts(19_000_000, 0)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: Did Dids (r:1 w:1)
+	fn add_key() -> Weight {
+		Weight::from_parts(20_000_000, 0)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: Did Dids (r:1 w:1)
+	fn remove_key() -> Weight {
+		Weight::from_parts(19_000_000, 0)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: Did Dids (r:1 w:1)
+	fn add_service() -> Weight {
+		Weight::from_parts(20_000_000, 0)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: Did Dids (r:1 w:1)
+	fn remove_service() -> Weight {
+		Weight::from_parts(19_000_000, 0)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: Did Dids (r:1 w:0)
+	/// Storage: Did Credentials (r:1 w:1)
+	/// Storage: PoeModule Proofs (r:1 w:1)
+	/// Storage: PoeModule TsaKeys (r:1 w:0)
+	/// Storage: PoeModule PendingAttestations (r:0 w:1)
+	fn issue_credential() -> Weight {
+		Weight::from_parts(36_000_000, 0)
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
+	}
+	/// Storage: Did Credentials (r:1 w:1)
+	/// Storage: Did Dids (r:1 w:0)
+	/// Storage: PoeModule Proofs (r:1 w:1)
+	/// Storage: PoeModule TsaKeys (r:1 w:0)
+	/// Storage: PoeModule PendingAttestations (r:0 w:1)
+	fn revoke_credential() -> Weight {
+		Weight::from_parts(36_000_000, 0)
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	/// Storage: Did Dids (r:1 w:1)
+	fn create_did() -> Weight {
+		Weight::from_parts(15_000_000, 0)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: Did Dids (r:1 w:1)
+	fn remove_did() -> Weight {
+		Weight::from_parts(16_000_000, 0)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: Did Dids (r:1 w:1)
+	fn add_controller() -> Weight {
+		Weight::from_parts(19_000_000, 0)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: Did Dids (r:1 w:1)
+	fn remove_controller() -> Weight {
+		Weight::from_parts(19_000_000, 0)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: Did Dids (r:1 w:1)
+	fn add_key() -> Weight {
+		Weight::from_parts(20_000_000, 0)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: Did Dids (r:1 w:1)
+	fn remove_key() -> Weight {
+		Weight::from_parts(19_000_000, 0)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: Did Dids (r:1 w:1)
+	fn add_service() -> Weight {
+		Weight::from_parts(20_000_000, 0)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: Did Dids (r:1 w:1)
+	fn remove_service() -> Weight {
+		Weight::from_parts(19_000_000, 0)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: Did Dids (r:1 w:0)
+	/// Storage: Did Credentials (r:1 w:1)
+	/// Storage: PoeModule Proofs (r:1 w:1)
+	/// Storage: PoeModule TsaKeys (r:1 w:0)
+	/// Storage: PoeModule PendingAttestations (r:0 w:1)
+	fn issue_credential() -> Weight {
+		Weight::from_parts(36_000_000, 0)
+			.saturating_add(RocksDbWeight::get().reads(4_u64))
+			.saturating_add(RocksDbWeight::get().writes(3_u64))
+	}
+	/// Storage: Did Credentials (r:1 w:1)
+	/// Storage: Did Dids (r:1 w:0)
+	/// Storage: PoeModule Proofs (r:1 w:1)
+	/// Storage: PoeModule TsaKeys (r:1 w:0)
+	/// Storage: PoeModule PendingAttestations (r:0 w:1)
+	fn revoke_credential() -> Weight {
+		Weight::from_parts(36_000_000, 0)
+			.saturating_add(RocksDbWeight::get().reads(4_u64))
+			.saturating_add(RocksDbWeight::get().writes(3_u64))
+	}
+}
@@ -0,0 +1,278 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! Gives every account a quota of fee-less calls per era, so that new users can try the chain
+//! before buying tokens.
+//!
+//! The [`CheckFreeQuota`] signed extension wraps the runtime's payment extension. A transaction
+//! either pays through the wrapped extension as usual, or asks to be free, in which case its call
+//! must be one of [`Config::FreeCalls`] and its signer must have quota left for the current era.
+//! Free transactions that don't meet both are rejected in `validate`, so they never enter the
+//! transaction pool.
+//!
+//! Only signers whose account exists, because they hold the existential deposit of the native
+//! token or of a sufficient asset, get a quota. Otherwise every new key would bring a fresh one.
+//! The quota used in past eras is removed from storage by `on_initialize`, a few accounts per
+//! block.
+//!
+//! The extension also checks the transaction's nonce in place of `frame_system::CheckNonce`, so
+//! that it can tell how far ahead of the signer's account a free transaction is. A free
+//! transaction is only valid if the signer has quota left for it and for every transaction before
+//! it, so the pool holds at most the remaining quota of free transactions per account.
+
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+#[frame_support::pallet]
+pub mod pallet {
+	use frame_support::{dispatch::DispatchInfo, pallet_prelude::*, traits::Contains};
+	use frame_system::{pallet_prelude::*, CheckNonce};
+	use sp_runtime::{
+		traits::{DispatchInfoOf, Dispatchable, PostDispatchInfoOf, SignedExtension, Zero},
+		transaction_validity::{InvalidTransaction, TransactionValidity, TransactionValidityError},
+	};
+	use sp_std::{fmt, vec::Vec};
+
+	/// `InvalidTransaction::Custom` code of free transactions whose signer has used up the quota.
+	pub const QUOTA_EXHAUSTED: u8 = 0;
+	/// `InvalidTransaction::Custom` code of free transactions whose signer has no account.
+	pub const NO_ACCOUNT: u8 = 1;
+	/// Entries of [`FreeCallsUsed`] `on_initialize` checks for a past era per block.
+	const MAX_PRUNE_CHECKS_PER_BLOCK: usize = 20;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// Calls that can be made without fees.
+		type FreeCalls: Contains<<Self as frame_system::Config>::RuntimeCall>;
+		/// Number of fee-less calls every account can make per era.
+		#[pallet::constant]
+		type FreeCallsPerEra: Get<u32>;
+		/// Length of an era in blocks.
+		#[pallet::constant]
+		type EraLength: Get<Self::BlockNumber>;
+	}
+
+	/// The era an account last made a fee-less call in, and how many it made in that era.
+	#[pallet::storage]
+	#[pallet::getter(fn free_calls_used)]
+	pub type FreeCallsUsed<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, (T::BlockNumber, u32), ValueQuery>;
+
+	/// The account `on_initialize` checked last, which the next block continues after.
+	#[pallet::storage]
+	pub type PruneCursor<T: Config> = StorageValue<_, T::AccountId>;
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_initialize(now: T::BlockNumber) -> Weight {
+			let era = Self::era(now);
+			let used = match PruneCursor::<T>::get() {
+				Some(who) => FreeCallsUsed::<T>::iter_from(FreeCallsUsed::<T>::hashed_key_for(who)),
+				None => FreeCallsUsed::<T>::iter(),
+			};
+			let used = used.take(MAX_PRUNE_CHECKS_PER_BLOCK).collect::<Vec<_>>();
+			let checked = used.len() as u64;
+			let next = if used.len() < MAX_PRUNE_CHECKS_PER_BLOCK {
+				None
+			} else {
+				used.last().map(|(who, _)| who.clone())
+			};
+
+			let mut pruned = 0u64;
+			for (who, (used_in, _)) in used {
+				if used_in != era {
+					FreeCallsUsed::<T>::remove(who);
+					pruned += 1;
+				}
+			}
+			PruneCursor::<T>::set(next);
+			T::DbWeight::get().reads_writes(checked + 1, pruned + 1)
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// The index of the era `now` falls in.
+		pub fn era(now: T::BlockNumber) -> T::BlockNumber {
+			let length = T::EraLength::get();
+			if length.is_zero() {
+				Zero::zero()
+			} else {
+				now / length
+			}
+		}
+
+		/// Number of fee-less calls `who` can still make in the current era.
+		pub fn remaining_free_calls(who: &T::AccountId) -> u32 {
+			let era = Self::era(frame_system::Pallet::<T>::block_number());
+			match FreeCallsUsed::<T>::get(who) {
+				(used_in, used) if used_in == era => T::FreeCallsPerEra::get().saturating_sub(used),
+				_ => T::FreeCallsPerEra::get(),
+			}
+		}
+
+		/// Checks that `who` may make `call` without fees.
+		pub fn check_free_call(
+			who: &T::AccountId,
+			call: &<T as frame_system::Config>::RuntimeCall,
+		) -> Result<(), TransactionValidityError> {
+			if !T::FreeCalls::contains(call) {
+				return Err(InvalidTransaction::Call.into())
+			}
+			if !frame_system::Pallet::<T>::account_exists(who) {
+				return Err(InvalidTransaction::Custom(NO_ACCOUNT).into())
+			}
+			if Self::remaining_free_calls(who) == 0 {
+				return Err(InvalidTransaction::Custom(QUOTA_EXHAUSTED).into())
+			}
+			Ok(())
+		}
+
+		/// Counts a fee-less call of `who` against the quota of the current era.
+		fn use_free_call(who: &T::AccountId) {
+			let era = Self::era(frame_system::Pallet::<T>::block_number());
+			FreeCallsUsed::<T>::mutate(who, |(used_in, used)| {
+				if *used_in != era {
+					*used_in = era;
+					*used = 0;
+				}
+				*used = used.saturating_add(1);
+			});
+		}
+	}
+
+	/// Checks the nonce of transactions and charges them through the payment extension `S`, or
+	/// not at all if they are free.
+	///
+	/// Free transactions are limited to [`Config::FreeCalls`] and to [`Config::FreeCallsPerEra`]
+	/// per account and era.
+	#[derive(Encode, Decode, Clone, Eq, PartialEq, TypeInfo)]
+	#[scale_info(skip_type_params(T))]
+	pub struct CheckFreeQuota<T: Config + Send + Sync, S> {
+		/// The nonce of the transaction.
+		nonce: CheckNonce<T>,
+		/// The payment extension, or `None` for a free transaction.
+		payment: Option<S>,
+	}
+
+	impl<T: Config + Send + Sync, S> CheckFreeQuota<T, S> {
+		/// A transaction with `nonce` paying through `payment`.
+		pub fn paid(nonce: T::Index, payment: S) -> Self {
+			Self { nonce: CheckNonce::from(nonce), payment: Some(payment) }
+		}
+
+		/// A transaction with `nonce` using the signer's quota instead of paying.
+		pub fn free(nonce: T::Index) -> Self {
+			Self { nonce: CheckNonce::from(nonce), payment: None }
+		}
+	}
+
+	impl<T: Config + Send + Sync, S: fmt::Debug> fmt::Debug for CheckFreeQuota<T, S> {
+		fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+			match &self.payment {
+				Some(payment) => write!(f, "CheckFreeQuota({:?}, {:?})", self.nonce, payment),
+				None => write!(f, "CheckFreeQuota({:?}, free)", self.nonce),
+			}
+		}
+	}
+
+	impl<T: Config + Send + Sync, S> SignedExtension for CheckFreeQuota<T, S>
+	where
+		<T as frame_system::Config>::RuntimeCall: Dispatchable<Info = DispatchInfo>,
+		S: SignedExtension<
+			AccountId = T::AccountId,
+			Call = <T as frame_system::Config>::RuntimeCall,
+			AdditionalSigned = (),
+		>,
+	{
+		const IDENTIFIER: &'static str = "CheckFreeQuota";
+		type AccountId = T::AccountId;
+		type Call = <T as frame_system::Config>::RuntimeCall;
+		type AdditionalSigned = ();
+		/// What the payment extension needs after dispatch, or `None` for a free transaction.
+		type Pre = Option<S::Pre>;
+
+		fn additional_signed(&self) -> Result<Self::AdditionalSigned, TransactionValidityError> {
+			Ok(())
+		}
+
+		fn validate(
+			&self,
+			who: &Self::AccountId,
+			call: &Self::Call,
+			info: &DispatchInfoOf<Self::Call>,
+			len: usize,
+		) -> TransactionValidity {
+			let valid = self.nonce.validate(who, call, info, len)?;
+			match &self.payment {
+				Some(payment) => Ok(valid.combine_with(payment.validate(who, call, info, len)?)),
+				None => {
+					Pallet::<T>::check_free_call(who, call)?;
+					// Every transaction of `who` before this one may be free as well, so the quota
+					// has to cover all of them.
+					let ahead =
+						self.nonce.0.saturating_sub(frame_system::Pallet::<T>::account_nonce(who));
+					if ahead >= Pallet::<T>::remaining_free_calls(who).into() {
+						return Err(InvalidTransaction::Custom(QUOTA_EXHAUSTED).into())
+					}
+					Ok(valid)
+				},
+			}
+		}
+
+		fn pre_dispatch(
+			self,
+			who: &Self::AccountId,
+			call: &Self::Call,
+			info: &DispatchInfoOf<Self::Call>,
+			len: usize,
+		) -> Result<Self::Pre, TransactionValidityError> {
+			self.nonce.pre_dispatch(who, call, info, len)?;
+			match self.payment {
+				Some(payment) => payment.pre_dispatch(who, call, info, len).map(Some),
+				None => {
+					Pallet::<T>::check_free_call(who, call)?;
+					Pallet::<T>::use_free_call(who);
+					Ok(None)
+				},
+			}
+		}
+
+		fn validate_unsigned(
+			call: &Self::Call,
+			info: &DispatchInfoOf<Self::Call>,
+			len: usize,
+		) -> TransactionValidity {
+			S::validate_unsigned(call, info, len)
+		}
+
+		fn pre_dispatch_unsigned(
+			call: &Self::Call,
+			info: &DispatchInfoOf<Self::Call>,
+			len: usize,
+		) -> Result<(), TransactionValidityError> {
+			S::pre_dispatch_unsigned(call, info, len)
+		}
+
+		fn post_dispatch(
+			pre: Option<Self::Pre>,
+			info: &DispatchInfoOf<Self::Call>,
+			post_info: &PostDispatchInfoOf<Self::Call>,
+			len: usize,
+			result: &DispatchResult,
+		) -> Result<(), TransactionValidityError> {
+			match pre {
+				Some(Some(pre)) => S::post_dispatch(Some(pre), info, post_info, len, result),
+				// Free transactions have nothing to settle.
+				Some(None) => Ok(()),
+				None => S::post_dispatch(None, info, post_info, len, result),
+			}
+		}
+	}
+}
@@ -0,0 +1,113 @@
+use crate as pallet_free_quota;
+use codec::{Decode, Encode};
+use frame_support::traits::{ConstU16, ConstU32, ConstU64, Contains};
+use scale_info::TypeInfo;
+use sp_core::H256;
+use sp_runtime::{
+	testing::Header,
+	traits::{BlakeTwo256, DispatchInfoOf, IdentityLookup, SignedExtension},
+	transaction_validity::TransactionValidityError,
+};
+use std::cell::RefCell;
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+// Configure a mock runtime to test the pallet.
+frame_support::construct_runtime!(
+	pub enum Test where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system,
+		FreeQuota: pallet_free_quota,
+	}
+);
+
+impl frame_system::Config for Test {
+	type BaseCallFilter = frame_support::traits::Everything;
+	type BlockWeights = ();
+	type BlockLength = ();
+	type DbWeight = ();
+	type RuntimeOrigin = RuntimeOrigin;
+	type RuntimeCall = RuntimeCall;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type RuntimeEvent = RuntimeEvent;
+	type BlockHashCount = ConstU64<250>;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = ();
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = ConstU16<42>;
+	type OnSetCode = ();
+	type MaxConsumers = frame_support::traits::ConstU32<16>;
+}
+
+/// Only `System::remark` is free.
+pub struct FreeCalls;
+
+impl Contains<RuntimeCall> for FreeCalls {
+	fn contains(call: &RuntimeCall) -> bool {
+		matches!(call, RuntimeCall::System(frame_system::Call::remark { .. }))
+	}
+}
+
+impl pallet_free_quota::Config for Test {
+	type FreeCalls = FreeCalls;
+	type FreeCallsPerEra = ConstU32<2>;
+	type EraLength = ConstU64<10>;
+}
+
+thread_local! {
+	/// The accounts `MockPayment` charged, in order.
+	pub static CHARGED: RefCell<Vec<u64>> = RefCell::new(Vec::new());
+}
+
+/// Stands in for the runtime's payment extension, recording who it charged.
+#[derive(Encode, Decode, Clone, Eq, PartialEq, Debug, TypeInfo)]
+pub struct MockPayment;
+
+impl SignedExtension for MockPayment {
+	const IDENTIFIER: &'static str = "MockPayment";
+	type AccountId = u64;
+	type Call = RuntimeCall;
+	type AdditionalSigned = ();
+	type Pre = u64;
+
+	fn additional_signed(&self) -> Result<Self::AdditionalSigned, TransactionValidityError> {
+		Ok(())
+	}
+
+	fn pre_dispatch(
+		self,
+		who: &Self::AccountId,
+		_call: &Self::Call,
+		_info: &DispatchInfoOf<Self::Call>,
+		_len: usize,
+	) -> Result<Self::Pre, TransactionValidityError> {
+		CHARGED.with(|charged| charged.borrow_mut().push(*who));
+		Ok(*who)
+	}
+}
+
+// Build genesis storage according to the mock runtime.
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let mut ext: sp_io::TestExternalities =
+		frame_system::GenesisConfig::default().build_storage::<Test>().unwrap().into();
+	ext.execute_with(|| {
+		System::set_block_number(1);
+		// Accounts 1 and 2 exist, as if they held the existential deposit.
+		System::inc_providers(&1);
+		System::inc_providers(&2);
+	});
+	ext
+}
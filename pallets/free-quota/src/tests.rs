@@ -0,0 +1,155 @@
+use crate::{mock::*, CheckFreeQuota, FreeCallsUsed, NO_ACCOUNT, QUOTA_EXHAUSTED};
+use frame_support::{assert_ok, dispatch::DispatchInfo, traits::Hooks};
+use sp_runtime::{traits::SignedExtension, transaction_validity::InvalidTransaction};
+
+fn remark() -> RuntimeCall {
+	RuntimeCall::System(frame_system::Call::remark { remark: vec![] })
+}
+
+fn remark_with_event() -> RuntimeCall {
+	RuntimeCall::System(frame_system::Call::remark_with_event { remark: vec![] })
+}
+
+fn free(nonce: u64) -> CheckFreeQuota<Test, MockPayment> {
+	CheckFreeQuota::free(nonce)
+}
+
+fn charged() -> Vec<u64> {
+	CHARGED.with(|charged| charged.borrow().clone())
+}
+
+#[test]
+fn free_calls_are_not_charged() {
+	new_test_ext().execute_with(|| {
+		let info = DispatchInfo::default();
+		assert_eq!(FreeQuota::remaining_free_calls(&1), 2);
+		assert_ok!(free(0).validate(&1, &remark(), &info, 0));
+		let pre = free(0).pre_dispatch(&1, &remark(), &info, 0).unwrap();
+		assert_eq!(pre, None);
+
+		assert!(charged().is_empty());
+		assert_eq!(FreeQuota::free_calls_used(1), (0, 1));
+		assert_eq!(FreeQuota::remaining_free_calls(&1), 1);
+		// Other accounts have their own quota.
+		assert_eq!(FreeQuota::remaining_free_calls(&2), 2);
+	});
+}
+
+#[test]
+fn over_quota_free_calls_are_invalid_transactions() {
+	new_test_ext().execute_with(|| {
+		let info = DispatchInfo::default();
+		assert_ok!(free(0).pre_dispatch(&1, &remark(), &info, 0));
+		assert_ok!(free(1).pre_dispatch(&1, &remark(), &info, 0));
+
+		let exhausted = Err(InvalidTransaction::Custom(QUOTA_EXHAUSTED).into());
+		assert_eq!(free(2).validate(&1, &remark(), &info, 0), exhausted);
+		assert_eq!(free(2).pre_dispatch(&1, &remark(), &info, 0), exhausted);
+		assert_eq!(FreeQuota::free_calls_used(1), (0, 2));
+	});
+}
+
+#[test]
+fn only_free_calls_can_be_free() {
+	new_test_ext().execute_with(|| {
+		let info = DispatchInfo::default();
+		assert_eq!(
+			free(0).validate(&1, &remark_with_event(), &info, 0),
+			Err(InvalidTransaction::Call.into())
+		);
+		assert_eq!(
+			free(0).pre_dispatch(&1, &remark_with_event(), &info, 0),
+			Err(InvalidTransaction::Call.into())
+		);
+		assert_eq!(FreeQuota::remaining_free_calls(&1), 2);
+	});
+}
+
+#[test]
+fn quota_is_renewed_every_era() {
+	new_test_ext().execute_with(|| {
+		let info = DispatchInfo::default();
+		assert_ok!(free(0).pre_dispatch(&1, &remark(), &info, 0));
+		assert_ok!(free(1).pre_dispatch(&1, &remark(), &info, 0));
+		System::set_block_number(9);
+		assert_eq!(FreeQuota::remaining_free_calls(&1), 0);
+
+		System::set_block_number(10);
+		assert_eq!(FreeQuota::remaining_free_calls(&1), 2);
+		assert_ok!(free(2).validate(&1, &remark(), &info, 0));
+		assert_ok!(free(2).pre_dispatch(&1, &remark(), &info, 0));
+		assert_eq!(FreeQuota::free_calls_used(1), (1, 1));
+	});
+}
+
+#[test]
+fn paid_calls_go_through_the_payment_extension() {
+	new_test_ext().execute_with(|| {
+		let info = DispatchInfo::default();
+		assert_ok!(free(0).pre_dispatch(&1, &remark(), &info, 0));
+		assert_ok!(free(1).pre_dispatch(&1, &remark(), &info, 0));
+
+		// Paying works regardless of the call and the quota, and uses none of it.
+		let paid = |nonce| CheckFreeQuota::<Test, _>::paid(nonce, MockPayment);
+		assert_ok!(paid(2).validate(&1, &remark_with_event(), &info, 0));
+		assert_ok!(paid(5).validate(&1, &remark(), &info, 0));
+		assert_eq!(paid(2).pre_dispatch(&1, &remark(), &info, 0), Ok(Some(1)));
+		assert_eq!(paid(0).pre_dispatch(&2, &remark(), &info, 0), Ok(Some(2)));
+		assert_eq!(charged(), vec![1, 2]);
+		assert_eq!(FreeQuota::remaining_free_calls(&2), 2);
+	});
+}
+
+#[test]
+fn free_transactions_need_quota_for_the_ones_before_them() {
+	new_test_ext().execute_with(|| {
+		let info = DispatchInfo::default();
+		let exhausted = Err(InvalidTransaction::Custom(QUOTA_EXHAUSTED).into());
+		// With a quota of 2, the pool takes the next two free transactions but not a third one.
+		assert_ok!(free(0).validate(&1, &remark(), &info, 0));
+		assert_ok!(free(1).validate(&1, &remark(), &info, 0));
+		assert_eq!(free(2).validate(&1, &remark(), &info, 0), exhausted);
+
+		assert_ok!(free(0).pre_dispatch(&1, &remark(), &info, 0));
+		assert_eq!(
+			free(0).validate(&1, &remark(), &info, 0),
+			Err(InvalidTransaction::Stale.into())
+		);
+		assert_ok!(free(1).validate(&1, &remark(), &info, 0));
+		assert_eq!(free(2).validate(&1, &remark(), &info, 0), exhausted);
+	});
+}
+
+#[test]
+fn only_existing_accounts_have_a_quota() {
+	new_test_ext().execute_with(|| {
+		let info = DispatchInfo::default();
+		let no_account = Err(InvalidTransaction::Custom(NO_ACCOUNT).into());
+		assert_eq!(free(0).validate(&3, &remark(), &info, 0), no_account);
+		assert_eq!(free(0).pre_dispatch(&3, &remark(), &info, 0), no_account);
+		assert!(!FreeCallsUsed::<Test>::contains_key(3));
+
+		System::inc_providers(&3);
+		assert_ok!(free(0).validate(&3, &remark(), &info, 0));
+	});
+}
+
+#[test]
+fn quota_used_in_past_eras_is_removed() {
+	new_test_ext().execute_with(|| {
+		let info = DispatchInfo::default();
+		for who in 3..=40 {
+			System::inc_providers(&who);
+			assert_ok!(free(0).pre_dispatch(&who, &remark(), &info, 0));
+		}
+		System::set_block_number(10);
+		assert_ok!(free(0).pre_dispatch(&1, &remark(), &info, 0));
+
+		// A block checks 20 entries, so two blocks go through all 39.
+		FreeQuota::on_initialize(10);
+		assert!(FreeCallsUsed::<Test>::iter().count() > 1);
+		FreeQuota::on_initialize(11);
+		assert_eq!(FreeCallsUsed::<Test>::iter().collect::<Vec<_>>(), vec![(1, (1, 1))]);
+		assert_eq!(FreeQuota::remaining_free_calls(&3), 2);
+	});
+}
@@ -0,0 +1,67 @@
+//! Benchmarking setup for pallet-kitties
+
+use super::*;
+use frame_benchmarking::v1::{account, benchmarks, whitelisted_caller};
+use frame_support::traits::fungibles::{Create, Mutate};
+use frame_system::RawOrigin;
+
+/// The asset `buy` pays with, created by the benchmark.
+const ASSET: u32 = 1_000;
+
+benchmarks! {
+	where_clause {
+		where
+			T::Assets: Create<T::AccountId>,
+			AssetIdOf<T>: From<u32>,
+	}
+
+	create {
+		let caller: T::AccountId = whitelisted_caller();
+	}: _(RawOrigin::Signed(caller.clone()))
+	verify {
+		assert_eq!(KittyOwner::<T>::get(0), Some(caller));
+	}
+
+	breed {
+		let caller: T::AccountId = whitelisted_caller();
+		Pallet::<T>::create(RawOrigin::Signed(caller.clone()).into())?;
+		Pallet::<T>::create(RawOrigin::Signed(caller.clone()).into())?;
+	}: _(RawOrigin::Signed(caller.clone()), 0, 1)
+	verify {
+		assert_eq!(KittyParents::<T>::get(2), (0, 1));
+	}
+
+	transfer {
+		let caller: T::AccountId = whitelisted_caller();
+		let target: T::AccountId = account("target", 0, 0);
+		Pallet::<T>::create(RawOrigin::Signed(caller.clone()).into())?;
+	}: _(RawOrigin::Signed(caller), target.clone(), 0)
+	verify {
+		assert_eq!(KittyOwner::<T>::get(0), Some(target));
+	}
+
+	set_price {
+		let caller: T::AccountId = whitelisted_caller();
+		Pallet::<T>::create(RawOrigin::Signed(caller.clone()).into())?;
+		let price = Some((ASSET.into(), 100u32.into()));
+	}: _(RawOrigin::Signed(caller), 0, price.clone())
+	verify {
+		assert_eq!(KittyPrices::<T>::get(0), price);
+	}
+
+	buy {
+		let seller: T::AccountId = account("seller", 0, 0);
+		let buyer: T::AccountId = whitelisted_caller();
+		let asset: AssetIdOf<T> = ASSET.into();
+		T::Assets::create(asset.clone(), seller.clone(), true, 1u32.into())?;
+		T::Assets::mint_into(asset.clone(), &buyer, 1_000u32.into())?;
+		Pallet::<T>::create(RawOrigin::Signed(seller.clone()).into())?;
+		let price = Some((asset.clone(), 100u32.into()));
+		Pallet::<T>::set_price(RawOrigin::Signed(seller).into(), 0, price)?;
+	}: _(RawOrigin::Signed(buyer.clone()), 0, asset, 100u32.into())
+	verify {
+		assert_eq!(KittyOwner::<T>::get(0), Some(buyer));
+	}
+
+	impl_benchmark_test_suite!(KittiesModule, crate::mock::new_test_ext(), crate::mock::Test);
+}
@@ -2,28 +2,51 @@
 
 pub use pallet::*;
 
-#[cfg(test)]
-mod mock;
+#[cfg(any(test, feature = "fuzzing"))]
+pub mod mock;
 
 #[cfg(test)]
 mod tests;
 
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+pub mod weights;
+pub use weights::*;
+
 #[frame_support::pallet]
 pub mod pallet {
 	use super::*;
-	use frame_support::pallet_prelude::*;
-	use frame_support::traits::Randomness;
+	use frame_support::{
+		pallet_prelude::*,
+		sp_std::vec::Vec,
+		traits::{
+			fungibles::{self, Mutate as _},
+			tokens::Preservation,
+			Randomness,
+		},
+	};
 	use frame_system::pallet_prelude::*;
 	use sp_io::hashing::blake2_128;
 
 	pub type KittyId = u32;
 
+	type AssetsOf<T> = <T as Config>::Assets;
+	type AccountIdOf<T> = <T as frame_system::Config>::AccountId;
+	/// An asset kitties can be sold for.
+	pub type AssetIdOf<T> = <AssetsOf<T> as fungibles::Inspect<AccountIdOf<T>>>::AssetId;
+	/// An amount of an asset kitties can be sold for.
+	pub type AssetBalanceOf<T> = <AssetsOf<T> as fungibles::Inspect<AccountIdOf<T>>>::Balance;
+
 	#[derive(
 		Encode, Decode, Clone, Copy, RuntimeDebug, PartialEq, Eq, Default, TypeInfo, MaxEncodedLen,
 	)]
 	pub struct Kitty(pub [u8; 16]);
 
+	/// The current storage version.
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
 	#[pallet::pallet]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	pub struct Pallet<T>(_);
 
 	#[pallet::config]
@@ -31,6 +54,10 @@ pub mod pallet {
 		/// Because this pallet emits events, it depends on the runtime's definition of an event.
 		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
 		type Randomness: Randomness<Self::Hash, Self::BlockNumber>;
+		/// The assets kitties are sold for.
+		type Assets: fungibles::Mutate<Self::AccountId>;
+		/// Weight information for extrinsics in this pallet.
+		type WeightInfo: WeightInfo;
 	}
 
 	#[pallet::storage]
@@ -51,12 +78,69 @@ pub mod pallet {
 	pub type KittyParents<T: Config> =
 		StorageMap<_, Blake2_128Concat, KittyId, (KittyId, KittyId), ValueQuery>;
 
+	/// The asset and the amount of it each kitty on sale is listed for.
+	#[pallet::storage]
+	#[pallet::getter(fn kitty_price)]
+	pub type KittyPrices<T: Config> =
+		StorageMap<_, Blake2_128Concat, KittyId, (AssetIdOf<T>, AssetBalanceOf<T>)>;
+
+	#[pallet::genesis_config]
+	pub struct GenesisConfig<T: Config> {
+		/// Accounts and the number of kitties each of them owns from genesis on.
+		pub kitties: Vec<(T::AccountId, u32)>,
+	}
+
+	#[cfg(feature = "std")]
+	impl<T: Config> Default for GenesisConfig<T> {
+		fn default() -> Self {
+			Self { kitties: Vec::new() }
+		}
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> GenesisBuild<T> for GenesisConfig<T> {
+		fn build(&self) {
+			for (owner, count) in &self.kitties {
+				for _ in 0..*count {
+					let kitty_id = Pallet::<T>::get_next_id().expect("too many genesis kitties");
+					Kitties::<T>::insert(kitty_id, Kitty(blake2_128(&kitty_id.encode())));
+					KittyOwner::<T>::insert(kitty_id, owner);
+				}
+			}
+		}
+	}
+
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
 	pub enum Event<T: Config> {
-		KittyCreated { who: T::AccountId, kitty_id: KittyId, kitty: Kitty },
-		KittyBreed { who: T::AccountId, kitty_id: KittyId, kitty: Kitty },
-		KittyTransferred { from: T::AccountId, to: T::AccountId, kitty_id: KittyId },
+		KittyCreated {
+			who: T::AccountId,
+			kitty_id: KittyId,
+			kitty: Kitty,
+		},
+		KittyBreed {
+			who: T::AccountId,
+			kitty_id: KittyId,
+			kitty: Kitty,
+		},
+		KittyTransferred {
+			from: T::AccountId,
+			to: T::AccountId,
+			kitty_id: KittyId,
+		},
+		/// A kitty was listed for sale, or taken off sale if `price` is `None`.
+		KittyPriceSet {
+			who: T::AccountId,
+			kitty_id: KittyId,
+			price: Option<(AssetIdOf<T>, AssetBalanceOf<T>)>,
+		},
+		KittySold {
+			seller: T::AccountId,
+			buyer: T::AccountId,
+			kitty_id: KittyId,
+			asset: AssetIdOf<T>,
+			price: AssetBalanceOf<T>,
+		},
 	}
 
 	// Errors inform users that something went wrong.
@@ -67,12 +151,27 @@ pub mod pallet {
 		/// Errors should have helpful documentation associated with them.
 		StorageOverflow,
 		InvalidKittyId,
+		/// Only the owner of a kitty can sell it.
+		NotOwner,
+		NotForSale,
+		/// The kitty is listed in another asset, or for more than the buyer is willing to pay.
+		PriceChanged,
+		/// The buyer already owns the kitty.
+		AlreadyOwned,
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		#[cfg(feature = "try-runtime")]
+		fn try_state(_n: T::BlockNumber) -> Result<(), &'static str> {
+			Self::do_try_state()
+		}
 	}
 
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
 		#[pallet::call_index(0)]
-		#[pallet::weight(10_1000)]
+		#[pallet::weight(T::WeightInfo::create())]
 		pub fn create(origin: OriginFor<T>) -> DispatchResult {
 			let who = ensure_signed(origin)?;
 			let kitty_id = Self::get_next_id()?;
@@ -88,7 +187,7 @@ pub mod pallet {
 
 		//breed kitty from two parents
 		#[pallet::call_index(1)]
-		#[pallet::weight(10_1000)]
+		#[pallet::weight(T::WeightInfo::breed())]
 		pub fn breed(
 			origin: OriginFor<T>,
 			kitty_id_1: KittyId,
@@ -122,13 +221,66 @@ pub mod pallet {
 
 		// transfer kitty to another account
 		#[pallet::call_index(2)]
-		#[pallet::weight(10_1000)]
+		#[pallet::weight(T::WeightInfo::transfer())]
 		pub fn transfer(
 			origin: OriginFor<T>,
 			to: T::AccountId,
 			kitty_id: KittyId,
 		) -> DispatchResult {
 			let from = ensure_signed(origin)?;
+			Self::do_transfer(from, to, kitty_id)
+		}
+
+		/// List `kitty_id` for sale at `price`, an amount of an asset, or take it off sale with
+		/// `None`. Transferring the kitty takes it off sale too.
+		#[pallet::call_index(3)]
+		#[pallet::weight(T::WeightInfo::set_price())]
+		pub fn set_price(
+			origin: OriginFor<T>,
+			kitty_id: KittyId,
+			price: Option<(AssetIdOf<T>, AssetBalanceOf<T>)>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let owner = KittyOwner::<T>::get(kitty_id).ok_or(Error::<T>::InvalidKittyId)?;
+			ensure!(owner == who, Error::<T>::NotOwner);
+
+			KittyPrices::<T>::set(kitty_id, price.clone());
+			Self::deposit_event(Event::KittyPriceSet { who, kitty_id, price });
+			Ok(())
+		}
+
+		/// Buy a kitty on sale, paying its owner the listed price. Fails if the kitty is not
+		/// listed in `asset`, or for more than `max_price`, so that the owner can't raise the
+		/// price under the buyer's feet.
+		#[pallet::call_index(4)]
+		#[pallet::weight(T::WeightInfo::buy())]
+		pub fn buy(
+			origin: OriginFor<T>,
+			kitty_id: KittyId,
+			asset: AssetIdOf<T>,
+			max_price: AssetBalanceOf<T>,
+		) -> DispatchResult {
+			let buyer = ensure_signed(origin)?;
+			let (listed_asset, price) =
+				KittyPrices::<T>::get(kitty_id).ok_or(Error::<T>::NotForSale)?;
+			ensure!(listed_asset == asset && price <= max_price, Error::<T>::PriceChanged);
+			let seller = KittyOwner::<T>::get(kitty_id).ok_or(Error::<T>::InvalidKittyId)?;
+			ensure!(seller != buyer, Error::<T>::AlreadyOwned);
+
+			T::Assets::transfer(asset.clone(), &buyer, &seller, price, Preservation::Expendable)?;
+			Self::do_transfer(seller.clone(), buyer.clone(), kitty_id)?;
+			Self::deposit_event(Event::KittySold { seller, buyer, kitty_id, asset, price });
+			Ok(())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// Transfer `kitty_id` from `from` to `to`, failing unless `from` owns it.
+		pub fn do_transfer(
+			from: T::AccountId,
+			to: T::AccountId,
+			kitty_id: KittyId,
+		) -> DispatchResult {
 			ensure!(Kitties::<T>::contains_key(kitty_id), Error::<T>::InvalidKittyId);
 			ensure!(KittyOwner::<T>::contains_key(kitty_id), Error::<T>::InvalidKittyId);
 
@@ -136,14 +288,36 @@ pub mod pallet {
 			ensure!(owner == from, Error::<T>::InvalidKittyId);
 
 			KittyOwner::<T>::insert(kitty_id, to.clone());
+			KittyPrices::<T>::remove(kitty_id);
 
 			// Emit an event.
 			Self::deposit_event(Event::KittyTransferred { from, to, kitty_id });
 			Ok(())
 		}
-	}
 
-	impl<T: Config> Pallet<T> {
+		/// Checks that every kitty has an owner and an id below `NextKittyId`, and that the
+		/// parents of a bred kitty exist and are older than it, and that only existing kitties are
+		/// on sale.
+		#[cfg(any(feature = "try-runtime", feature = "fuzzing", test))]
+		pub fn do_try_state() -> Result<(), &'static str> {
+			let next_kitty_id = NextKittyId::<T>::get();
+			for kitty_id in Kitties::<T>::iter_keys() {
+				ensure!(KittyOwner::<T>::contains_key(kitty_id), "kitty without an owner");
+				ensure!(kitty_id < next_kitty_id, "kitty id not below NextKittyId");
+			}
+			for (kitty_id, (parent_1, parent_2)) in KittyParents::<T>::iter() {
+				ensure!(Kitties::<T>::contains_key(kitty_id), "parents of a missing kitty");
+				for parent in [parent_1, parent_2] {
+					ensure!(Kitties::<T>::contains_key(parent), "kitty parent does not exist");
+					ensure!(parent < kitty_id, "kitty parent is not older than the kitty");
+				}
+			}
+			for kitty_id in KittyPrices::<T>::iter_keys() {
+				ensure!(Kitties::<T>::contains_key(kitty_id), "price of a missing kitty");
+			}
+			Ok(())
+		}
+
 		fn get_next_id() -> Result<KittyId, DispatchError> {
 			NextKittyId::<T>::try_mutate(|next_id| -> Result<KittyId, DispatchError> {
 				let current_id = *next_id;
@@ -1,11 +1,13 @@
-use crate::{mock::*, Error, Event, Kitty, KittyId, NextKittyId};
+use crate::{
+	mock::*, Error, Event, Kitty, KittyId, KittyOwner, KittyParents, KittyPrices, NextKittyId,
+};
 use frame_support::{assert_noop, assert_ok};
 //import testevent
 use frame_system::{EventRecord, Phase};
 
 #[test]
 fn create_kitty_works() {
-	new_test_ext().execute_with(|| {
+	build_and_execute(|| {
 		let kitty_id = 0;
 		assert_ok!(KittiesModule::create(RuntimeOrigin::signed(1)));
 		assert_eq!(
@@ -18,7 +20,7 @@ fn create_kitty_works() {
 // test event
 #[test]
 fn create_kitty_works_with_event_sent() {
-	new_test_ext().execute_with(|| {
+	build_and_execute(|| {
 		let kitty_id = 0;
 		let who = 1;
 		assert_ok!(KittiesModule::create(RuntimeOrigin::signed(1)));
@@ -30,7 +32,7 @@ fn create_kitty_works_with_event_sent() {
 
 #[test]
 fn create_kitty_failed_when_kitty_count_exceeds_max_value() {
-	new_test_ext().execute_with(|| {
+	build_and_execute(|| {
 		NextKittyId::<Test>::set(KittyId::max_value());
 		assert_noop!(
 			KittiesModule::create(RuntimeOrigin::signed(1)),
@@ -41,7 +43,7 @@ fn create_kitty_failed_when_kitty_count_exceeds_max_value() {
 
 #[test]
 fn breed_kitty_works() {
-	new_test_ext().execute_with(|| {
+	build_and_execute(|| {
 		let kitty_id_1 = 0;
 		let kitty_id_2 = 1;
 		let kitty_id_3 = 2;
@@ -62,7 +64,7 @@ fn breed_kitty_works() {
 // test event KittyBreeded
 #[test]
 fn breed_kitty_works_with_event_sent() {
-	new_test_ext().execute_with(|| {
+	build_and_execute(|| {
 		let kitty_id_1 = 0;
 		let kitty_id_2 = 1;
 		let kitty_id_3 = 2;
@@ -78,7 +80,7 @@ fn breed_kitty_works_with_event_sent() {
 
 #[test]
 fn breed_kitty_failed_when_kitty_count_exceeds_max_value() {
-	new_test_ext().execute_with(|| {
+	build_and_execute(|| {
 		let kitty_id_1 = 0;
 		let kitty_id_2 = 1;
 		assert_ok!(KittiesModule::create(RuntimeOrigin::signed(1))); // 0
@@ -93,7 +95,7 @@ fn breed_kitty_failed_when_kitty_count_exceeds_max_value() {
 
 #[test]
 fn transfer_kitty_works() {
-	new_test_ext().execute_with(|| {
+	build_and_execute(|| {
 		let kitty_id = 0;
 		let from_account_id = 1;
 		let to_account_id = 2;
@@ -110,7 +112,7 @@ fn transfer_kitty_works() {
 // test event KittyTransferred
 #[test]
 fn transfer_kitty_works_with_event_sent() {
-	new_test_ext().execute_with(|| {
+	build_and_execute(|| {
 		let kitty_id = 0;
 		let from_account_id = 1;
 		let to_account_id = 2;
@@ -125,3 +127,111 @@ fn transfer_kitty_works_with_event_sent() {
 		System::assert_has_event(expected_event.clone().into())
 	});
 }
+
+
+
+#[test]
+fn try_state_detects_broken_invariants() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesModule::create(RuntimeOrigin::signed(1)));
+		assert_ok!(KittiesModule::create(RuntimeOrigin::signed(1)));
+		assert_ok!(KittiesModule::breed(RuntimeOrigin::signed(1), 0, 1));
+		assert_ok!(KittiesModule::do_try_state());
+
+		KittyParents::<Test>::insert(2, (0, 3));
+		assert_noop!(KittiesModule::do_try_state(), "kitty parent does not exist");
+
+		KittyParents::<Test>::insert(1, (0, 2));
+		KittyParents::<Test>::remove(2);
+		assert_noop!(KittiesModule::do_try_state(), "kitty parent is not older than the kitty");
+
+		KittyParents::<Test>::remove(1);
+		NextKittyId::<Test>::put(2);
+		assert_noop!(KittiesModule::do_try_state(), "kitty id not below NextKittyId");
+
+		NextKittyId::<Test>::put(3);
+		KittyOwner::<Test>::remove(0);
+		assert_noop!(KittiesModule::do_try_state(), "kitty without an owner");
+	});
+}
+
+#[test]
+fn kitties_are_sold_for_assets() {
+	build_and_execute(|| {
+		let kitty_id = 0;
+		assert_ok!(KittiesModule::create(RuntimeOrigin::signed(1)));
+		assert_noop!(
+			KittiesModule::set_price(RuntimeOrigin::signed(2), kitty_id, Some((ASSET, 100))),
+			Error::<Test>::NotOwner
+		);
+		assert_noop!(
+			KittiesModule::buy(RuntimeOrigin::signed(2), kitty_id, ASSET, 100),
+			Error::<Test>::NotForSale
+		);
+		assert_ok!(KittiesModule::set_price(
+			RuntimeOrigin::signed(1),
+			kitty_id,
+			Some((ASSET, 100))
+		));
+		System::assert_last_event(
+			Event::KittyPriceSet { who: 1, kitty_id, price: Some((ASSET, 100)) }.into(),
+		);
+
+		// The buyer is protected against price and asset changes.
+		assert_noop!(
+			KittiesModule::buy(RuntimeOrigin::signed(2), kitty_id, ASSET, 99),
+			Error::<Test>::PriceChanged
+		);
+		assert_noop!(
+			KittiesModule::buy(RuntimeOrigin::signed(2), kitty_id, ASSET + 1, 100),
+			Error::<Test>::PriceChanged
+		);
+		assert_noop!(
+			KittiesModule::buy(RuntimeOrigin::signed(1), kitty_id, ASSET, 100),
+			Error::<Test>::AlreadyOwned
+		);
+
+		assert_ok!(KittiesModule::buy(RuntimeOrigin::signed(2), kitty_id, ASSET, 100));
+		System::assert_last_event(
+			Event::KittySold { seller: 1, buyer: 2, kitty_id, asset: ASSET, price: 100 }.into(),
+		);
+		assert_eq!(KittyOwner::<Test>::get(kitty_id), Some(2));
+		assert_eq!(Assets::balance(ASSET, 1), ASSET_ENDOWMENT + 100);
+		assert_eq!(Assets::balance(ASSET, 2), ASSET_ENDOWMENT - 100);
+		// Changing hands takes the kitty off sale.
+		assert_eq!(KittyPrices::<Test>::get(kitty_id), None);
+	});
+}
+
+#[test]
+fn buy_failed_when_the_buyer_can_not_pay() {
+	build_and_execute(|| {
+		let kitty_id = 0;
+		assert_ok!(KittiesModule::create(RuntimeOrigin::signed(1)));
+		let price = ASSET_ENDOWMENT + 1;
+		assert_ok!(KittiesModule::set_price(
+			RuntimeOrigin::signed(1),
+			kitty_id,
+			Some((ASSET, price))
+		));
+		assert!(KittiesModule::buy(RuntimeOrigin::signed(2), kitty_id, ASSET, price).is_err());
+		assert_eq!(KittyOwner::<Test>::get(kitty_id), Some(1));
+		assert_eq!(Assets::balance(ASSET, 2), ASSET_ENDOWMENT);
+	});
+}
+
+#[test]
+fn genesis_kitties_belong_to_their_owners() {
+	let mut storage = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	crate::GenesisConfig::<Test> { kitties: vec![(1, 2), (2, 1)] }
+		.assimilate_storage(&mut storage)
+		.unwrap();
+	sp_io::TestExternalities::from(storage).execute_with(|| {
+		assert_eq!(NextKittyId::<Test>::get(), 3);
+		assert_eq!(KittyOwner::<Test>::get(0), Some(1));
+		assert_eq!(KittyOwner::<Test>::get(1), Some(1));
+		assert_eq!(KittyOwner::<Test>::get(2), Some(2));
+		assert_ok!(KittiesModule::transfer(RuntimeOrigin::signed(1), 2, 1));
+		KittiesModule::do_try_state().unwrap();
+	});
+}
@@ -0,0 +1,122 @@
+
+//! Weights for pallet_kitties
+//!
+//! These are estimates from the storage each call accesses, not benchmark results. Regenerate
+//! them on reference hardware with:
+//!
+//! ./target/release/node-template benchmark pallet --chain dev --pallet pallet_kitties \
+//!     --extrinsic '*' --steps=50 --repeat=20 --output pallets/kitties/src/weights.rs \
+//!     --template .maintain/frame-weight-template.hbs
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use core::marker::PhantomData;
+
+/// Weight functions needed for pallet_kitties.
+pub trait WeightInfo {
+	fn create() -> Weight;
+	fn breed() -> Weight;
+	fn transfer() -> Weight;
+	fn set_price() -> Weight;
+	fn buy() -> Weight;
+}
+
+/// Weights for pallet_kitties using the Substrate node and recommended hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	/// Storage: Kitties NextKittyId (r:1 w:1)
+	/// Storage: RandomnessCollectiveFlip RandomMaterial (r:1 w:0)
+	/// Storage: Kitties Kitties (r:0 w:1)
+	/// Storage: Kitties KittyOwner (r:0 w:1)
+	fn create() -> Weight {
+		Weight::from_parts(15_000_000, 0)
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
+	}
+	/// Storage: Kitties Kitties (r:2 w:1)
+	/// Storage: Kitties NextKittyId (r:1 w:1)
+	/// Storage: RandomnessCollectiveFlip RandomMaterial (r:1 w:0)
+	/// Storage: Kitties KittyOwner (r:0 w:1)
+	/// Storage: Kitties KittyParents (r:0 w:1)
+	fn breed() -> Weight {
+		Weight::from_parts(22_000_000, 0)
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().writes(4_u64))
+	}
+	/// Storage: Kitties Kitties (r:1 w:0)
+	/// Storage: Kitties KittyOwner (r:1 w:1)
+	/// Storage: Kitties KittyPrices (r:0 w:1)
+	fn transfer() -> Weight {
+		Weight::from_parts(14_000_000, 0)
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+	/// Storage: Kitties KittyOwner (r:1 w:0)
+	/// Storage: Kitties KittyPrices (r:0 w:1)
+	fn set_price() -> Weight {
+		Weight::from_parts(12_000_000, 0)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: Kitties KittyPrices (r:1 w:1)
+	/// Storage: Kitties Kitties (r:1 w:0)
+	/// Storage: Kitties KittyOwner (r:1 w:1)
+	/// Storage: Assets Asset (r:1 w:1)
+	/// Storage: Assets Account (r:2 w:2)
+	fn buy() -> Weight {
+		Weight::from_parts(40_000_000, 0)
+			.saturating_add(T::DbWeight::get().reads(6_u64))
+			.saturating_add(T::DbWeight::get().writes(5_u64))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	/// Storage: Kitties NextKittyId (r:1 w:1)
+	/// Storage: RandomnessCollectiveFlip RandomMaterial (r:1 w:0)
+	/// Storage: Kitties Kitties (r:0 w:1)
+	/// Storage: Kitties KittyOwner (r:0 w:1)
+	fn create() -> Weight {
+		Weight::from_parts(15_000_000, 0)
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().writes(3_u64))
+	}
+	/// Storage: Kitties Kitties (r:2 w:1)
+	/// Storage: Kitties NextKittyId (r:1 w:1)
+	/// Storage: RandomnessCollectiveFlip RandomMaterial (r:1 w:0)
+	/// Storage: Kitties KittyOwner (r:0 w:1)
+	/// Storage: Kitties KittyParents (r:0 w:1)
+	fn breed() -> Weight {
+		Weight::from_parts(22_000_000, 0)
+			.saturating_add(RocksDbWeight::get().reads(4_u64))
+			.saturating_add(RocksDbWeight::get().writes(4_u64))
+	}
+	/// Storage: Kitties Kitties (r:1 w:0)
+	/// Storage: Kitties KittyOwner (r:1 w:1)
+	/// Storage: Kitties KittyPrices (r:0 w:1)
+	fn transfer() -> Weight {
+		Weight::from_parts(14_000_000, 0)
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+	/// Storage: Kitties KittyOwner (r:1 w:0)
+	/// Storage: Kitties KittyPrices (r:0 w:1)
+	fn set_price() -> Weight {
+		Weight::from_parts(12_000_000, 0)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: Kitties KittyPrices (r:1 w:1)
+	/// Storage: Kitties Kitties (r:1 w:0)
+	/// Storage: Kitties KittyOwner (r:1 w:1)
+	/// Storage: Assets Asset (r:1 w:1)
+	/// Storage: Assets Account (r:2 w:2)
+	fn buy() -> Weight {
+		Weight::from_parts(40_000_000, 0)
+			.saturating_add(RocksDbWeight::get().reads(6_u64))
+			.saturating_add(RocksDbWeight::get().writes(5_u64))
+	}
+}
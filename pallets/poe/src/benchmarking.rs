@@ -1,20 +1,64 @@
 use crate::*;
-use frame_benchmarking::v1::{benchmarks, whitelisted_caller, account};
+use frame_benchmarking::v1::{benchmarks, whitelisted_caller, account, BenchmarkError};
+use frame_support::traits::UnixTime;
 use frame_system::RawOrigin;
+use sp_core::ed25519;
+use sp_runtime::traits::IdentifyAccount;
 use sp_std::vec;
 
+type Claim<T> = BoundedVec<u8, <T as Config>::MaxClaimLength>;
+
+/// A claim of the maximum length.
+fn max_claim<T: Config>() -> Claim<T> {
+	BoundedVec::try_from(vec![1; T::MaxClaimLength::get() as usize]).unwrap()
+}
+
+/// Fills `TsaKeys` up to one short of `MaxTsaKeys`.
+fn add_tsa_keys<T: Config>() {
+	let keys = (0..T::MaxTsaKeys::get().saturating_sub(1))
+		.map(|i| ed25519::Public::from_raw([i as u8; 32]))
+		.collect::<Vec<_>>();
+	TsaKeys::<T>::put(BoundedVec::try_from(keys).unwrap());
+}
+
+fn cosigners<T: Config>(c: u32) -> BoundedVec<T::AccountId, T::MaxCosigners> {
+	BoundedVec::try_from((0..c).map(|i| account("cosigner", i, 0)).collect::<Vec<_>>()).unwrap()
+}
+
+/// Has `creator` create a claim of the maximum length with `c` co-signers, and has all of them
+/// but the last one sign it.
+fn new_multi_party_claim<T: Config>(creator: &T::AccountId, c: u32, revoke_threshold: u32) -> (Claim<T>, Vec<T::AccountId>) {
+	let claim = max_claim::<T>();
+	let cosigners = cosigners::<T>(c);
+	// Queue the claim for attestation too.
+	TsaKeys::<T>::put(BoundedVec::try_from(vec![ed25519::Public::from_raw([0; 32])]).unwrap());
+	assert!(Pallet::<T>::create_multi_party_claim(RawOrigin::Signed(creator.clone()).into(), claim.clone(), cosigners.clone(), revoke_threshold).is_ok());
+	for cosigner in &cosigners[..cosigners.len() - 1] {
+		assert!(Pallet::<T>::cosign_claim(RawOrigin::Signed(cosigner.clone()).into(), claim.clone()).is_ok());
+	}
+	(claim, cosigners.into_inner())
+}
+
 benchmarks! {
 	create_claim {
 		let d in 0 .. T::MaxClaimLength::get();
+		let m in 0 .. 2 * T::MaxMetadataLength::get();
 		let claim = BoundedVec::try_from(vec![0; d as usize]).unwrap();
 		let caller: T::AccountId = whitelisted_caller();
-	}: _(RawOrigin::Signed(caller.clone()), claim.clone())
+		// Queue the claim for attestation too.
+		TsaKeys::<T>::put(BoundedVec::try_from(vec![ed25519::Public::from_raw([0; 32])]).unwrap());
+		let filename_len = m.min(T::MaxMetadataLength::get());
+		let metadata = ClaimMetadata {
+			filename: BoundedVec::try_from(vec![0; filename_len as usize]).unwrap(),
+			mime_type: BoundedVec::try_from(vec![0; (m - filename_len) as usize]).unwrap(),
+		};
+	}: _(RawOrigin::Signed(caller.clone()), claim.clone(), Some(metadata))
 
 	revoke_claim {
 		let d in 0 .. T::MaxClaimLength::get();
 		let claim = BoundedVec::try_from(vec![0; d as usize]).unwrap();
 		let caller: T::AccountId = whitelisted_caller();
-		assert!(Pallet::<T>::create_claim(RawOrigin::Signed(caller.clone()).into(), claim.clone()).is_ok());
+		assert!(Pallet::<T>::create_claim(RawOrigin::Signed(caller.clone()).into(), claim.clone(), None).is_ok());
 	}: _(RawOrigin::Signed(caller.clone()), claim.clone())
 
 	transfer_claim {
@@ -22,10 +66,111 @@ benchmarks! {
 		let claim = BoundedVec::try_from(vec![0; d as usize]).unwrap();
 		let caller: T::AccountId = whitelisted_caller();
 		let target: T::AccountId = account("target", 0, 0);
-		assert!(Pallet::<T>::create_claim(RawOrigin::Signed(caller.clone()).into(), claim.clone()).is_ok());
+		assert!(Pallet::<T>::create_claim(RawOrigin::Signed(caller.clone()).into(), claim.clone(), None).is_ok());
 	}: _(RawOrigin::Signed(caller.clone()), claim.clone(), target)
 
-	impl_benchmark_test_suite!(PoeModule, crate::mock::new_test_ext(), crate::mock::Test);
+	submit_attestation {
+		let claim = max_claim::<T>();
+		let caller: T::AccountId = whitelisted_caller();
+		add_tsa_keys::<T>();
+		let key = sp_io::crypto::ed25519_generate(KEY_TYPE, None);
+		TsaKeys::<T>::mutate(|keys| keys.try_push(key)).unwrap();
+		assert!(Pallet::<T>::create_claim(RawOrigin::Signed(caller.clone()).into(), claim.clone(), None).is_ok());
+		let timestamp = T::UnixTime::now().as_millis() as u64;
+		let signature = sp_io::crypto::ed25519_sign(KEY_TYPE, &key, &TsaToken::payload(&claim, timestamp)).unwrap();
+		let token = TsaToken { key, timestamp, signature };
+	}: _(RawOrigin::Signed(caller), claim.clone(), token)
+	verify {
+		assert!(Attestations::<T>::contains_key(&claim));
+	}
+
+	add_tsa_key {
+		add_tsa_keys::<T>();
+		let key = ed25519::Public::from_raw([u8::MAX; 32]);
+		let origin = T::TsaOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+	}: _<T::RuntimeOrigin>(origin, key)
+	verify {
+		assert!(TsaKeys::<T>::get().contains(&key));
+	}
+
+	remove_tsa_key {
+		add_tsa_keys::<T>();
+		let key = ed25519::Public::from_raw([u8::MAX; 32]);
+		TsaKeys::<T>::mutate(|keys| keys.try_push(key)).unwrap();
+		let origin = T::TsaOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+	}: _<T::RuntimeOrigin>(origin, key)
+	verify {
+		assert!(!TsaKeys::<T>::get().contains(&key));
+	}
+
+	create_claim_signed {
+		let d in 0 .. T::MaxClaimLength::get();
+		let claim: Claim<T> = BoundedVec::try_from(vec![0; d as usize]).unwrap();
+		let caller: T::AccountId = whitelisted_caller();
+		let (public, signature) = T::BenchmarkHelper::sign(&Pallet::<T>::claim_payload(&claim, 0));
+		let signer = public.into_account();
+		// Queue the claim for attestation too.
+		TsaKeys::<T>::put(BoundedVec::try_from(vec![ed25519::Public::from_raw([0; 32])]).unwrap());
+	}: _(RawOrigin::Signed(caller), claim.clone(), signer.clone(), signature)
+	verify {
+		assert_eq!(Proofs::<T>::get(&claim).map(|(owner, _)| owner), Some(signer));
+	}
+
+	create_multi_party_claim {
+		let d in 0 .. T::MaxClaimLength::get();
+		let c in 1 .. T::MaxCosigners::get();
+		let claim: Claim<T> = BoundedVec::try_from(vec![0; d as usize]).unwrap();
+		let caller: T::AccountId = whitelisted_caller();
+		// Queue the claim for attestation too.
+		TsaKeys::<T>::put(BoundedVec::try_from(vec![ed25519::Public::from_raw([0; 32])]).unwrap());
+	}: _(RawOrigin::Signed(caller), claim.clone(), cosigners::<T>(c), c + 1)
+	verify {
+		assert!(MultiPartyClaims::<T>::contains_key(&claim));
+	}
+
+	// The last co-signer signs, so the claim becomes fully signed.
+	cosign_claim {
+		let c in 1 .. T::MaxCosigners::get();
+		let caller: T::AccountId = whitelisted_caller();
+		let (claim, cosigners) = new_multi_party_claim::<T>(&caller, c, 1);
+		let last = cosigners[cosigners.len() - 1].clone();
+	}: _(RawOrigin::Signed(last), claim.clone())
+	verify {
+		let info = MultiPartyClaims::<T>::get(&claim).unwrap();
+		assert!(info.cosigners.iter().all(|(_, signed)| *signed));
+	}
+
+	// Every other party approved already, so the claim is revoked along with their approvals.
+	approve_revocation {
+		let c in 1 .. T::MaxCosigners::get();
+		let caller: T::AccountId = whitelisted_caller();
+		let (claim, cosigners) = new_multi_party_claim::<T>(&caller, c, c + 1);
+		let last = cosigners[cosigners.len() - 1].clone();
+		assert!(Pallet::<T>::cosign_claim(RawOrigin::Signed(last).into(), claim.clone()).is_ok());
+		for cosigner in cosigners {
+			assert!(Pallet::<T>::approve_revocation(RawOrigin::Signed(cosigner).into(), claim.clone()).is_ok());
+		}
+	}: _(RawOrigin::Signed(caller), claim.clone())
+	verify {
+		assert!(!Proofs::<T>::contains_key(&claim));
+	}
+
+	// Every party that can approve revoking the claim did, so there are as many approvals to
+	// remove as there can be.
+	withdraw_claim {
+		let c in 1 .. T::MaxCosigners::get();
+		let caller: T::AccountId = whitelisted_caller();
+		let (claim, cosigners) = new_multi_party_claim::<T>(&caller, c, c + 1);
+		let approvers = cosigners[..cosigners.len() - 1].iter().chain([&caller]);
+		for approver in approvers {
+			assert!(Pallet::<T>::approve_revocation(RawOrigin::Signed(approver.clone()).into(), claim.clone()).is_ok());
+		}
+	}: _(RawOrigin::Signed(caller), claim.clone())
+	verify {
+		assert!(!Proofs::<T>::contains_key(&claim));
+	}
+
+	impl_benchmark_test_suite!(PoeModule, crate::mock::new_bench_ext(), crate::mock::Test);
 }
 /* 
 //! Benchmarking setup for pallet-template
@@ -34,7 +179,9 @@ use super::*;
 
 #[allow(unused)]
 use frame_benchmarking::v2::*;
+use frame_support::traits::UnixTime;
 use frame_system::RawOrigin;
+use sp_core::ed25519;
 
 #[benchmarks]
 mod benchmarks {
@@ -58,7 +205,9 @@ use super::*;
 
 #[allow(unused)]
 use frame_benchmarking::{benchmarks, whitelisted_caller};
+use frame_support::traits::UnixTime;
 use frame_system::RawOrigin;
+use sp_core::ed25519;
 
 benchmarks! {
 	create_claim {
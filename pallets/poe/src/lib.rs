@@ -1,8 +1,8 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 pub use pallet::*;
 pub use weights::WeightInfo;
-#[cfg(test)]
-mod mock;
+#[cfg(any(test, feature = "fuzzing"))]
+pub mod mock;
 
 #[cfg(test)]
 mod tests;
@@ -12,6 +12,65 @@ mod benchmarking;
 
 pub mod weights;
 
+use sp_core::crypto::KeyTypeId;
+
+/// Key type of the accounts the offchain worker submits TSA attestations with.
+pub const KEY_TYPE: KeyTypeId = KeyTypeId(*b"poe!");
+
+/// Offchain storage key holding the URL of the TSA endpoint, as raw UTF-8.
+///
+/// The offchain worker does nothing until it is set, either with the node's `--poe-tsa-endpoint`
+/// flag or through the `offchain_localStorageSet` RPC.
+pub const TSA_ENDPOINT_KEY: &[u8] = b"poe::tsa-endpoint";
+
+const LOG_TARGET: &str = "runtime::poe";
+
+/// Offchain storage key of the metadata indexed for `claim`.
+pub fn metadata_key(claim: &[u8]) -> sp_std::vec::Vec<u8> {
+	use codec::Encode;
+	(b"poe::metadata", claim).encode()
+}
+
+/// Signs the payloads of `create_claim_signed` in benchmarks.
+#[cfg(feature = "runtime-benchmarks")]
+pub trait BenchmarkHelper<Public, Signature> {
+	/// Returns a new key and its signature over `message`.
+	fn sign(message: &[u8]) -> (Public, Signature);
+}
+
+/// Signs with a new sr25519 key from the keystore.
+#[cfg(feature = "runtime-benchmarks")]
+impl<Public, Signature> BenchmarkHelper<Public, Signature> for ()
+where
+	Public: From<sp_core::sr25519::Public>,
+	Signature: From<sp_core::sr25519::Signature>,
+{
+	fn sign(message: &[u8]) -> (Public, Signature) {
+		let public = sp_io::crypto::sr25519_generate(KEY_TYPE, None);
+		let signature = sp_io::crypto::sr25519_sign(KEY_TYPE, &public, message)
+			.expect("the key was just generated; qed");
+		(public.into(), signature.into())
+	}
+}
+
+pub mod crypto {
+	use super::KEY_TYPE;
+	use sp_runtime::{
+		app_crypto::{app_crypto, sr25519},
+		MultiSignature, MultiSigner,
+	};
+	app_crypto!(sr25519, KEY_TYPE);
+
+	/// Signs the offchain worker's `submit_attestation` transactions.
+	pub struct PoeAuthId;
+
+	impl frame_system::offchain::AppCrypto<MultiSigner, MultiSignature> for PoeAuthId {
+		type RuntimeAppPublic = Public;
+		type GenericSignature = sp_core::sr25519::Signature;
+		type GenericPublic = sp_core::sr25519::Public;
+	}
+}
+
 #[frame_support::pallet]
 pub mod pallet {
 	pub use super::*;
@@ -20,11 +79,125 @@ pub mod pallet {
 	pub use sp_std::prelude::*;
 	use super::WeightInfo;
 
+	use frame_support::traits::{Contains, UnixTime};
+	use frame_system::offchain::{
+		AppCrypto, CreateSignedTransaction, SendSignedTransaction, Signer,
+	};
+	use sp_core::ed25519;
+	use sp_io::hashing::blake2_256;
+	use sp_runtime::{
+		offchain::{http, storage::StorageValueRef, Duration, StorageKind},
+		traits::{IdentifyAccount, Verify, Zero},
+	};
+
+	/// Claims the offchain worker sends to the TSA per block.
+	const MAX_ATTESTATIONS_PER_BLOCK: usize = 5;
+	/// Pending claims `on_initialize` checks for an expired attestation window per block.
+	const MAX_EXPIRY_CHECKS_PER_BLOCK: usize = 10;
+	/// Blocks to wait before sending the same claim to the TSA again.
+	const ATTESTATION_RETRY_BLOCKS: u32 = 10;
+	/// Offchain storage key of the claim the offchain worker continues after.
+	const TSA_CURSOR_KEY: &[u8] = b"poe::tsa-cursor";
+	/// Offchain storage key of the hashes of the claims recently sent to the TSA, and when.
+	const TSA_SENT_KEY: &[u8] = b"poe::tsa-sent";
+	/// How long the offchain worker waits for the TSA to answer.
+	const TSA_TIMEOUT_MS: u64 = 2_000;
+
+	/// The current storage version.
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
 	#[pallet::pallet]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	pub struct Pallet<T>(_);
 
+	/// A timestamp authority's signed statement that it saw a claim at `timestamp`.
+	///
+	/// The TSA answers the offchain worker's request with this token, SCALE encoded. This is a
+	/// format of this pallet, not an RFC 3161 timestamp token, which the runtime could not
+	/// verify without an ASN.1 and X.509 stack. Using a real RFC 3161 TSA therefore takes a
+	/// gateway at the TSA endpoint that gets the TSA's token, checks it, and answers with a
+	/// `TsaToken` signed by one of the gateway's keys in `TsaKeys`.
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub struct TsaToken {
+		/// The TSA key that signed the token.
+		pub key: ed25519::Public,
+		/// When the TSA saw the claim, in milliseconds since the Unix epoch.
+		pub timestamp: u64,
+		/// Signature over [`TsaToken::payload`].
+		pub signature: ed25519::Signature,
+	}
+
+	impl TsaToken {
+		/// The message a TSA signs to attest `claim`: the claim's blake2-256 hash and the
+		/// timestamp, SCALE encoded.
+		pub fn payload(claim: &[u8], timestamp: u64) -> Vec<u8> {
+			(blake2_256(claim), timestamp).encode()
+		}
+
+		/// Whether the token's signature over `claim` is valid for its key.
+		pub fn verify(&self, claim: &[u8]) -> bool {
+			sp_io::crypto::ed25519_verify(
+				&self.signature,
+				&Self::payload(claim, self.timestamp),
+				&self.key,
+			)
+		}
+	}
+
+	/// Describes the document behind a claim. Kept in the offchain database rather than in state,
+	/// see [`metadata_key`].
+	#[derive(
+		Encode, Decode, CloneNoBound, PartialEqNoBound, EqNoBound, RuntimeDebugNoBound, TypeInfo,
+	)]
+	#[scale_info(skip_type_params(S))]
+	pub struct ClaimMetadata<S: Get<u32>> {
+		pub filename: BoundedVec<u8, S>,
+		pub mime_type: BoundedVec<u8, S>,
+	}
+
+	impl<S: Get<u32>> ClaimMetadata<S> {
+		/// The combined length of the filename and MIME type, which `create_claim` is weighed by.
+		pub fn content_len(&self) -> u32 {
+			(self.filename.len() + self.mime_type.len()) as u32
+		}
+	}
+
+	/// A claim made together by its creator, the owner in `Proofs`, and the co-signers they
+	/// invited.
+	#[derive(
+		Encode,
+		Decode,
+		CloneNoBound,
+		PartialEqNoBound,
+		EqNoBound,
+		RuntimeDebugNoBound,
+		TypeInfo,
+		MaxEncodedLen,
+	)]
+	#[scale_info(skip_type_params(T))]
+	pub struct MultiPartyClaim<T: Config> {
+		/// The invited co-signers, and whether each has signed.
+		pub cosigners: BoundedVec<(T::AccountId, bool), T::MaxCosigners>,
+		/// Number of parties that must approve revoking the claim.
+		pub revoke_threshold: u32,
+		/// Number of parties that approved revoking the claim.
+		pub revoke_approvals: u32,
+	}
+
+	/// Who signed a multi-party claim so far, as returned by [`PoeApi::signature_status`].
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+	pub struct SignatureStatus<AccountId> {
+		pub creator: AccountId,
+		pub signed: Vec<AccountId>,
+		pub pending: Vec<AccountId>,
+		/// Whether every co-signer signed.
+		pub fully_signed: bool,
+		pub revoke_threshold: u32,
+		pub revoke_approvals: u32,
+	}
+
 	#[pallet::config]
-	pub trait Config: frame_system::Config {
+	pub trait Config: CreateSignedTransaction<Call<Self>> + frame_system::Config {
 		/// Because this pallet emits events, it depends on the runtime's definition of an event.
 		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
 		/// Type representing the weight of this pallet
@@ -32,6 +205,43 @@ pub mod pallet {
 		type MaxClaimLength: Get<u32>;
 
 		type WeightInfo: WeightInfo;
+
+		/// Maximum length of a claim's filename and MIME type.
+		#[pallet::constant]
+		type MaxMetadataLength: Get<u32>;
+
+		/// Key the offchain worker signs attestation transactions with.
+		type AuthorityId: AppCrypto<Self::Public, Self::Signature>;
+		/// Origin allowed to add and remove TSA keys.
+		type TsaOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+		/// Maximum number of trusted TSA keys.
+		#[pallet::constant]
+		type MaxTsaKeys: Get<u32>;
+
+		/// Signature over the payload of `create_claim_signed`.
+		type OffchainSignature: Verify<Signer = Self::OffchainPublic> + Parameter;
+		/// Key the owners of `create_claim_signed` claims sign with.
+		type OffchainPublic: IdentifyAccount<AccountId = Self::AccountId>;
+
+		/// Maximum number of co-signers of a multi-party claim.
+		#[pallet::constant]
+		type MaxCosigners: Get<u32>;
+
+		/// The time claims are created at, to check attestations against.
+		type UnixTime: UnixTime;
+		/// How far, in milliseconds, a TSA token's timestamp may be from the time its claim was
+		/// created.
+		#[pallet::constant]
+		type AttestationWindow: Get<u64>;
+
+		/// Claims that only other pallets may create, through [`Pallet::do_create_claim`]. The
+		/// calls of this pallet reject them, so nobody can claim them first, and their owners
+		/// can't revoke or transfer them behind the back of the pallet they are reserved for.
+		type ReservedClaims: Contains<BoundedVec<u8, Self::MaxClaimLength>>;
+
+		/// Signs the payloads of `create_claim_signed` in benchmarks.
+		#[cfg(feature = "runtime-benchmarks")]
+		type BenchmarkHelper: crate::BenchmarkHelper<Self::OffchainPublic, Self::OffchainSignature>;
 	}
 
 	#[pallet::storage]
@@ -43,11 +253,76 @@ pub mod pallet {
 		(T::AccountId, T::BlockNumber),
 	>;
 
+	/// Keys of the timestamp authorities whose tokens are accepted.
+	#[pallet::storage]
+	#[pallet::getter(fn tsa_keys)]
+	pub type TsaKeys<T: Config> =
+		StorageValue<_, BoundedVec<ed25519::Public, T::MaxTsaKeys>, ValueQuery>;
+
+	/// Claims waiting for the offchain worker to get them attested, and when each was created in
+	/// milliseconds since the Unix epoch.
+	///
+	/// Only claims made while at least one TSA key is registered are queued. They are dropped
+	/// once `AttestationWindow` has passed, as no token could be accepted for them any more.
+	#[pallet::storage]
+	pub type PendingAttestations<T: Config> =
+		StorageMap<_, Blake2_128Concat, BoundedVec<u8, T::MaxClaimLength>, u64>;
+
+	/// The pending claim `on_initialize` checked last, which the next block continues after.
+	#[pallet::storage]
+	pub type ExpiryCursor<T: Config> = StorageValue<_, BoundedVec<u8, T::MaxClaimLength>>;
+
+	/// The TSA token of each attested claim.
+	#[pallet::storage]
+	#[pallet::getter(fn attestation)]
+	pub type Attestations<T: Config> =
+		StorageMap<_, Blake2_128Concat, BoundedVec<u8, T::MaxClaimLength>, TsaToken>;
+
+	/// The nonce each account's next `create_claim_signed` payload must be signed with.
+	#[pallet::storage]
+	#[pallet::getter(fn claim_nonce)]
+	pub type ClaimNonces<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, u64, ValueQuery>;
+
+	/// The co-signers and revocation threshold of multi-party claims.
+	#[pallet::storage]
+	#[pallet::getter(fn multi_party_claim)]
+	pub type MultiPartyClaims<T: Config> =
+		StorageMap<_, Blake2_128Concat, BoundedVec<u8, T::MaxClaimLength>, MultiPartyClaim<T>>;
+
+	/// The parties that approved revoking each multi-party claim.
+	#[pallet::storage]
+	pub type RevocationApprovals<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		BoundedVec<u8, T::MaxClaimLength>,
+		Blake2_128Concat,
+		T::AccountId,
+		(),
+	>;
+
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
 	pub enum Event<T: Config> {
 		ClaimCreated(T::AccountId, BoundedVec<u8, T::MaxClaimLength>),
 		ClaimRevoked(T::AccountId, BoundedVec<u8, T::MaxClaimLength>),
+		/// A claim was attested by the TSA key at the given timestamp.
+		ClaimAttested(BoundedVec<u8, T::MaxClaimLength>, ed25519::Public, u64),
+		TsaKeyAdded(ed25519::Public),
+		TsaKeyRemoved(ed25519::Public),
+		/// The creator of a multi-party claim invited its co-signers.
+		CosignersInvited(
+			BoundedVec<u8, T::MaxClaimLength>,
+			BoundedVec<T::AccountId, T::MaxCosigners>,
+		),
+		/// A co-signer signed a multi-party claim.
+		ClaimCosigned(T::AccountId, BoundedVec<u8, T::MaxClaimLength>),
+		/// Every co-signer of a multi-party claim signed it.
+		ClaimFullySigned(BoundedVec<u8, T::MaxClaimLength>),
+		/// A party of a multi-party claim approved revoking it.
+		RevocationApproved(T::AccountId, BoundedVec<u8, T::MaxClaimLength>),
+		/// A claim stopped waiting for attestation, as its `AttestationWindow` passed.
+		AttestationExpired(BoundedVec<u8, T::MaxClaimLength>),
 	}
 
 	#[pallet::error]
@@ -56,26 +331,88 @@ pub mod pallet {
 		ClaimTooLong,
 		ClaimNotExist,
 		NotClaimOwner,
+		/// The claim already has a TSA token.
+		AlreadyAttested,
+		/// The token is signed by a key that is not in `TsaKeys`.
+		UnknownTsaKey,
+		/// The token's signature does not match the claim.
+		BadTsaSignature,
+		/// The claim was made while no TSA key was registered, so it is not awaiting attestation.
+		NotAwaitingAttestation,
+		/// The token's timestamp is more than `AttestationWindow` away from the claim's creation.
+		AttestationOutOfWindow,
+		TsaKeyAlreadyAdded,
+		TooManyTsaKeys,
+		/// The signature is not the signer's over the claim and the signer's current nonce.
+		BadClaimSignature,
+		/// Co-signers must be distinct, non-empty and not include the creator.
+		InvalidCosigners,
+		/// The revocation threshold must be between one and the number of parties.
+		InvalidRevokeThreshold,
+		/// The claim is not a multi-party claim.
+		NotMultiPartyClaim,
+		/// Multi-party claims can only be revoked with `approve_revocation`, and not transferred.
+		IsMultiPartyClaim,
+		/// The caller was not invited to co-sign the claim.
+		NotCosigner,
+		AlreadyCosigned,
+		/// Only the creator and co-signers that signed can approve revoking the claim.
+		NotSigner,
+		AlreadyApproved,
+		/// The claim is reserved for another pallet.
+		ClaimReserved,
+		/// Every co-signer signed the claim, so it can only be revoked with `approve_revocation`.
+		FullySigned,
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_initialize(_n: T::BlockNumber) -> Weight {
+			let now = T::UnixTime::now().as_millis() as u64;
+			let (pending, next) = Self::pending_attestations_after(
+				ExpiryCursor::<T>::get(),
+				MAX_EXPIRY_CHECKS_PER_BLOCK,
+			);
+			let checked = pending.len() as u64;
+			let mut expired = 0u64;
+			for (claim, created) in pending {
+				if Self::attestation_expired(created, now) {
+					PendingAttestations::<T>::remove(&claim);
+					Self::deposit_event(Event::AttestationExpired(claim));
+					expired += 1;
+				}
+			}
+			ExpiryCursor::<T>::set(next);
+			T::DbWeight::get().reads_writes(checked + 1, expired + 1)
+		}
+
+		fn offchain_worker(now: T::BlockNumber) {
+			if let Err(e) = Self::attest_pending_claims(now) {
+				log::warn!(target: LOG_TARGET, "Skipped TSA attestations: {}", e);
+			}
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn try_state(_n: T::BlockNumber) -> Result<(), &'static str> {
+			Self::do_try_state()
+		}
 	}
 
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
 		#[pallet::call_index(0)]
-		#[pallet::weight(T::WeightInfo::create_claim(claim.len() as u32))]
+		#[pallet::weight(T::WeightInfo::create_claim(
+			claim.len() as u32,
+			metadata.as_ref().map_or(0, ClaimMetadata::content_len),
+		))]
 		pub fn create_claim(
 			origin: OriginFor<T>,
 			claim: BoundedVec<u8, T::MaxClaimLength>,
+			metadata: Option<ClaimMetadata<T::MaxMetadataLength>>,
 		) -> DispatchResultWithPostInfo {
 			let sender = ensure_signed(origin)?;
-			ensure!(!Proofs::<T>::contains_key(&claim), Error::<T>::ProofAlreadyExist);
-			// Update storage.
-			Proofs::<T>::insert(
-				&claim,
-				(sender.clone(), frame_system::Pallet::<T>::block_number()),
-			);
-
-			// Emit an event.
-			Self::deposit_event(Event::ClaimCreated(sender, claim));
+			ensure!(!T::ReservedClaims::contains(&claim), Error::<T>::ClaimReserved);
+			Self::do_create_claim(sender, claim, metadata)?;
 			// Return a successful DispatchResultWithPostInfo
 			Ok(().into())
 		}
@@ -88,10 +425,11 @@ pub mod pallet {
 			claim: BoundedVec<u8, T::MaxClaimLength>,
 		) -> DispatchResultWithPostInfo {
 			let sender = ensure_signed(origin)?;
+			ensure!(!T::ReservedClaims::contains(&claim), Error::<T>::ClaimReserved);
 			let (owner, _) = Proofs::<T>::get(&claim).ok_or(Error::<T>::ClaimNotExist)?;
 			ensure!(owner == sender, Error::<T>::NotClaimOwner);
-			Proofs::<T>::remove(&claim);
-			Self::deposit_event(Event::ClaimRevoked(sender, claim));
+			ensure!(!MultiPartyClaims::<T>::contains_key(&claim), Error::<T>::IsMultiPartyClaim);
+			Self::remove_claim(sender, claim);
 			Ok(().into())
 		}
 
@@ -103,10 +441,428 @@ pub mod pallet {
 			dest: T::AccountId,
 		) -> DispatchResultWithPostInfo {
 			let sender = ensure_signed(origin)?;
+			ensure!(!T::ReservedClaims::contains(&claim), Error::<T>::ClaimReserved);
 			let (owner, _) = Proofs::<T>::get(&claim).ok_or(Error::<T>::ClaimNotExist)?;
 			ensure!(owner == sender, Error::<T>::NotClaimOwner);
+			ensure!(!MultiPartyClaims::<T>::contains_key(&claim), Error::<T>::IsMultiPartyClaim);
 			Proofs::<T>::insert(&claim, (dest.clone(), frame_system::Pallet::<T>::block_number()));
 			Ok(().into())
 		}
+
+		/// Store a TSA token for `claim`. Submitted by the offchain worker, but anybody holding
+		/// a valid token may submit it.
+		///
+		/// The token's timestamp must be within `AttestationWindow` of the time the claim was
+		/// created, so that a token can't date a claim long before or after it was made.
+		#[pallet::call_index(3)]
+		#[pallet::weight(T::WeightInfo::submit_attestation())]
+		pub fn submit_attestation(
+			origin: OriginFor<T>,
+			claim: BoundedVec<u8, T::MaxClaimLength>,
+			token: TsaToken,
+		) -> DispatchResultWithPostInfo {
+			ensure_signed(origin)?;
+			ensure!(Proofs::<T>::contains_key(&claim), Error::<T>::ClaimNotExist);
+			ensure!(!Attestations::<T>::contains_key(&claim), Error::<T>::AlreadyAttested);
+			ensure!(TsaKeys::<T>::get().contains(&token.key), Error::<T>::UnknownTsaKey);
+			ensure!(token.verify(&claim), Error::<T>::BadTsaSignature);
+			let created =
+				PendingAttestations::<T>::get(&claim).ok_or(Error::<T>::NotAwaitingAttestation)?;
+			ensure!(
+				token.timestamp.abs_diff(created) <= T::AttestationWindow::get(),
+				Error::<T>::AttestationOutOfWindow
+			);
+
+			PendingAttestations::<T>::remove(&claim);
+			Attestations::<T>::insert(&claim, &token);
+			Self::deposit_event(Event::ClaimAttested(claim, token.key, token.timestamp));
+			Ok(().into())
+		}
+
+		#[pallet::call_index(4)]
+		#[pallet::weight(T::WeightInfo::add_tsa_key())]
+		pub fn add_tsa_key(origin: OriginFor<T>, key: ed25519::Public) -> DispatchResult {
+			T::TsaOrigin::ensure_origin(origin)?;
+			TsaKeys::<T>::try_mutate(|keys| {
+				ensure!(!keys.contains(&key), Error::<T>::TsaKeyAlreadyAdded);
+				keys.try_push(key).map_err(|_| Error::<T>::TooManyTsaKeys)
+			})?;
+			Self::deposit_event(Event::TsaKeyAdded(key));
+			Ok(())
+		}
+
+		/// Stop accepting tokens from `key`. Tokens already stored stay.
+		#[pallet::call_index(5)]
+		#[pallet::weight(T::WeightInfo::remove_tsa_key())]
+		pub fn remove_tsa_key(origin: OriginFor<T>, key: ed25519::Public) -> DispatchResult {
+			T::TsaOrigin::ensure_origin(origin)?;
+			TsaKeys::<T>::try_mutate(|keys| {
+				let index = keys.iter().position(|k| k == &key).ok_or(Error::<T>::UnknownTsaKey)?;
+				keys.remove(index);
+				Ok::<_, Error<T>>(())
+			})?;
+			Self::deposit_event(Event::TsaKeyRemoved(key));
+			Ok(())
+		}
+
+		/// Create `claim` on behalf of `signer`, who signed [`Pallet::claim_payload`] off-chain.
+		/// Lets any account submit, and pay for, the claims of another.
+		#[pallet::call_index(6)]
+		#[pallet::weight(T::WeightInfo::create_claim_signed(claim.len() as u32))]
+		pub fn create_claim_signed(
+			origin: OriginFor<T>,
+			claim: BoundedVec<u8, T::MaxClaimLength>,
+			signer: T::AccountId,
+			signature: T::OffchainSignature,
+		) -> DispatchResultWithPostInfo {
+			ensure_signed(origin)?;
+			ensure!(!T::ReservedClaims::contains(&claim), Error::<T>::ClaimReserved);
+			let nonce = ClaimNonces::<T>::get(&signer);
+			let payload = Self::claim_payload(&claim, nonce);
+			ensure!(signature.verify(&payload[..], &signer), Error::<T>::BadClaimSignature);
+
+			Self::do_create_claim(signer.clone(), claim, None)?;
+			// The signature can't be replayed once the nonce moved on.
+			ClaimNonces::<T>::insert(&signer, nonce.saturating_add(1));
+			Ok(().into())
+		}
+
+		/// Create `claim` and invite `cosigners` to sign it. It is fully signed once every
+		/// co-signer called `cosign_claim`, and revoked once `revoke_threshold` of the parties,
+		/// the caller included, called `approve_revocation`. Until it is fully signed, the caller
+		/// can give up on the co-signers with `withdraw_claim`.
+		#[pallet::call_index(7)]
+		#[pallet::weight(
+			T::WeightInfo::create_multi_party_claim(claim.len() as u32, cosigners.len() as u32)
+		)]
+		pub fn create_multi_party_claim(
+			origin: OriginFor<T>,
+			claim: BoundedVec<u8, T::MaxClaimLength>,
+			cosigners: BoundedVec<T::AccountId, T::MaxCosigners>,
+			revoke_threshold: u32,
+		) -> DispatchResultWithPostInfo {
+			let sender = ensure_signed(origin)?;
+			ensure!(!T::ReservedClaims::contains(&claim), Error::<T>::ClaimReserved);
+			let mut distinct = cosigners.to_vec();
+			distinct.sort();
+			distinct.dedup();
+			ensure!(
+				!cosigners.is_empty() &&
+					distinct.len() == cosigners.len() &&
+					!cosigners.contains(&sender),
+				Error::<T>::InvalidCosigners
+			);
+			let parties = cosigners.len() as u32 + 1;
+			ensure!((1..=parties).contains(&revoke_threshold), Error::<T>::InvalidRevokeThreshold);
+
+			Self::do_create_claim(sender, claim.clone(), None)?;
+			let invited = cosigners.iter().map(|cosigner| (cosigner.clone(), false)).collect();
+			MultiPartyClaims::<T>::insert(
+				&claim,
+				MultiPartyClaim {
+					cosigners: BoundedVec::truncate_from(invited),
+					revoke_threshold,
+					revoke_approvals: 0,
+				},
+			);
+			Self::deposit_event(Event::CosignersInvited(claim, cosigners));
+			Ok(().into())
+		}
+
+		/// Sign a multi-party claim the caller was invited to.
+		#[pallet::call_index(8)]
+		#[pallet::weight(T::WeightInfo::cosign_claim(T::MaxCosigners::get()))]
+		pub fn cosign_claim(
+			origin: OriginFor<T>,
+			claim: BoundedVec<u8, T::MaxClaimLength>,
+		) -> DispatchResultWithPostInfo {
+			let sender = ensure_signed(origin)?;
+			let fully_signed = MultiPartyClaims::<T>::try_mutate(&claim, |info| {
+				let info = info.as_mut().ok_or(Error::<T>::NotMultiPartyClaim)?;
+				let (_, signed) = info
+					.cosigners
+					.iter_mut()
+					.find(|(cosigner, _)| *cosigner == sender)
+					.ok_or(Error::<T>::NotCosigner)?;
+				ensure!(!*signed, Error::<T>::AlreadyCosigned);
+				*signed = true;
+				Ok::<_, Error<T>>(info.cosigners.iter().all(|(_, signed)| *signed))
+			})?;
+
+			Self::deposit_event(Event::ClaimCosigned(sender, claim.clone()));
+			if fully_signed {
+				Self::deposit_event(Event::ClaimFullySigned(claim));
+			}
+			Ok(().into())
+		}
+
+		/// Approve revoking a multi-party claim. The claim is revoked by the approval that
+		/// reaches its threshold.
+		#[pallet::call_index(9)]
+		#[pallet::weight(T::WeightInfo::approve_revocation(T::MaxCosigners::get()))]
+		pub fn approve_revocation(
+			origin: OriginFor<T>,
+			claim: BoundedVec<u8, T::MaxClaimLength>,
+		) -> DispatchResultWithPostInfo {
+			let sender = ensure_signed(origin)?;
+			let mut info =
+				MultiPartyClaims::<T>::get(&claim).ok_or(Error::<T>::NotMultiPartyClaim)?;
+			let (creator, _) = Proofs::<T>::get(&claim).ok_or(Error::<T>::ClaimNotExist)?;
+			ensure!(
+				sender == creator ||
+					info.cosigners
+						.iter()
+						.any(|(cosigner, signed)| *cosigner == sender && *signed),
+				Error::<T>::NotSigner
+			);
+			ensure!(
+				!RevocationApprovals::<T>::contains_key(&claim, &sender),
+				Error::<T>::AlreadyApproved
+			);
+
+			info.revoke_approvals += 1;
+			Self::deposit_event(Event::RevocationApproved(sender.clone(), claim.clone()));
+			if info.revoke_approvals < info.revoke_threshold {
+				RevocationApprovals::<T>::insert(&claim, &sender, ());
+				MultiPartyClaims::<T>::insert(&claim, info);
+				return Ok(().into())
+			}
+
+			Self::remove_multi_party_claim(creator, claim);
+			Ok(().into())
+		}
+
+		/// Revoke a multi-party claim the caller created and not every co-signer signed, e.g.
+		/// because some of them never will.
+		#[pallet::call_index(10)]
+		#[pallet::weight(T::WeightInfo::withdraw_claim(T::MaxCosigners::get()))]
+		pub fn withdraw_claim(
+			origin: OriginFor<T>,
+			claim: BoundedVec<u8, T::MaxClaimLength>,
+		) -> DispatchResultWithPostInfo {
+			let sender = ensure_signed(origin)?;
+			let info = MultiPartyClaims::<T>::get(&claim).ok_or(Error::<T>::NotMultiPartyClaim)?;
+			let (creator, _) = Proofs::<T>::get(&claim).ok_or(Error::<T>::ClaimNotExist)?;
+			ensure!(sender == creator, Error::<T>::NotClaimOwner);
+			ensure!(!info.cosigners.iter().all(|(_, signed)| *signed), Error::<T>::FullySigned);
+
+			Self::remove_multi_party_claim(creator, claim);
+			Ok(().into())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// The message the owner of `claim` signs for `create_claim_signed`: the genesis hash, the
+		/// claim and the owner's `nonce`, SCALE encoded. The genesis hash keeps signatures from
+		/// being replayed on other chains.
+		pub fn claim_payload(claim: &[u8], nonce: u64) -> Vec<u8> {
+			let genesis_hash = frame_system::Pallet::<T>::block_hash(T::BlockNumber::zero());
+			(genesis_hash, claim, nonce).encode()
+		}
+
+		/// Record `claim` as owned by `owner`, failing if it already exists.
+		///
+		/// Lets other pallets anchor hashes as claims on behalf of an account. Doesn't check
+		/// `ReservedClaims`, so that the pallets they are reserved for can create them.
+		pub fn do_create_claim(
+			owner: T::AccountId,
+			claim: BoundedVec<u8, T::MaxClaimLength>,
+			metadata: Option<ClaimMetadata<T::MaxMetadataLength>>,
+		) -> DispatchResult {
+			ensure!(!Proofs::<T>::contains_key(&claim), Error::<T>::ProofAlreadyExist);
+			// Update storage.
+			Proofs::<T>::insert(&claim, (owner.clone(), frame_system::Pallet::<T>::block_number()));
+			if !TsaKeys::<T>::get().is_empty() {
+				let now = T::UnixTime::now().as_millis() as u64;
+				PendingAttestations::<T>::insert(&claim, now);
+			}
+			// Only nodes running with `--enable-offchain-indexing true` keep it.
+			if let Some(metadata) = metadata {
+				sp_io::offchain_index::set(&metadata_key(&claim), &metadata.encode());
+			}
+
+			// Emit an event.
+			Self::deposit_event(Event::ClaimCreated(owner, claim));
+			Ok(())
+		}
+
+		/// Removes `claim` of `owner` along with its attestation and metadata.
+		fn remove_claim(owner: T::AccountId, claim: BoundedVec<u8, T::MaxClaimLength>) {
+			Proofs::<T>::remove(&claim);
+			PendingAttestations::<T>::remove(&claim);
+			Attestations::<T>::remove(&claim);
+			sp_io::offchain_index::clear(&metadata_key(&claim));
+			Self::deposit_event(Event::ClaimRevoked(owner, claim));
+		}
+
+		/// Removes the multi-party claim `claim` of `creator` along with its revocation approvals.
+		fn remove_multi_party_claim(
+			creator: T::AccountId,
+			claim: BoundedVec<u8, T::MaxClaimLength>,
+		) {
+			MultiPartyClaims::<T>::remove(&claim);
+			let _ =
+				RevocationApprovals::<T>::clear_prefix(&claim, T::MaxCosigners::get() + 1, None);
+			Self::remove_claim(creator, claim);
+		}
+
+		/// Who signed the multi-party claim `claim`, or `None` if it is not one.
+		pub fn signature_status(claim: &[u8]) -> Option<SignatureStatus<T::AccountId>> {
+			let claim = BoundedVec::<u8, T::MaxClaimLength>::try_from(claim.to_vec()).ok()?;
+			let info = MultiPartyClaims::<T>::get(&claim)?;
+			let (creator, _) = Proofs::<T>::get(&claim)?;
+			let (signed, pending): (Vec<_>, Vec<_>) =
+				info.cosigners.into_iter().partition(|(_, signed)| *signed);
+			Some(SignatureStatus {
+				creator,
+				fully_signed: pending.is_empty(),
+				signed: signed.into_iter().map(|(cosigner, _)| cosigner).collect(),
+				pending: pending.into_iter().map(|(cosigner, _)| cosigner).collect(),
+				revoke_threshold: info.revoke_threshold,
+				revoke_approvals: info.revoke_approvals,
+			})
+		}
+
+		/// Sends pending claims to the TSA and submits the tokens it returns.
+		fn attest_pending_claims(now: T::BlockNumber) -> Result<(), &'static str> {
+			let endpoint =
+				sp_io::offchain::local_storage_get(StorageKind::PERSISTENT, TSA_ENDPOINT_KEY);
+			// Attestation is not set up on this node.
+			let Some(endpoint) = endpoint else { return Ok(()) };
+			let endpoint =
+				sp_std::str::from_utf8(&endpoint).map_err(|_| "TSA endpoint is not UTF-8")?;
+			let signer = Signer::<T, T::AuthorityId>::any_account();
+			if !signer.can_sign() {
+				return Err("no `poe!` key in the keystore")
+			}
+
+			// Continue where the last block stopped, so that every pending claim gets its turn.
+			let cursor = StorageValueRef::persistent(TSA_CURSOR_KEY);
+			let (pending, next) = Self::pending_attestations_after(
+				cursor.get().ok().flatten(),
+				MAX_ATTESTATIONS_PER_BLOCK,
+			);
+			match next {
+				Some(claim) => cursor.set(&claim),
+				None => cursor.clear(),
+			}
+
+			// Don't ask again while an earlier answer may still be on its way on chain. Entries
+			// are forgotten after the retry delay, which keeps the list short.
+			let sent = StorageValueRef::persistent(TSA_SENT_KEY);
+			let mut recently_sent =
+				sent.get::<Vec<([u8; 32], T::BlockNumber)>>().ok().flatten().unwrap_or_default();
+			recently_sent.retain(|(_, at)| now < *at + ATTESTATION_RETRY_BLOCKS.into());
+			let unix_now = sp_io::offchain::timestamp().unix_millis();
+			let claims = pending
+				.into_iter()
+				.filter(|(claim, created)| {
+					!Self::attestation_expired(*created, unix_now) &&
+						!recently_sent.iter().any(|(hash, _)| *hash == blake2_256(claim))
+				})
+				.map(|(claim, _)| claim)
+				.collect::<Vec<_>>();
+			recently_sent.extend(claims.iter().map(|claim| (blake2_256(claim), now)));
+			sent.set(&recently_sent);
+
+			for claim in claims {
+				let token = match Self::fetch_tsa_token(endpoint, &claim) {
+					Ok(token) => token,
+					Err(e) => {
+						log::warn!(target: LOG_TARGET, "TSA request failed: {:?}", e);
+						continue
+					},
+				};
+				let result = signer.send_signed_transaction(|_| Call::submit_attestation {
+					claim: claim.clone(),
+					token: token.clone(),
+				});
+				if let Some((_, Err(()))) = result {
+					log::warn!(target: LOG_TARGET, "Failed to submit a TSA attestation");
+				}
+			}
+			Ok(())
+		}
+
+		/// Up to `max` pending claims with their creation times, starting after `cursor`, and the
+		/// cursor to continue after. The cursor is `None` once the end is reached, so that the
+		/// next call starts over.
+		fn pending_attestations_after(
+			cursor: Option<BoundedVec<u8, T::MaxClaimLength>>,
+			max: usize,
+		) -> (
+			Vec<(BoundedVec<u8, T::MaxClaimLength>, u64)>,
+			Option<BoundedVec<u8, T::MaxClaimLength>>,
+		) {
+			let pending = match cursor {
+				Some(claim) => PendingAttestations::<T>::iter_from(
+					PendingAttestations::<T>::hashed_key_for(&claim),
+				),
+				None => PendingAttestations::<T>::iter(),
+			};
+			let pending = pending.take(max).collect::<Vec<_>>();
+			let next =
+				if pending.len() < max { None } else { pending.last().map(|(c, _)| c.clone()) };
+			(pending, next)
+		}
+
+		/// Whether a claim created at `created` can no longer be attested at `now`, both in
+		/// milliseconds since the Unix epoch.
+		fn attestation_expired(created: u64, now: u64) -> bool {
+			now > created.saturating_add(T::AttestationWindow::get())
+		}
+
+		/// POSTs the claim's blake2-256 hash to the TSA and decodes the [`TsaToken`] it returns.
+		fn fetch_tsa_token(endpoint: &str, claim: &[u8]) -> Result<TsaToken, http::Error> {
+			let deadline = sp_io::offchain::timestamp().add(Duration::from_millis(TSA_TIMEOUT_MS));
+			let pending = http::Request::post(endpoint, vec![blake2_256(claim).to_vec()])
+				.add_header("Content-Type", "application/octet-stream")
+				.deadline(deadline)
+				.send()
+				.map_err(|_| http::Error::IoError)?;
+			let response =
+				pending.try_wait(deadline).map_err(|_| http::Error::DeadlineReached)??;
+			if response.code != 200 {
+				return Err(http::Error::Unknown)
+			}
+
+			let body = response.body().collect::<Vec<u8>>();
+			TsaToken::decode(&mut &body[..]).map_err(|_| http::Error::Unknown)
+		}
+
+		/// Checks that no claim was made after the current block and that only existing claims are
+		/// attested or waiting for it.
+		#[cfg(any(feature = "try-runtime", feature = "fuzzing", test))]
+		pub fn do_try_state() -> Result<(), &'static str> {
+			let now = frame_system::Pallet::<T>::block_number();
+			for (_, (_, block_number)) in Proofs::<T>::iter() {
+				ensure!(block_number <= now, "claim made in a future block");
+			}
+			let attested = Attestations::<T>::iter_keys();
+			for claim in attested.chain(PendingAttestations::<T>::iter_keys()) {
+				ensure!(Proofs::<T>::contains_key(&claim), "attestation of a missing claim");
+			}
+			for (claim, info) in MultiPartyClaims::<T>::iter() {
+				ensure!(Proofs::<T>::contains_key(&claim), "multi-party claim without a proof");
+				ensure!(
+					info.revoke_approvals < info.revoke_threshold,
+					"multi-party claim kept past its revocation threshold"
+				);
+				let approvals = RevocationApprovals::<T>::iter_prefix(&claim).count() as u32;
+				ensure!(approvals == info.revoke_approvals, "revocation approvals out of sync");
+			}
+			Ok(())
+		}
+	}
+}
+
+sp_api::decl_runtime_apis! {
+	/// Lets clients query the signatures of multi-party claims.
+	pub trait PoeApi<AccountId>
+	where
+		AccountId: codec::Codec,
+	{
+		/// Who signed the multi-party claim `claim`, or `None` if it is not one.
+		fn signature_status(claim: sp_std::vec::Vec<u8>) -> Option<SignatureStatus<AccountId>>;
 	}
 }
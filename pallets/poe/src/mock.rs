@@ -1,9 +1,13 @@
 use crate as pallet_poe;
-use frame_support::traits::{ConstU16, ConstU32, ConstU64};
+use frame_support::{
+	traits::{ConstU16, ConstU32, ConstU64, Contains, UnixTime},
+	BoundedVec,
+};
+use frame_system::EnsureRoot;
 use sp_core::H256;
 use sp_runtime::{
-	testing::Header,
-	traits::{BlakeTwo256, IdentityLookup},
+	testing::{Header, TestSignature, TestXt, UintAuthorityId},
+	traits::{BlakeTwo256, Extrinsic as ExtrinsicT, IdentityLookup},
 };
 
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
@@ -48,13 +52,119 @@ impl frame_system::Config for Test {
 	type MaxConsumers = frame_support::traits::ConstU32<16>;
 }
 
+pub type Extrinsic = TestXt<RuntimeCall, ()>;
+
+impl frame_system::offchain::SigningTypes for Test {
+	type Public = UintAuthorityId;
+	type Signature = TestSignature;
+}
+
+impl<LocalCall> frame_system::offchain::SendTransactionTypes<LocalCall> for Test
+where
+	RuntimeCall: From<LocalCall>,
+{
+	type OverarchingCall = RuntimeCall;
+	type Extrinsic = Extrinsic;
+}
+
+impl<LocalCall> frame_system::offchain::CreateSignedTransaction<LocalCall> for Test
+where
+	RuntimeCall: From<LocalCall>,
+{
+	fn create_transaction<C: frame_system::offchain::AppCrypto<Self::Public, Self::Signature>>(
+		call: RuntimeCall,
+		_public: UintAuthorityId,
+		_account: u64,
+		nonce: u64,
+	) -> Option<(RuntimeCall, <Extrinsic as ExtrinsicT>::SignaturePayload)> {
+		Some((call, (nonce, ())))
+	}
+}
+
+/// Signs with the `UintAuthorityId` keys set through `UintAuthorityId::set_all_keys`.
+pub struct TestAuthId;
+
+impl frame_system::offchain::AppCrypto<UintAuthorityId, TestSignature> for TestAuthId {
+	type RuntimeAppPublic = UintAuthorityId;
+	type GenericPublic = UintAuthorityId;
+	type GenericSignature = TestSignature;
+}
+
 impl pallet_poe::Config for Test {
 	type RuntimeEvent = RuntimeEvent;
 	type MaxClaimLength = ConstU32<10>;
 	type WeightInfo = ();
+	type MaxMetadataLength = ConstU32<16>;
+	type AuthorityId = TestAuthId;
+	type TsaOrigin = EnsureRoot<u64>;
+	type MaxTsaKeys = ConstU32<2>;
+	type OffchainSignature = TestSignature;
+	type OffchainPublic = UintAuthorityId;
+	type MaxCosigners = ConstU32<3>;
+	type UnixTime = MockTime;
+	type AttestationWindow = ConstU64<60_000>;
+	type ReservedClaims = ReservedPrefix;
+	#[cfg(feature = "runtime-benchmarks")]
+	type BenchmarkHelper = MockBenchmarkHelper;
+}
+
+/// Signs as account 1.
+#[cfg(feature = "runtime-benchmarks")]
+pub struct MockBenchmarkHelper;
+
+#[cfg(feature = "runtime-benchmarks")]
+impl pallet_poe::BenchmarkHelper<UintAuthorityId, TestSignature> for MockBenchmarkHelper {
+	fn sign(message: &[u8]) -> (UintAuthorityId, TestSignature) {
+		(UintAuthorityId(1), TestSignature(1, message.to_vec()))
+	}
+}
+
+/// Claims starting with this prefix can't be created with the pallet's calls.
+pub const RESERVED: &[u8] = b"r:";
+
+pub struct ReservedPrefix;
+
+impl Contains<BoundedVec<u8, ConstU32<10>>> for ReservedPrefix {
+	fn contains(claim: &BoundedVec<u8, ConstU32<10>>) -> bool {
+		claim.starts_with(RESERVED)
+	}
+}
+
+/// The time the mock starts at, in milliseconds since the Unix epoch.
+pub const NOW: u64 = 1_700_000_000_000;
+
+frame_support::parameter_types! {
+	/// The time in the mock, in milliseconds since the Unix epoch.
+	pub static Now: u64 = NOW;
+}
+
+pub struct MockTime;
+
+impl UnixTime for MockTime {
+	fn now() -> core::time::Duration {
+		core::time::Duration::from_millis(Now::get())
+	}
 }
 
 // Build genesis storage according to the mock runtime.
 pub fn new_test_ext() -> sp_io::TestExternalities {
 	frame_system::GenesisConfig::default().build_storage::<Test>().unwrap().into()
 }
+
+/// Like [`new_test_ext`], with a keystore for the benchmarks to sign TSA tokens with.
+#[cfg(test)]
+pub fn new_bench_ext() -> sp_io::TestExternalities {
+	let mut ext = new_test_ext();
+	ext.register_extension(sp_keystore::KeystoreExt::new(
+		sp_keystore::testing::MemoryKeystore::new(),
+	));
+	ext
+}
+
+/// Runs `test` in a fresh test externalities and checks the pallet's invariants afterwards.
+pub fn build_and_execute(test: impl FnOnce()) {
+	new_test_ext().execute_with(|| {
+		test();
+		PoeModule::do_try_state().unwrap();
+	});
+}
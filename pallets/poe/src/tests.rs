@@ -1,11 +1,29 @@
-use crate::{mock::*, Error, Proofs};
-use frame_support::{assert_noop, assert_ok, BoundedVec};
+use crate::{
+	metadata_key, mock::*, Attestations, ClaimMetadata, Error, Event, MultiPartyClaims,
+	PendingAttestations, Proofs, RevocationApprovals, SignatureStatus, TsaToken, TSA_ENDPOINT_KEY,
+};
+use codec::{Decode, Encode};
+use frame_support::{assert_noop, assert_ok, traits::Hooks, BoundedVec};
+use sp_core::{
+	ed25519,
+	offchain::{
+		testing::{OffchainState, PendingRequest, TestOffchainExt, TestTransactionPoolExt},
+		OffchainDbExt, OffchainWorkerExt, StorageKind, Timestamp, TransactionPoolExt,
+	},
+	Pair,
+};
+use sp_runtime::{
+	testing::{TestSignature, UintAuthorityId},
+	traits::BadOrigin,
+};
+
+type Claim = BoundedVec<u8, <Test as crate::Config>::MaxClaimLength>;
 
 #[test]
 fn claim_works() {
-	new_test_ext().execute_with(|| {
+	build_and_execute(|| {
 		let claim = BoundedVec::try_from(vec![0; 10]).unwrap();
-		assert_ok!(PoeModule::create_claim(RuntimeOrigin::signed(1), claim.clone()));
+		assert_ok!(PoeModule::create_claim(RuntimeOrigin::signed(1), claim.clone(), None));
 		assert_eq!(
 			Proofs::<Test>::get(&claim),
 			Some((1, frame_system::Pallet::<Test>::block_number()))
@@ -15,21 +33,75 @@ fn claim_works() {
 
 #[test]
 fn claim_already_exist() {
-	new_test_ext().execute_with(|| {
+	build_and_execute(|| {
 		let claim = BoundedVec::try_from(vec![0; 10]).unwrap();
-		assert_ok!(PoeModule::create_claim(RuntimeOrigin::signed(1), claim.clone()));
+		assert_ok!(PoeModule::create_claim(RuntimeOrigin::signed(1), claim.clone(), None));
 		assert_noop!(
-			PoeModule::create_claim(RuntimeOrigin::signed(1), claim),
+			PoeModule::create_claim(RuntimeOrigin::signed(1), claim, None),
 			Error::<Test>::ProofAlreadyExist
 		);
 	});
 }
 
+#[test]
+fn reserved_claims_can_only_be_created_by_other_pallets() {
+	build_and_execute(|| {
+		let claim: Claim = BoundedVec::try_from([RESERVED, &[0; 8]].concat()).unwrap();
+		assert_noop!(
+			PoeModule::create_claim(RuntimeOrigin::signed(1), claim.clone(), None),
+			Error::<Test>::ClaimReserved
+		);
+		let signature = TestSignature(1, PoeModule::claim_payload(&claim, 0));
+		assert_noop!(
+			PoeModule::create_claim_signed(RuntimeOrigin::signed(2), claim.clone(), 1, signature),
+			Error::<Test>::ClaimReserved
+		);
+		assert_noop!(
+			PoeModule::create_multi_party_claim(
+				RuntimeOrigin::signed(1),
+				claim.clone(),
+				BoundedVec::try_from(vec![2]).unwrap(),
+				1
+			),
+			Error::<Test>::ClaimReserved
+		);
+
+		assert_ok!(PoeModule::do_create_claim(1, claim.clone(), None));
+		assert_eq!(Proofs::<Test>::get(&claim), Some((1, System::block_number())));
+	});
+}
+
+#[test]
+fn reserved_claims_can_not_be_revoked() {
+	build_and_execute(|| {
+		let claim: Claim = BoundedVec::try_from([RESERVED, &[0; 8]].concat()).unwrap();
+		assert_ok!(PoeModule::do_create_claim(1, claim.clone(), None));
+		assert_noop!(
+			PoeModule::revoke_claim(RuntimeOrigin::signed(1), claim.clone()),
+			Error::<Test>::ClaimReserved
+		);
+		assert_eq!(Proofs::<Test>::get(&claim), Some((1, System::block_number())));
+	});
+}
+
+#[test]
+fn reserved_claims_can_not_be_transferred() {
+	build_and_execute(|| {
+		let claim: Claim = BoundedVec::try_from([RESERVED, &[0; 8]].concat()).unwrap();
+		assert_ok!(PoeModule::do_create_claim(1, claim.clone(), None));
+		assert_noop!(
+			PoeModule::transfer_claim(RuntimeOrigin::signed(1), claim.clone(), 2),
+			Error::<Test>::ClaimReserved
+		);
+		assert_eq!(Proofs::<Test>::get(&claim), Some((1, System::block_number())));
+	});
+}
+
 #[test]
 fn revoke_works() {
-	new_test_ext().execute_with(|| {
+	build_and_execute(|| {
 		let claim = BoundedVec::try_from(vec![0; 10]).unwrap();
-		assert_ok!(PoeModule::create_claim(RuntimeOrigin::signed(1), claim.clone()));
+		assert_ok!(PoeModule::create_claim(RuntimeOrigin::signed(1), claim.clone(), None));
 		assert_ok!(PoeModule::revoke_claim(RuntimeOrigin::signed(1), claim.clone()));
 		assert_eq!(Proofs::<Test>::get(&claim), None);
 	});
@@ -37,7 +109,7 @@ fn revoke_works() {
 
 #[test]
 fn revoke_failed_claim_not_exist() {
-	new_test_ext().execute_with(|| {
+	build_and_execute(|| {
 		let claim = BoundedVec::try_from(vec![0; 10]).unwrap();
 		assert_noop!(
 			PoeModule::revoke_claim(RuntimeOrigin::signed(1), claim),
@@ -48,9 +120,9 @@ fn revoke_failed_claim_not_exist() {
 
 #[test]
 fn revoke_failed_not_claim_owner() {
-	new_test_ext().execute_with(|| {
+	build_and_execute(|| {
 		let claim = BoundedVec::try_from(vec![0; 10]).unwrap();
-		assert_ok!(PoeModule::create_claim(RuntimeOrigin::signed(1), claim.clone()));
+		assert_ok!(PoeModule::create_claim(RuntimeOrigin::signed(1), claim.clone(), None));
 		assert_noop!(
 			PoeModule::revoke_claim(RuntimeOrigin::signed(2), claim),
 			Error::<Test>::NotClaimOwner
@@ -60,9 +132,9 @@ fn revoke_failed_not_claim_owner() {
 
 #[test]
 fn transfer_works() {
-	new_test_ext().execute_with(|| {
+	build_and_execute(|| {
 		let claim = BoundedVec::try_from(vec![0; 10]).unwrap();
-		assert_ok!(PoeModule::create_claim(RuntimeOrigin::signed(1), claim.clone()));
+		assert_ok!(PoeModule::create_claim(RuntimeOrigin::signed(1), claim.clone(), None));
 		assert_ok!(PoeModule::transfer_claim(RuntimeOrigin::signed(1), claim.clone(), 2));
 		assert_eq!(
 			Proofs::<Test>::get(&claim),
@@ -73,7 +145,7 @@ fn transfer_works() {
 
 #[test]
 fn transfer_failed_claim_not_exist() {
-	new_test_ext().execute_with(|| {
+	build_and_execute(|| {
 		let claim = BoundedVec::try_from(vec![0; 10]).unwrap();
 		assert_noop!(
 			PoeModule::transfer_claim(RuntimeOrigin::signed(1), claim, 2),
@@ -84,12 +156,636 @@ fn transfer_failed_claim_not_exist() {
 
 #[test]
 fn transfer_failed_not_claim_owner() {
-	new_test_ext().execute_with(|| {
+	build_and_execute(|| {
 		let claim = BoundedVec::try_from(vec![0; 10]).unwrap();
-		assert_ok!(PoeModule::create_claim(RuntimeOrigin::signed(1), claim.clone()));
+		assert_ok!(PoeModule::create_claim(RuntimeOrigin::signed(1), claim.clone(), None));
 		assert_noop!(
 			PoeModule::transfer_claim(RuntimeOrigin::signed(2), claim, 3),
 			Error::<Test>::NotClaimOwner
 		);
 	});
 }
+
+#[test]
+fn claim_metadata_is_indexed_offchain() {
+	let mut ext = new_test_ext();
+	let claim: Claim = BoundedVec::try_from(vec![0; 10]).unwrap();
+	let metadata = ClaimMetadata {
+		filename: b"deed.pdf".to_vec().try_into().unwrap(),
+		mime_type: b"application/pdf".to_vec().try_into().unwrap(),
+	};
+
+	ext.execute_with(|| {
+		assert_ok!(PoeModule::create_claim(
+			RuntimeOrigin::signed(1),
+			claim.clone(),
+			Some(metadata.clone())
+		));
+		// Nothing but the proof itself goes into state.
+		assert_eq!(sp_io::storage::get(&metadata_key(&claim)), None);
+	});
+	ext.persist_offchain_overlay();
+	assert_eq!(ext.offchain_db().get(&metadata_key(&claim)), Some(metadata.encode()));
+
+	ext.execute_with(|| {
+		assert_ok!(PoeModule::revoke_claim(RuntimeOrigin::signed(1), claim.clone()));
+	});
+	ext.persist_offchain_overlay();
+	assert_eq!(ext.offchain_db().get(&metadata_key(&claim)), None);
+}
+
+#[test]
+fn try_state_detects_claims_from_future_blocks() {
+	new_test_ext().execute_with(|| {
+		let claim = BoundedVec::try_from(vec![0; 10]).unwrap();
+		assert_ok!(PoeModule::create_claim(RuntimeOrigin::signed(1), claim.clone(), None));
+		assert_ok!(PoeModule::do_try_state());
+
+		Proofs::<Test>::insert(&claim, (1, System::block_number() + 1));
+		assert_noop!(PoeModule::do_try_state(), "claim made in a future block");
+	});
+}
+
+fn tsa_pair() -> ed25519::Pair {
+	ed25519::Pair::from_seed(&[7u8; 32])
+}
+
+fn tsa_token(pair: &ed25519::Pair, claim: &[u8]) -> TsaToken {
+	tsa_token_at(pair, claim, NOW + 1_000)
+}
+
+fn tsa_token_at(pair: &ed25519::Pair, claim: &[u8], timestamp: u64) -> TsaToken {
+	let signature = pair.sign(&TsaToken::payload(claim, timestamp));
+	TsaToken { key: pair.public(), timestamp, signature }
+}
+
+#[test]
+fn tsa_keys_are_managed_by_tsa_origin() {
+	build_and_execute(|| {
+		let key = tsa_pair().public();
+		assert_noop!(PoeModule::add_tsa_key(RuntimeOrigin::signed(1), key), BadOrigin);
+		assert_ok!(PoeModule::add_tsa_key(RuntimeOrigin::root(), key));
+		System::assert_last_event(Event::TsaKeyAdded(key).into());
+		assert_noop!(
+			PoeModule::add_tsa_key(RuntimeOrigin::root(), key),
+			Error::<Test>::TsaKeyAlreadyAdded
+		);
+		assert_ok!(PoeModule::add_tsa_key(
+			RuntimeOrigin::root(),
+			ed25519::Public::from_raw([1; 32])
+		));
+		assert_noop!(
+			PoeModule::add_tsa_key(RuntimeOrigin::root(), ed25519::Public::from_raw([2; 32])),
+			Error::<Test>::TooManyTsaKeys
+		);
+
+		assert_noop!(PoeModule::remove_tsa_key(RuntimeOrigin::signed(1), key), BadOrigin);
+		assert_ok!(PoeModule::remove_tsa_key(RuntimeOrigin::root(), key));
+		System::assert_last_event(Event::TsaKeyRemoved(key).into());
+		assert_noop!(
+			PoeModule::remove_tsa_key(RuntimeOrigin::root(), key),
+			Error::<Test>::UnknownTsaKey
+		);
+	});
+}
+
+#[test]
+fn claims_are_queued_for_attestation_once_a_tsa_is_known() {
+	build_and_execute(|| {
+		let claim: Claim = BoundedVec::try_from(vec![0; 10]).unwrap();
+		assert_ok!(PoeModule::create_claim(RuntimeOrigin::signed(1), claim.clone(), None));
+		assert!(!PendingAttestations::<Test>::contains_key(&claim));
+
+		assert_ok!(PoeModule::add_tsa_key(RuntimeOrigin::root(), tsa_pair().public()));
+		let claim: Claim = BoundedVec::try_from(vec![1; 10]).unwrap();
+		assert_ok!(PoeModule::create_claim(RuntimeOrigin::signed(1), claim.clone(), None));
+		assert_eq!(PendingAttestations::<Test>::get(&claim), Some(NOW));
+	});
+}
+
+#[test]
+fn submit_attestation_works() {
+	build_and_execute(|| {
+		let pair = tsa_pair();
+		let claim: Claim = BoundedVec::try_from(vec![0; 10]).unwrap();
+		let token = tsa_token(&pair, &claim);
+		assert_ok!(PoeModule::add_tsa_key(RuntimeOrigin::root(), pair.public()));
+		assert_ok!(PoeModule::create_claim(RuntimeOrigin::signed(1), claim.clone(), None));
+
+		assert_ok!(PoeModule::submit_attestation(
+			RuntimeOrigin::signed(2),
+			claim.clone(),
+			token.clone()
+		));
+
+		assert_eq!(Attestations::<Test>::get(&claim), Some(token.clone()));
+		assert!(!PendingAttestations::<Test>::contains_key(&claim));
+		System::assert_last_event(Event::ClaimAttested(claim, token.key, token.timestamp).into());
+	});
+}
+
+#[test]
+fn submit_attestation_failed_invalid_token() {
+	build_and_execute(|| {
+		let pair = tsa_pair();
+		let claim: Claim = BoundedVec::try_from(vec![0; 10]).unwrap();
+		let token = tsa_token(&pair, &claim);
+		assert_noop!(
+			PoeModule::submit_attestation(RuntimeOrigin::signed(2), claim.clone(), token.clone()),
+			Error::<Test>::ClaimNotExist
+		);
+
+		assert_ok!(PoeModule::add_tsa_key(RuntimeOrigin::root(), pair.public()));
+		assert_ok!(PoeModule::create_claim(RuntimeOrigin::signed(1), claim.clone(), None));
+		let unknown = tsa_token(&ed25519::Pair::from_seed(&[8u8; 32]), &claim);
+		assert_noop!(
+			PoeModule::submit_attestation(RuntimeOrigin::signed(2), claim.clone(), unknown),
+			Error::<Test>::UnknownTsaKey
+		);
+
+		let forged = TsaToken { timestamp: token.timestamp + 1, ..token.clone() };
+		assert_noop!(
+			PoeModule::submit_attestation(RuntimeOrigin::signed(2), claim.clone(), forged),
+			Error::<Test>::BadTsaSignature
+		);
+
+		assert_ok!(PoeModule::submit_attestation(
+			RuntimeOrigin::signed(2),
+			claim.clone(),
+			token.clone()
+		));
+		assert_noop!(
+			PoeModule::submit_attestation(RuntimeOrigin::signed(2), claim, token),
+			Error::<Test>::AlreadyAttested
+		);
+	});
+}
+
+#[test]
+fn submit_attestation_failed_out_of_window() {
+	build_and_execute(|| {
+		let pair = tsa_pair();
+		let claim: Claim = BoundedVec::try_from(vec![0; 10]).unwrap();
+		assert_ok!(PoeModule::add_tsa_key(RuntimeOrigin::root(), pair.public()));
+		assert_ok!(PoeModule::create_claim(RuntimeOrigin::signed(1), claim.clone(), None));
+
+		for timestamp in [NOW - 60_001, NOW + 60_001] {
+			assert_noop!(
+				PoeModule::submit_attestation(
+					RuntimeOrigin::signed(2),
+					claim.clone(),
+					tsa_token_at(&pair, &claim, timestamp)
+				),
+				Error::<Test>::AttestationOutOfWindow
+			);
+		}
+		assert_ok!(PoeModule::submit_attestation(
+			RuntimeOrigin::signed(2),
+			claim.clone(),
+			tsa_token_at(&pair, &claim, NOW - 60_000)
+		));
+	});
+}
+
+#[test]
+fn claims_made_without_a_tsa_can_not_be_attested() {
+	build_and_execute(|| {
+		let pair = tsa_pair();
+		let claim: Claim = BoundedVec::try_from(vec![0; 10]).unwrap();
+		assert_ok!(PoeModule::create_claim(RuntimeOrigin::signed(1), claim.clone(), None));
+		assert_ok!(PoeModule::add_tsa_key(RuntimeOrigin::root(), pair.public()));
+
+		assert_noop!(
+			PoeModule::submit_attestation(
+				RuntimeOrigin::signed(2),
+				claim.clone(),
+				tsa_token(&pair, &claim)
+			),
+			Error::<Test>::NotAwaitingAttestation
+		);
+	});
+}
+
+#[test]
+fn expired_claims_stop_waiting_for_attestation() {
+	build_and_execute(|| {
+		System::set_block_number(1);
+		let pair = tsa_pair();
+		let claim: Claim = BoundedVec::try_from(vec![0; 10]).unwrap();
+		assert_ok!(PoeModule::add_tsa_key(RuntimeOrigin::root(), pair.public()));
+		assert_ok!(PoeModule::create_claim(RuntimeOrigin::signed(1), claim.clone(), None));
+
+		Now::set(NOW + 60_000);
+		PoeModule::on_initialize(2);
+		assert!(PendingAttestations::<Test>::contains_key(&claim));
+
+		Now::set(NOW + 60_001);
+		PoeModule::on_initialize(3);
+		assert!(!PendingAttestations::<Test>::contains_key(&claim));
+		System::assert_last_event(Event::AttestationExpired(claim.clone()).into());
+		assert_noop!(
+			PoeModule::submit_attestation(
+				RuntimeOrigin::signed(2),
+				claim.clone(),
+				tsa_token_at(&pair, &claim, NOW + 60_001)
+			),
+			Error::<Test>::NotAwaitingAttestation
+		);
+	});
+}
+
+#[test]
+fn expiry_checks_continue_where_the_last_block_stopped() {
+	build_and_execute(|| {
+		assert_ok!(PoeModule::add_tsa_key(RuntimeOrigin::root(), tsa_pair().public()));
+		for i in 0..12 {
+			let claim: Claim = BoundedVec::try_from(vec![i; 10]).unwrap();
+			assert_ok!(PoeModule::create_claim(RuntimeOrigin::signed(1), claim, None));
+		}
+		Now::set(NOW + 60_001);
+
+		// Ten claims are checked per block.
+		PoeModule::on_initialize(2);
+		assert_eq!(PendingAttestations::<Test>::iter_keys().count(), 2);
+		PoeModule::on_initialize(3);
+		assert_eq!(PendingAttestations::<Test>::iter_keys().count(), 0);
+	});
+}
+
+#[test]
+fn revoke_removes_attestation() {
+	build_and_execute(|| {
+		let pair = tsa_pair();
+		let claim: Claim = BoundedVec::try_from(vec![0; 10]).unwrap();
+		assert_ok!(PoeModule::add_tsa_key(RuntimeOrigin::root(), pair.public()));
+		assert_ok!(PoeModule::create_claim(RuntimeOrigin::signed(1), claim.clone(), None));
+		assert_ok!(PoeModule::submit_attestation(
+			RuntimeOrigin::signed(2),
+			claim.clone(),
+			tsa_token(&pair, &claim)
+		));
+
+		assert_ok!(PoeModule::revoke_claim(RuntimeOrigin::signed(1), claim.clone()));
+		assert_eq!(Attestations::<Test>::get(&claim), None);
+	});
+}
+
+/// An externality with the TSA endpoint and a `poe!` key set up for the offchain worker.
+fn offchain_ext(
+	offchain: TestOffchainExt,
+	pool: TestTransactionPoolExt,
+) -> sp_io::TestExternalities {
+	let mut ext = new_test_ext();
+	ext.register_extension(OffchainDbExt::new(offchain.clone()));
+	ext.register_extension(OffchainWorkerExt::new(offchain));
+	ext.register_extension(TransactionPoolExt::new(pool));
+	ext.execute_with(|| {
+		UintAuthorityId::set_all_keys(vec![5u64]);
+		sp_io::offchain::local_storage_set(StorageKind::PERSISTENT, TSA_ENDPOINT_KEY, TSA_ENDPOINT);
+	});
+	ext
+}
+
+const TSA_ENDPOINT: &[u8] = b"http://localhost:3161/attest";
+
+/// Makes the TSA answer the next request, which must be for `claim`, with `token`.
+fn expect_tsa_request(state: &mut OffchainState, claim: &[u8], token: &TsaToken) {
+	state.expect_request(PendingRequest {
+		method: "POST".into(),
+		uri: String::from_utf8(TSA_ENDPOINT.to_vec()).unwrap(),
+		headers: vec![("Content-Type".into(), "application/octet-stream".into())],
+		body: sp_io::hashing::blake2_256(claim).to_vec(),
+		response: Some(token.encode()),
+		sent: true,
+		..Default::default()
+	});
+}
+
+#[test]
+fn offchain_worker_submits_tsa_tokens() {
+	let (offchain, offchain_state) = TestOffchainExt::new();
+	let (pool, pool_state) = TestTransactionPoolExt::new();
+	let mut ext = offchain_ext(offchain, pool);
+	ext.execute_with(|| {
+		let pair = tsa_pair();
+		let claim: Claim = BoundedVec::try_from(vec![0; 10]).unwrap();
+		let token = tsa_token(&pair, &claim);
+		assert_ok!(PoeModule::add_tsa_key(RuntimeOrigin::root(), pair.public()));
+		assert_ok!(PoeModule::create_claim(RuntimeOrigin::signed(1), claim.clone(), None));
+		expect_tsa_request(&mut offchain_state.write(), &claim, &token);
+
+		PoeModule::offchain_worker(System::block_number());
+
+		let tx = pool_state.write().transactions.pop().unwrap();
+		assert!(pool_state.read().transactions.is_empty());
+		let tx = Extrinsic::decode(&mut &*tx).unwrap();
+		assert_eq!(tx.signature, Some((0, ())));
+		assert_eq!(
+			tx.call,
+			RuntimeCall::PoeModule(crate::Call::submit_attestation { claim, token })
+		);
+
+		// The claim is not sent again while the transaction may still be on its way.
+		PoeModule::offchain_worker(System::block_number() + 1);
+		assert!(pool_state.read().transactions.is_empty());
+		PoeModule::do_try_state().unwrap();
+	});
+}
+
+#[test]
+fn offchain_worker_continues_where_the_last_block_stopped() {
+	let (offchain, offchain_state) = TestOffchainExt::new();
+	let (pool, pool_state) = TestTransactionPoolExt::new();
+	let mut ext = offchain_ext(offchain, pool);
+	ext.execute_with(|| {
+		let pair = tsa_pair();
+		assert_ok!(PoeModule::add_tsa_key(RuntimeOrigin::root(), pair.public()));
+		for i in 0..7 {
+			let claim: Claim = BoundedVec::try_from(vec![i; 10]).unwrap();
+			assert_ok!(PoeModule::create_claim(RuntimeOrigin::signed(1), claim, None));
+		}
+		// Requests are answered in the order they are expected, which is the storage order.
+		let claims = PendingAttestations::<Test>::iter_keys().collect::<Vec<_>>();
+		for claim in &claims {
+			expect_tsa_request(&mut offchain_state.write(), claim, &tsa_token(&pair, claim));
+		}
+
+		// Five claims are sent per block, and the next block sends the other two.
+		PoeModule::offchain_worker(1);
+		assert_eq!(pool_state.read().transactions.len(), 5);
+		PoeModule::offchain_worker(2);
+		assert_eq!(pool_state.read().transactions.len(), 7);
+
+		let sent = pool_state
+			.read()
+			.transactions
+			.iter()
+			.map(|tx| match Extrinsic::decode(&mut &tx[..]).unwrap().call {
+				RuntimeCall::PoeModule(crate::Call::submit_attestation { claim, .. }) => claim,
+				call => panic!("unexpected call {:?}", call),
+			})
+			.collect::<Vec<_>>();
+		assert_eq!(sent, claims);
+	});
+}
+
+#[test]
+fn offchain_worker_skips_expired_claims() {
+	let (offchain, offchain_state) = TestOffchainExt::new();
+	let (pool, pool_state) = TestTransactionPoolExt::new();
+	let mut ext = offchain_ext(offchain, pool);
+	ext.execute_with(|| {
+		assert_ok!(PoeModule::add_tsa_key(RuntimeOrigin::root(), tsa_pair().public()));
+		let claim: Claim = BoundedVec::try_from(vec![0; 10]).unwrap();
+		assert_ok!(PoeModule::create_claim(RuntimeOrigin::signed(1), claim, None));
+		offchain_state.write().timestamp = Timestamp::from_unix_millis(NOW + 60_001);
+
+		// The TSA is not asked, which would fail as it has no answer set.
+		PoeModule::offchain_worker(1);
+		assert!(pool_state.read().transactions.is_empty());
+	});
+}
+
+#[test]
+fn create_claim_signed_works() {
+	build_and_execute(|| {
+		System::set_block_number(1);
+		let claim: Claim = BoundedVec::try_from(vec![0; 10]).unwrap();
+		let signature = TestSignature(1, PoeModule::claim_payload(&claim, 0));
+		// Account 2 submits the claim of account 1.
+		assert_ok!(PoeModule::create_claim_signed(
+			RuntimeOrigin::signed(2),
+			claim.clone(),
+			1,
+			signature
+		));
+		assert_eq!(Proofs::<Test>::get(&claim), Some((1, System::block_number())));
+		assert_eq!(PoeModule::claim_nonce(1), 1);
+		System::assert_last_event(Event::ClaimCreated(1, claim).into());
+	});
+}
+
+#[test]
+fn create_claim_signed_failed_bad_signature() {
+	build_and_execute(|| {
+		let claim: Claim = BoundedVec::try_from(vec![0; 10]).unwrap();
+		let other: Claim = BoundedVec::try_from(vec![1; 10]).unwrap();
+		for signature in [
+			// Signed by another account.
+			TestSignature(3, PoeModule::claim_payload(&claim, 0)),
+			// Signed for another claim.
+			TestSignature(1, PoeModule::claim_payload(&other, 0)),
+			// Signed with the wrong nonce.
+			TestSignature(1, PoeModule::claim_payload(&claim, 1)),
+		] {
+			assert_noop!(
+				PoeModule::create_claim_signed(
+					RuntimeOrigin::signed(2),
+					claim.clone(),
+					1,
+					signature
+				),
+				Error::<Test>::BadClaimSignature
+			);
+		}
+	});
+}
+
+#[test]
+fn create_claim_signed_cannot_be_replayed() {
+	build_and_execute(|| {
+		let claim: Claim = BoundedVec::try_from(vec![0; 10]).unwrap();
+		let signature = TestSignature(1, PoeModule::claim_payload(&claim, 0));
+		assert_ok!(PoeModule::create_claim_signed(
+			RuntimeOrigin::signed(2),
+			claim.clone(),
+			1,
+			signature.clone()
+		));
+		assert_ok!(PoeModule::revoke_claim(RuntimeOrigin::signed(1), claim.clone()));
+
+		assert_noop!(
+			PoeModule::create_claim_signed(RuntimeOrigin::signed(2), claim.clone(), 1, signature),
+			Error::<Test>::BadClaimSignature
+		);
+		let signature = TestSignature(1, PoeModule::claim_payload(&claim, 1));
+		assert_ok!(PoeModule::create_claim_signed(RuntimeOrigin::signed(2), claim, 1, signature));
+		assert_eq!(PoeModule::claim_nonce(1), 2);
+	});
+}
+
+/// Has account 1 create a claim with 2 and 3 as co-signers and a revocation threshold of 2.
+fn create_multi_party_claim() -> Claim {
+	let claim: Claim = BoundedVec::try_from(vec![0; 10]).unwrap();
+	let cosigners = BoundedVec::try_from(vec![2, 3]).unwrap();
+	assert_ok!(PoeModule::create_multi_party_claim(
+		RuntimeOrigin::signed(1),
+		claim.clone(),
+		cosigners,
+		2
+	));
+	claim
+}
+
+#[test]
+fn create_multi_party_claim_failed_invalid_parameters() {
+	build_and_execute(|| {
+		let claim: Claim = BoundedVec::try_from(vec![0; 10]).unwrap();
+		for cosigners in [vec![], vec![2, 2], vec![1, 2]] {
+			assert_noop!(
+				PoeModule::create_multi_party_claim(
+					RuntimeOrigin::signed(1),
+					claim.clone(),
+					BoundedVec::try_from(cosigners).unwrap(),
+					1
+				),
+				Error::<Test>::InvalidCosigners
+			);
+		}
+		for threshold in [0, 3] {
+			assert_noop!(
+				PoeModule::create_multi_party_claim(
+					RuntimeOrigin::signed(1),
+					claim.clone(),
+					BoundedVec::try_from(vec![2]).unwrap(),
+					threshold
+				),
+				Error::<Test>::InvalidRevokeThreshold
+			);
+		}
+	});
+}
+
+#[test]
+fn multi_party_claim_is_fully_signed_once_every_cosigner_signed() {
+	build_and_execute(|| {
+		System::set_block_number(1);
+		let claim = create_multi_party_claim();
+		assert_eq!(Proofs::<Test>::get(&claim), Some((1, 1)));
+		System::assert_last_event(
+			Event::CosignersInvited(claim.clone(), BoundedVec::try_from(vec![2, 3]).unwrap())
+				.into(),
+		);
+
+		assert_noop!(
+			PoeModule::cosign_claim(RuntimeOrigin::signed(4), claim.clone()),
+			Error::<Test>::NotCosigner
+		);
+		assert_ok!(PoeModule::cosign_claim(RuntimeOrigin::signed(3), claim.clone()));
+		System::assert_last_event(Event::ClaimCosigned(3, claim.clone()).into());
+		assert_noop!(
+			PoeModule::cosign_claim(RuntimeOrigin::signed(3), claim.clone()),
+			Error::<Test>::AlreadyCosigned
+		);
+		assert_eq!(
+			PoeModule::signature_status(&claim),
+			Some(SignatureStatus {
+				creator: 1,
+				signed: vec![3],
+				pending: vec![2],
+				fully_signed: false,
+				revoke_threshold: 2,
+				revoke_approvals: 0,
+			})
+		);
+
+		assert_ok!(PoeModule::cosign_claim(RuntimeOrigin::signed(2), claim.clone()));
+		System::assert_has_event(Event::ClaimCosigned(2, claim.clone()).into());
+		System::assert_last_event(Event::ClaimFullySigned(claim.clone()).into());
+		let status = PoeModule::signature_status(&claim).unwrap();
+		assert!(status.fully_signed);
+		assert_eq!(status.signed, vec![2, 3]);
+
+		let plain: Claim = BoundedVec::try_from(vec![1; 10]).unwrap();
+		assert_ok!(PoeModule::create_claim(RuntimeOrigin::signed(1), plain.clone(), None));
+		assert_eq!(PoeModule::signature_status(&plain), None);
+		assert_noop!(
+			PoeModule::cosign_claim(RuntimeOrigin::signed(2), plain),
+			Error::<Test>::NotMultiPartyClaim
+		);
+	});
+}
+
+#[test]
+fn multi_party_claim_is_revoked_at_the_threshold() {
+	build_and_execute(|| {
+		System::set_block_number(1);
+		let claim = create_multi_party_claim();
+		assert_ok!(PoeModule::cosign_claim(RuntimeOrigin::signed(2), claim.clone()));
+
+		// The creator can't bypass the threshold.
+		assert_noop!(
+			PoeModule::revoke_claim(RuntimeOrigin::signed(1), claim.clone()),
+			Error::<Test>::IsMultiPartyClaim
+		);
+		assert_noop!(
+			PoeModule::transfer_claim(RuntimeOrigin::signed(1), claim.clone(), 4),
+			Error::<Test>::IsMultiPartyClaim
+		);
+		// 3 has not signed yet.
+		assert_noop!(
+			PoeModule::approve_revocation(RuntimeOrigin::signed(3), claim.clone()),
+			Error::<Test>::NotSigner
+		);
+
+		assert_ok!(PoeModule::approve_revocation(RuntimeOrigin::signed(2), claim.clone()));
+		System::assert_last_event(Event::RevocationApproved(2, claim.clone()).into());
+		assert_noop!(
+			PoeModule::approve_revocation(RuntimeOrigin::signed(2), claim.clone()),
+			Error::<Test>::AlreadyApproved
+		);
+		assert_eq!(PoeModule::signature_status(&claim).unwrap().revoke_approvals, 1);
+		assert!(Proofs::<Test>::contains_key(&claim));
+
+		assert_ok!(PoeModule::approve_revocation(RuntimeOrigin::signed(1), claim.clone()));
+		System::assert_has_event(Event::RevocationApproved(1, claim.clone()).into());
+		System::assert_last_event(Event::ClaimRevoked(1, claim.clone()).into());
+		assert_eq!(Proofs::<Test>::get(&claim), None);
+		assert_eq!(MultiPartyClaims::<Test>::get(&claim), None);
+		assert_eq!(PoeModule::signature_status(&claim), None);
+	});
+}
+
+#[test]
+fn creator_can_withdraw_claims_that_are_not_fully_signed() {
+	build_and_execute(|| {
+		System::set_block_number(1);
+		let claim = create_multi_party_claim();
+		assert_ok!(PoeModule::cosign_claim(RuntimeOrigin::signed(2), claim.clone()));
+		assert_ok!(PoeModule::approve_revocation(RuntimeOrigin::signed(2), claim.clone()));
+
+		// 3 never signs, so the creator gives up on it.
+		assert_noop!(
+			PoeModule::withdraw_claim(RuntimeOrigin::signed(2), claim.clone()),
+			Error::<Test>::NotClaimOwner
+		);
+		assert_ok!(PoeModule::withdraw_claim(RuntimeOrigin::signed(1), claim.clone()));
+		System::assert_last_event(Event::ClaimRevoked(1, claim.clone()).into());
+		assert_eq!(Proofs::<Test>::get(&claim), None);
+		assert_eq!(MultiPartyClaims::<Test>::get(&claim), None);
+		assert_eq!(RevocationApprovals::<Test>::iter_prefix(&claim).count(), 0);
+
+		// The claim can be made again.
+		create_multi_party_claim();
+	});
+}
+
+#[test]
+fn fully_signed_claims_can_not_be_withdrawn() {
+	build_and_execute(|| {
+		let claim = create_multi_party_claim();
+		assert_ok!(PoeModule::cosign_claim(RuntimeOrigin::signed(2), claim.clone()));
+		assert_ok!(PoeModule::cosign_claim(RuntimeOrigin::signed(3), claim.clone()));
+		assert_noop!(
+			PoeModule::withdraw_claim(RuntimeOrigin::signed(1), claim),
+			Error::<Test>::FullySigned
+		);
+
+		let plain: Claim = BoundedVec::try_from(vec![1; 10]).unwrap();
+		assert_ok!(PoeModule::create_claim(RuntimeOrigin::signed(1), plain.clone(), None));
+		assert_noop!(
+			PoeModule::withdraw_claim(RuntimeOrigin::signed(1), plain),
+			Error::<Test>::NotMultiPartyClaim
+		);
+	});
+}
@@ -8,6 +8,10 @@
 //! HOSTNAME: `web3`, CPU: `Westmere E56xx/L56xx/X56xx (Nehalem-C)`
 //! EXECUTION: Some(Wasm), WASM-EXECUTION: Compiled, CHAIN: Some("dev"), DB CACHE:
 //! 1024
+//!
+//! The `m` component and the `TsaKeys` and `PendingAttestations` accesses of `create_claim` were
+//! added by hand, and so were the weights of the calls after `transfer_claim`, which are
+//! estimates from the storage they access; rerun the command below to measure them.
 
 // Executed Command:
 // ./target/release/node-template
@@ -43,9 +47,17 @@ use core::marker::PhantomData;
 
 /// Weight functions needed for pallet_poe.
 pub trait WeightInfo {
-fn create_claim(d: u32, ) -> Weight;
+fn create_claim(d: u32, m: u32, ) -> Weight;
 fn revoke_claim(d: u32, ) -> Weight;
 fn transfer_claim(d: u32, ) -> Weight;
+fn submit_attestation() -> Weight;
+fn add_tsa_key() -> Weight;
+fn remove_tsa_key() -> Weight;
+fn create_claim_signed(d: u32, ) -> Weight;
+fn create_multi_party_claim(d: u32, c: u32, ) -> Weight;
+fn cosign_claim(c: u32, ) -> Weight;
+fn approve_revocation(c: u32, ) -> Weight;
+fn withdraw_claim(c: u32, ) -> Weight;
 }
 
 /// Weights for pallet_poe using the Substrate node and recommended hardware.
@@ -53,8 +65,11 @@ pub struct SubstrateWeight<T>(PhantomData<T>);
                 impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
                         /// Storage: PoeModule Proofs (r:1 w:1)
                         /// Proof: PoeModule Proofs (max_values: None, max_size: Some(85), added: 2560, mode: MaxEncodedLen)
+                        /// Storage: PoeModule TsaKeys (r:1 w:0)
+                        /// Storage: PoeModule PendingAttestations (r:0 w:1)
                         /// The range of component `d` is `[0, 32]`.
-                        fn create_claim(d: u32, ) -> Weight {
+                        /// The range of component `m` is `[0, 512]`.
+                        fn create_claim(d: u32, m: u32, ) -> Weight {
                         // Proof Size summary in bytes:
                         // Measured: `6`
                         // Estimated: `3550`
@@ -64,8 +79,11 @@ pub struct SubstrateWeight<T>(PhantomData<T>);
                         // Standard Error: 37_606
                         .saturating_add(Weight::from_parts(228_602,
                         0).saturating_mul(d.into()))
-                        .saturating_add(T::DbWeight::get().reads(1_u64))
-                        .saturating_add(T::DbWeight::get().writes(1_u64))
+                        // Estimated, not measured: hashing and indexing the metadata.
+                        .saturating_add(Weight::from_parts(5_000,
+                        0).saturating_mul(m.into()))
+                        .saturating_add(T::DbWeight::get().reads(2_u64))
+                        .saturating_add(T::DbWeight::get().writes(2_u64))
                         }
                         /// Storage: PoeModule Proofs (r:1 w:1)
                         /// Proof: PoeModule Proofs (max_values: None, max_size: Some(85), added: 2560, mode: MaxEncodedLen)
@@ -99,14 +117,118 @@ pub struct SubstrateWeight<T>(PhantomData<T>);
                         .saturating_add(T::DbWeight::get().reads(1_u64))
                         .saturating_add(T::DbWeight::get().writes(1_u64))
                         }
+                        /// Storage: PoeModule Proofs (r:1 w:0)
+                        /// Storage: PoeModule Attestations (r:1 w:1)
+                        /// Storage: PoeModule TsaKeys (r:1 w:0)
+                        /// Storage: PoeModule PendingAttestations (r:1 w:1)
+                        fn submit_attestation() -> Weight {
+                        // Estimated, not measured.
+                        Weight::from_parts(75_000_000,
+                        0)
+                        .saturating_add(T::DbWeight::get().reads(4_u64))
+                        .saturating_add(T::DbWeight::get().writes(2_u64))
+                        }
+                        /// Storage: PoeModule TsaKeys (r:1 w:1)
+                        fn add_tsa_key() -> Weight {
+                        // Estimated, not measured.
+                        Weight::from_parts(15_000_000,
+                        0)
+                        .saturating_add(T::DbWeight::get().reads(1_u64))
+                        .saturating_add(T::DbWeight::get().writes(1_u64))
+                        }
+                        /// Storage: PoeModule TsaKeys (r:1 w:1)
+                        fn remove_tsa_key() -> Weight {
+                        // Estimated, not measured.
+                        Weight::from_parts(15_000_000,
+                        0)
+                        .saturating_add(T::DbWeight::get().reads(1_u64))
+                        .saturating_add(T::DbWeight::get().writes(1_u64))
+                        }
+                        /// Storage: PoeModule ClaimNonces (r:1 w:1)
+                        /// Storage: PoeModule Proofs (r:1 w:1)
+                        /// Storage: PoeModule TsaKeys (r:1 w:0)
+                        /// Storage: PoeModule PendingAttestations (r:0 w:1)
+                        /// The range of component `d` is `[0, 32]`.
+                        fn create_claim_signed(d: u32, ) -> Weight {
+                        // Estimated, not measured.
+                        Weight::from_parts(95_000_000,
+                        0)
+                        .saturating_add(Weight::from_parts(230_000,
+                        0).saturating_mul(d.into()))
+                        .saturating_add(T::DbWeight::get().reads(3_u64))
+                        .saturating_add(T::DbWeight::get().writes(3_u64))
+                        }
+                        /// Storage: PoeModule Proofs (r:1 w:1)
+                        /// Storage: PoeModule TsaKeys (r:1 w:0)
+                        /// Storage: PoeModule PendingAttestations (r:0 w:1)
+                        /// Storage: PoeModule MultiPartyClaims (r:0 w:1)
+                        /// The range of component `d` is `[0, 32]`.
+                        /// The range of component `c` is `[1, 16]`.
+                        fn create_multi_party_claim(d: u32, c: u32, ) -> Weight {
+                        // Estimated, not measured.
+                        Weight::from_parts(45_000_000,
+                        0)
+                        .saturating_add(Weight::from_parts(230_000,
+                        0).saturating_mul(d.into()))
+                        .saturating_add(Weight::from_parts(400_000,
+                        0).saturating_mul(c.into()))
+                        .saturating_add(T::DbWeight::get().reads(2_u64))
+                        .saturating_add(T::DbWeight::get().writes(3_u64))
+                        }
+                        /// Storage: PoeModule MultiPartyClaims (r:1 w:1)
+                        /// The range of component `c` is `[1, 16]`.
+                        fn cosign_claim(c: u32, ) -> Weight {
+                        // Estimated, not measured.
+                        Weight::from_parts(20_000_000,
+                        0)
+                        .saturating_add(Weight::from_parts(150_000,
+                        0).saturating_mul(c.into()))
+                        .saturating_add(T::DbWeight::get().reads(1_u64))
+                        .saturating_add(T::DbWeight::get().writes(1_u64))
+                        }
+                        /// Storage: PoeModule MultiPartyClaims (r:1 w:1)
+                        /// Storage: PoeModule Proofs (r:1 w:1)
+                        /// Storage: PoeModule RevocationApprovals (r:1 w:16)
+                        /// Storage: PoeModule PendingAttestations (r:0 w:1)
+                        /// Storage: PoeModule Attestations (r:0 w:1)
+                        /// The range of component `c` is `[1, 16]`.
+                        fn approve_revocation(c: u32, ) -> Weight {
+                        // Estimated, not measured.
+                        Weight::from_parts(45_000_000,
+                        0)
+                        .saturating_add(Weight::from_parts(1_500_000,
+                        0).saturating_mul(c.into()))
+                        .saturating_add(T::DbWeight::get().reads(3_u64))
+                        .saturating_add(T::DbWeight::get().writes(4_u64))
+                        .saturating_add(T::DbWeight::get().writes((1_u64).saturating_mul(c.into())))
+                        }
+                        /// Storage: PoeModule MultiPartyClaims (r:1 w:1)
+                        /// Storage: PoeModule Proofs (r:1 w:1)
+                        /// Storage: PoeModule RevocationApprovals (r:0 w:16)
+                        /// Storage: PoeModule PendingAttestations (r:0 w:1)
+                        /// Storage: PoeModule Attestations (r:0 w:1)
+                        /// The range of component `c` is `[1, 16]`.
+                        fn withdraw_claim(c: u32, ) -> Weight {
+                        // Estimated, not measured.
+                        Weight::from_parts(40_000_000,
+                        0)
+                        .saturating_add(Weight::from_parts(1_500_000,
+                        0).saturating_mul(c.into()))
+                        .saturating_add(T::DbWeight::get().reads(2_u64))
+                        .saturating_add(T::DbWeight::get().writes(4_u64))
+                        .saturating_add(T::DbWeight::get().writes((1_u64).saturating_mul(c.into())))
+                        }
                         }
 
                         // For backwards compatibility and tests
                         impl WeightInfo for () {
                         /// Storage: PoeModule Proofs (r:1 w:1)
                         /// Proof: PoeModule Proofs (max_values: None, max_size: Some(85), added: 2560, mode: MaxEncodedLen)
+                        /// Storage: PoeModule TsaKeys (r:1 w:0)
+                        /// Storage: PoeModule PendingAttestations (r:0 w:1)
                         /// The range of component `d` is `[0, 32]`.
-                        fn create_claim(d: u32, ) -> Weight {
+                        /// The range of component `m` is `[0, 512]`.
+                        fn create_claim(d: u32, m: u32, ) -> Weight {
                         // Proof Size summary in bytes:
                         // Measured: `6`
                         // Estimated: `3550`
@@ -116,8 +238,11 @@ pub struct SubstrateWeight<T>(PhantomData<T>);
                         // Standard Error: 37_606
                         .saturating_add(Weight::from_parts(228_602,
                         0).saturating_mul(d.into()))
-                        .saturating_add(RocksDbWeight::get().reads(1_u64))
-                        .saturating_add(RocksDbWeight::get().writes(1_u64))
+                        // Estimated, not measured: hashing and indexing the metadata.
+                        .saturating_add(Weight::from_parts(5_000,
+                        0).saturating_mul(m.into()))
+                        .saturating_add(RocksDbWeight::get().reads(2_u64))
+                        .saturating_add(RocksDbWeight::get().writes(2_u64))
                         }
                         /// Storage: PoeModule Proofs (r:1 w:1)
                         /// Proof: PoeModule Proofs (max_values: None, max_size: Some(85), added: 2560, mode: MaxEncodedLen)
@@ -151,4 +276,105 @@ pub struct SubstrateWeight<T>(PhantomData<T>);
                         .saturating_add(RocksDbWeight::get().reads(1_u64))
                         .saturating_add(RocksDbWeight::get().writes(1_u64))
                         }
+                        /// Storage: PoeModule Proofs (r:1 w:0)
+                        /// Storage: PoeModule Attestations (r:1 w:1)
+                        /// Storage: PoeModule TsaKeys (r:1 w:0)
+                        /// Storage: PoeModule PendingAttestations (r:1 w:1)
+                        fn submit_attestation() -> Weight {
+                        // Estimated, not measured.
+                        Weight::from_parts(75_000_000,
+                        0)
+                        .saturating_add(RocksDbWeight::get().reads(4_u64))
+                        .saturating_add(RocksDbWeight::get().writes(2_u64))
+                        }
+                        /// Storage: PoeModule TsaKeys (r:1 w:1)
+                        fn add_tsa_key() -> Weight {
+                        // Estimated, not measured.
+                        Weight::from_parts(15_000_000,
+                        0)
+                        .saturating_add(RocksDbWeight::get().reads(1_u64))
+                        .saturating_add(RocksDbWeight::get().writes(1_u64))
+                        }
+                        /// Storage: PoeModule TsaKeys (r:1 w:1)
+                        fn remove_tsa_key() -> Weight {
+                        // Estimated, not measured.
+                        Weight::from_parts(15_000_000,
+                        0)
+                        .saturating_add(RocksDbWeight::get().reads(1_u64))
+                        .saturating_add(RocksDbWeight::get().writes(1_u64))
+                        }
+                        /// Storage: PoeModule ClaimNonces (r:1 w:1)
+                        /// Storage: PoeModule Proofs (r:1 w:1)
+                        /// Storage: PoeModule TsaKeys (r:1 w:0)
+                        /// Storage: PoeModule PendingAttestations (r:0 w:1)
+                        /// The range of component `d` is `[0, 32]`.
+                        fn create_claim_signed(d: u32, ) -> Weight {
+                        // Estimated, not measured.
+                        Weight::from_parts(95_000_000,
+                        0)
+                        .saturating_add(Weight::from_parts(230_000,
+                        0).saturating_mul(d.into()))
+                        .saturating_add(RocksDbWeight::get().reads(3_u64))
+                        .saturating_add(RocksDbWeight::get().writes(3_u64))
+                        }
+                        /// Storage: PoeModule Proofs (r:1 w:1)
+                        /// Storage: PoeModule TsaKeys (r:1 w:0)
+                        /// Storage: PoeModule PendingAttestations (r:0 w:1)
+                        /// Storage: PoeModule MultiPartyClaims (r:0 w:1)
+                        /// The range of component `d` is `[0, 32]`.
+                        /// The range of component `c` is `[1, 16]`.
+                        fn create_multi_party_claim(d: u32, c: u32, ) -> Weight {
+                        // Estimated, not measured.
+                        Weight::from_parts(45_000_000,
+                        0)
+                        .saturating_add(Weight::from_parts(230_000,
+                        0).saturating_mul(d.into()))
+                        .saturating_add(Weight::from_parts(400_000,
+                        0).saturating_mul(c.into()))
+                        .saturating_add(RocksDbWeight::get().reads(2_u64))
+                        .saturating_add(RocksDbWeight::get().writes(3_u64))
+                        }
+                        /// Storage: PoeModule MultiPartyClaims (r:1 w:1)
+                        /// The range of component `c` is `[1, 16]`.
+                        fn cosign_claim(c: u32, ) -> Weight {
+                        // Estimated, not measured.
+                        Weight::from_parts(20_000_000,
+                        0)
+                        .saturating_add(Weight::from_parts(150_000,
+                        0).saturating_mul(c.into()))
+                        .saturating_add(RocksDbWeight::get().reads(1_u64))
+                        .saturating_add(RocksDbWeight::get().writes(1_u64))
+                        }
+                        /// Storage: PoeModule MultiPartyClaims (r:1 w:1)
+                        /// Storage: PoeModule Proofs (r:1 w:1)
+                        /// Storage: PoeModule RevocationApprovals (r:1 w:16)
+                        /// Storage: PoeModule PendingAttestations (r:0 w:1)
+                        /// Storage: PoeModule Attestations (r:0 w:1)
+                        /// The range of component `c` is `[1, 16]`.
+                        fn approve_revocation(c: u32, ) -> Weight {
+                        // Estimated, not measured.
+                        Weight::from_parts(45_000_000,
+                        0)
+                        .saturating_add(Weight::from_parts(1_500_000,
+                        0).saturating_mul(c.into()))
+                        .saturating_add(RocksDbWeight::get().reads(3_u64))
+                        .saturating_add(RocksDbWeight::get().writes(4_u64))
+                        .saturating_add(RocksDbWeight::get().writes((1_u64).saturating_mul(c.into())))
+                        }
+                        /// Storage: PoeModule MultiPartyClaims (r:1 w:1)
+                        /// Storage: PoeModule Proofs (r:1 w:1)
+                        /// Storage: PoeModule RevocationApprovals (r:0 w:16)
+                        /// Storage: PoeModule PendingAttestations (r:0 w:1)
+                        /// Storage: PoeModule Attestations (r:0 w:1)
+                        /// The range of component `c` is `[1, 16]`.
+                        fn withdraw_claim(c: u32, ) -> Weight {
+                        // Estimated, not measured.
+                        Weight::from_parts(40_000_000,
+                        0)
+                        .saturating_add(Weight::from_parts(1_500_000,
+                        0).saturating_mul(c.into()))
+                        .saturating_add(RocksDbWeight::get().reads(2_u64))
+                        .saturating_add(RocksDbWeight::get().writes(4_u64))
+                        .saturating_add(RocksDbWeight::get().writes((1_u64).saturating_mul(c.into())))
+                        }
                         }
\ No newline at end of file
@@ -0,0 +1,70 @@
+//! Benchmarking setup for pallet-signed-scheduler
+#![cfg(feature = "runtime-benchmarks")]
+use super::*;
+
+#[allow(unused)]
+use crate::Pallet as SignedScheduler;
+use frame_benchmarking::v2::*;
+use frame_support::traits::{schedule::v3::TaskName, Currency, Get};
+use frame_system::RawOrigin;
+use sp_runtime::traits::{Bounded, One};
+use sp_std::boxed::Box;
+
+/// Funds `who` and fills its tasks and those of block `when` up to one below the limits.
+fn prepare<T: Config>(who: &T::AccountId, when: T::BlockNumber) {
+	T::Currency::make_free_balance_be(who, BalanceOf::<T>::max_value() / 2u32.into());
+	AccountTasks::<T>::insert(who, T::MaxTasksPerAccount::get().saturating_sub(1));
+	BlockTasks::<T>::insert(when, T::MaxTasksPerBlock::get().saturating_sub(1));
+}
+
+fn schedule_task<T: Config>(who: &T::AccountId, when: T::BlockNumber) -> TaskName {
+	let index = NextTaskIndex::<T>::get();
+	let call = Box::new(T::BenchmarkHelper::call());
+	SignedScheduler::<T>::schedule(RawOrigin::Signed(who.clone()).into(), when, call).unwrap();
+	SignedScheduler::<T>::task_id(index)
+}
+
+#[benchmarks]
+mod benchmarks {
+	use super::*;
+
+	#[benchmark]
+	fn schedule() {
+		let caller: T::AccountId = whitelisted_caller();
+		let when = frame_system::Pallet::<T>::block_number() + One::one();
+		prepare::<T>(&caller, when);
+		let id = SignedScheduler::<T>::task_id(NextTaskIndex::<T>::get());
+		let call = Box::new(T::BenchmarkHelper::call());
+		#[extrinsic_call]
+		schedule(RawOrigin::Signed(caller.clone()), when, call);
+
+		assert_eq!(Tasks::<T>::get(id), Some((caller, when)));
+	}
+
+	#[benchmark]
+	fn cancel() {
+		let caller: T::AccountId = whitelisted_caller();
+		let when = frame_system::Pallet::<T>::block_number() + One::one();
+		prepare::<T>(&caller, when);
+		let id = schedule_task::<T>(&caller, when);
+		#[extrinsic_call]
+		cancel(RawOrigin::Signed(caller.clone()), id);
+
+		assert!(!Tasks::<T>::contains_key(id));
+	}
+
+	#[benchmark]
+	fn dispatch_task() {
+		let caller: T::AccountId = whitelisted_caller();
+		let when = frame_system::Pallet::<T>::block_number() + One::one();
+		prepare::<T>(&caller, when);
+		let id = schedule_task::<T>(&caller, when);
+		let call = Box::new(T::BenchmarkHelper::call());
+		#[extrinsic_call]
+		dispatch_task(RawOrigin::Root, id, call);
+
+		assert!(!Tasks::<T>::contains_key(id));
+	}
+
+	impl_benchmark_test_suite!(SignedScheduler, crate::mock::new_test_ext(), crate::mock::Test);
+}
@@ -0,0 +1,257 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! Lets accounts schedule calls on their own behalf, on top of a scheduler that only governance
+//! can use directly.
+//!
+//! [`Pallet::schedule`] hands the scheduler a [`Pallet::dispatch_task`] call with root origin,
+//! which dispatches the account's call from the account when the task is due. Every task holds
+//! [`Config::Deposit`] of the account until it is dispatched or cancelled, and there are limits
+//! on the tasks of an account ([`Config::MaxTasksPerAccount`]) and on the tasks of all accounts
+//! in a block ([`Config::MaxTasksPerBlock`]). Keeping the latter below the scheduler's own limit
+//! leaves room in every block for governance.
+//!
+//! Calls are handed to the scheduler inline, so no preimage is noted for them. Calls too long to
+//! fit are rejected.
+
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+pub mod weights;
+pub use weights::*;
+
+/// Provides a call that can be scheduled in benchmarks.
+#[cfg(feature = "runtime-benchmarks")]
+pub trait BenchmarkHelper<RuntimeCall> {
+	/// Returns one of [`Config::SchedulableCalls`].
+	fn call() -> RuntimeCall;
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::WeightInfo;
+	use frame_support::{
+		dispatch::{GetDispatchInfo, PostDispatchInfo},
+		pallet_prelude::*,
+		traits::{
+			schedule::{
+				v3::{Named as ScheduleNamed, TaskName},
+				DispatchTime, LOWEST_PRIORITY,
+			},
+			Bounded, Contains, Currency, ReservableCurrency,
+		},
+	};
+	use frame_system::pallet_prelude::*;
+	use sp_runtime::traits::Dispatchable;
+	use sp_std::boxed::Box;
+
+	pub type BalanceOf<T> =
+		<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// Because this pallet emits events, it depends on the runtime's definition of an event.
+		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+		/// The runtime's call type, which the scheduled calls are of.
+		type RuntimeCall: Parameter
+			+ Dispatchable<
+				RuntimeOrigin = <Self as frame_system::Config>::RuntimeOrigin,
+				PostInfo = PostDispatchInfo,
+			> + GetDispatchInfo
+			+ From<Call<Self>>;
+		/// The origin the scheduler dispatches calls with.
+		type PalletsOrigin: From<frame_system::RawOrigin<Self::AccountId>>;
+		/// The scheduler the tasks are handed to.
+		type Scheduler: ScheduleNamed<
+			Self::BlockNumber,
+			<Self as Config>::RuntimeCall,
+			Self::PalletsOrigin,
+		>;
+		/// The currency the deposits are reserved in.
+		type Currency: ReservableCurrency<Self::AccountId>;
+		/// Calls that accounts may schedule.
+		type SchedulableCalls: Contains<<Self as Config>::RuntimeCall>;
+		/// The deposit held for every task until it is dispatched or cancelled.
+		#[pallet::constant]
+		type Deposit: Get<BalanceOf<Self>>;
+		/// The maximum number of tasks an account can have waiting.
+		#[pallet::constant]
+		type MaxTasksPerAccount: Get<u32>;
+		/// The maximum number of tasks accounts can schedule in one block together.
+		#[pallet::constant]
+		type MaxTasksPerBlock: Get<u32>;
+		/// Weight information for extrinsics in this pallet.
+		type WeightInfo: WeightInfo;
+
+		/// Provides a schedulable call in benchmarks.
+		#[cfg(feature = "runtime-benchmarks")]
+		type BenchmarkHelper: crate::BenchmarkHelper<<Self as Config>::RuntimeCall>;
+	}
+
+	/// The account and block of every waiting task, keyed by the name of its scheduler task.
+	#[pallet::storage]
+	#[pallet::getter(fn task)]
+	pub type Tasks<T: Config> =
+		StorageMap<_, Identity, TaskName, (T::AccountId, T::BlockNumber), OptionQuery>;
+
+	/// The number of waiting tasks of each account.
+	#[pallet::storage]
+	pub type AccountTasks<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, u32, ValueQuery>;
+
+	/// The number of waiting tasks in each block.
+	#[pallet::storage]
+	pub type BlockTasks<T: Config> = StorageMap<_, Twox64Concat, T::BlockNumber, u32, ValueQuery>;
+
+	/// The index the name of the next task is made from.
+	#[pallet::storage]
+	pub type NextTaskIndex<T: Config> = StorageValue<_, u64, ValueQuery>;
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// `who` scheduled task `id` for block `when`.
+		Scheduled { who: T::AccountId, id: TaskName, when: T::BlockNumber },
+		/// `who` cancelled task `id`.
+		Cancelled { who: T::AccountId, id: TaskName },
+		/// Task `id` of `who` was dispatched.
+		Dispatched { who: T::AccountId, id: TaskName, result: DispatchResult },
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The call is not one of the calls accounts may schedule.
+		CallNotAllowed,
+		/// The call is too long to be handed to the scheduler inline.
+		CallTooLong,
+		/// The block is not in the future.
+		InPast,
+		/// The account has as many tasks waiting as it may.
+		TooManyTasks,
+		/// The block has as many tasks of accounts as it may.
+		BlockFull,
+		/// There is no such task.
+		TaskNotFound,
+		/// The task was scheduled by another account.
+		NotOwner,
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Schedule `call` to be dispatched from the caller in block `when`, holding
+		/// [`Config::Deposit`] until then.
+		#[pallet::call_index(0)]
+		#[pallet::weight(T::WeightInfo::schedule())]
+		pub fn schedule(
+			origin: OriginFor<T>,
+			when: T::BlockNumber,
+			call: Box<<T as Config>::RuntimeCall>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(T::SchedulableCalls::contains(&call), Error::<T>::CallNotAllowed);
+			ensure!(when > frame_system::Pallet::<T>::block_number(), Error::<T>::InPast);
+
+			let index = NextTaskIndex::<T>::mutate(|index| {
+				let current = *index;
+				*index = index.wrapping_add(1);
+				current
+			});
+			let id = Self::task_id(index);
+			let task: <T as Config>::RuntimeCall = Call::<T>::dispatch_task { id, call }.into();
+			let task = task.encode().try_into().map_err(|_| Error::<T>::CallTooLong)?;
+
+			AccountTasks::<T>::try_mutate(&who, |count| {
+				ensure!(*count < T::MaxTasksPerAccount::get(), Error::<T>::TooManyTasks);
+				*count += 1;
+				Ok::<_, Error<T>>(())
+			})?;
+			BlockTasks::<T>::try_mutate(when, |count| {
+				ensure!(*count < T::MaxTasksPerBlock::get(), Error::<T>::BlockFull);
+				*count += 1;
+				Ok::<_, Error<T>>(())
+			})?;
+			T::Currency::reserve(&who, T::Deposit::get())?;
+			T::Scheduler::schedule_named(
+				id,
+				DispatchTime::At(when),
+				None,
+				LOWEST_PRIORITY,
+				frame_system::RawOrigin::Root.into(),
+				Bounded::Inline(task),
+			)?;
+			Tasks::<T>::insert(id, (&who, when));
+
+			Self::deposit_event(Event::Scheduled { who, id, when });
+			Ok(())
+		}
+
+		/// Cancel task `id` of the caller and release its deposit.
+		#[pallet::call_index(1)]
+		#[pallet::weight(T::WeightInfo::cancel())]
+		pub fn cancel(origin: OriginFor<T>, id: TaskName) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let (owner, _) = Tasks::<T>::get(id).ok_or(Error::<T>::TaskNotFound)?;
+			ensure!(owner == who, Error::<T>::NotOwner);
+
+			Self::remove_task(id);
+			// The task is gone already if governance cancelled it through the scheduler, which
+			// must not keep the deposit locked.
+			let _ = T::Scheduler::cancel_named(id);
+
+			Self::deposit_event(Event::Cancelled { who, id });
+			Ok(())
+		}
+
+		/// Dispatch `call` from the account that scheduled task `id` and release its deposit.
+		///
+		/// The scheduler calls this with root origin when the task is due.
+		#[pallet::call_index(2)]
+		#[pallet::weight(
+			T::WeightInfo::dispatch_task().saturating_add(call.get_dispatch_info().weight)
+		)]
+		pub fn dispatch_task(
+			origin: OriginFor<T>,
+			id: TaskName,
+			call: Box<<T as Config>::RuntimeCall>,
+		) -> DispatchResult {
+			ensure_root(origin)?;
+			let who = Self::remove_task(id).ok_or(Error::<T>::TaskNotFound)?;
+
+			// The origin is subject to the runtime's call filter like that of a transaction.
+			let origin = frame_system::RawOrigin::Signed(who.clone()).into();
+			let result = call.dispatch(origin).map(|_| ()).map_err(|e| e.error);
+
+			Self::deposit_event(Event::Dispatched { who, id, result });
+			Ok(())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// The name of the scheduler task of the `index`th call scheduled through this pallet.
+		pub fn task_id(index: u64) -> TaskName {
+			(b"signed-scheduler", index).using_encoded(sp_io::hashing::blake2_256)
+		}
+
+		/// Removes task `id` from the counts and releases its deposit, returning its owner.
+		fn remove_task(id: TaskName) -> Option<T::AccountId> {
+			let (who, when) = Tasks::<T>::take(id)?;
+			AccountTasks::<T>::mutate_exists(&who, |count| {
+				*count = count.map(|count| count.saturating_sub(1)).filter(|count| *count > 0)
+			});
+			BlockTasks::<T>::mutate_exists(when, |count| {
+				*count = count.map(|count| count.saturating_sub(1)).filter(|count| *count > 0)
+			});
+			T::Currency::unreserve(&who, T::Deposit::get());
+			Some(who)
+		}
+	}
+}
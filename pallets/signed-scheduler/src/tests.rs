@@ -0,0 +1,166 @@
+use crate::{mock::*, AccountTasks, BlockTasks, Error, Event, Tasks};
+use frame_support::{
+	assert_noop, assert_ok,
+	traits::{Currency, OnInitialize},
+};
+use sp_runtime::traits::{BlakeTwo256, Hash};
+
+fn remark(remark: Vec<u8>) -> Box<RuntimeCall> {
+	Box::new(RuntimeCall::System(frame_system::Call::remark_with_event { remark }))
+}
+
+fn schedule(who: u64, when: u64) -> [u8; 32] {
+	assert_ok!(SignedScheduler::schedule(RuntimeOrigin::signed(who), when, remark(vec![])));
+	match System::events().last().map(|record| record.event.clone()) {
+		Some(RuntimeEvent::SignedScheduler(Event::Scheduled { id, .. })) => id,
+		event => panic!("unexpected event {:?}", event),
+	}
+}
+
+fn run_to_block(n: u64) {
+	while System::block_number() < n {
+		System::set_block_number(System::block_number() + 1);
+		Scheduler::on_initialize(System::block_number());
+	}
+}
+
+#[test]
+fn scheduled_calls_are_dispatched_from_the_account() {
+	new_test_ext().execute_with(|| {
+		let id = schedule(ALICE, 3);
+		System::assert_last_event(Event::Scheduled { who: ALICE, id, when: 3 }.into());
+		assert_eq!(Balances::reserved_balance(ALICE), DEPOSIT);
+		assert_eq!(Tasks::<Test>::get(id), Some((ALICE, 3)));
+
+		run_to_block(3);
+
+		System::assert_has_event(
+			frame_system::Event::Remarked { sender: ALICE, hash: BlakeTwo256::hash(&[]) }.into(),
+		);
+		System::assert_has_event(Event::Dispatched { who: ALICE, id, result: Ok(()) }.into());
+		assert_eq!(Balances::reserved_balance(ALICE), 0);
+		assert_eq!(Tasks::<Test>::get(id), None);
+		assert!(!AccountTasks::<Test>::contains_key(ALICE));
+		assert!(!BlockTasks::<Test>::contains_key(3));
+	});
+}
+
+#[test]
+fn only_schedulable_calls_can_be_scheduled() {
+	new_test_ext().execute_with(|| {
+		let call = Box::new(RuntimeCall::System(frame_system::Call::remark { remark: vec![] }));
+		assert_noop!(
+			SignedScheduler::schedule(RuntimeOrigin::signed(ALICE), 3, call),
+			Error::<Test>::CallNotAllowed
+		);
+	});
+}
+
+#[test]
+fn calls_must_fit_inline() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			SignedScheduler::schedule(RuntimeOrigin::signed(ALICE), 3, remark(vec![0; 128])),
+			Error::<Test>::CallTooLong
+		);
+	});
+}
+
+#[test]
+fn calls_can_not_be_scheduled_in_the_past() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			SignedScheduler::schedule(RuntimeOrigin::signed(ALICE), 1, remark(vec![])),
+			Error::<Test>::InPast
+		);
+	});
+}
+
+#[test]
+fn accounts_can_only_have_a_few_tasks_waiting() {
+	new_test_ext().execute_with(|| {
+		schedule(ALICE, 3);
+		schedule(ALICE, 4);
+		assert_noop!(
+			SignedScheduler::schedule(RuntimeOrigin::signed(ALICE), 5, remark(vec![])),
+			Error::<Test>::TooManyTasks
+		);
+		assert_eq!(Balances::reserved_balance(ALICE), 2 * DEPOSIT);
+
+		// Room is made once a task is dispatched.
+		run_to_block(3);
+		schedule(ALICE, 5);
+	});
+}
+
+#[test]
+fn blocks_keep_room_for_governance() {
+	new_test_ext().execute_with(|| {
+		schedule(ALICE, 3);
+		schedule(BOB, 3);
+		assert_noop!(
+			SignedScheduler::schedule(RuntimeOrigin::signed(CHARLIE), 3, remark(vec![])),
+			Error::<Test>::BlockFull
+		);
+
+		assert_ok!(Scheduler::schedule(RuntimeOrigin::root(), 3, None, 0, remark(vec![])));
+	});
+}
+
+#[test]
+fn accounts_can_only_schedule_with_the_deposit() {
+	new_test_ext().execute_with(|| {
+		let poor = 42;
+		Balances::make_free_balance_be(&poor, DEPOSIT - 1);
+		assert!(SignedScheduler::schedule(RuntimeOrigin::signed(poor), 3, remark(vec![])).is_err());
+		assert!(!AccountTasks::<Test>::contains_key(poor));
+	});
+}
+
+#[test]
+fn owners_can_cancel_their_tasks() {
+	new_test_ext().execute_with(|| {
+		let id = schedule(ALICE, 3);
+		assert_noop!(
+			SignedScheduler::cancel(RuntimeOrigin::signed(BOB), id),
+			Error::<Test>::NotOwner
+		);
+
+		assert_ok!(SignedScheduler::cancel(RuntimeOrigin::signed(ALICE), id));
+		System::assert_last_event(Event::Cancelled { who: ALICE, id }.into());
+		assert_eq!(Balances::reserved_balance(ALICE), 0);
+		assert!(!BlockTasks::<Test>::contains_key(3));
+
+		run_to_block(3);
+		assert!(System::events().iter().all(|record| !matches!(
+			record.event,
+			RuntimeEvent::System(frame_system::Event::Remarked { .. })
+		)));
+		assert_noop!(
+			SignedScheduler::cancel(RuntimeOrigin::signed(ALICE), id),
+			Error::<Test>::TaskNotFound
+		);
+	});
+}
+
+#[test]
+fn deposits_are_released_when_governance_cancels() {
+	new_test_ext().execute_with(|| {
+		let id = schedule(ALICE, 3);
+		assert_ok!(Scheduler::cancel_named(RuntimeOrigin::root(), id));
+
+		assert_ok!(SignedScheduler::cancel(RuntimeOrigin::signed(ALICE), id));
+		assert_eq!(Balances::reserved_balance(ALICE), 0);
+	});
+}
+
+#[test]
+fn only_root_dispatches_tasks() {
+	new_test_ext().execute_with(|| {
+		let id = schedule(ALICE, 3);
+		assert_noop!(
+			SignedScheduler::dispatch_task(RuntimeOrigin::signed(ALICE), id, remark(vec![1])),
+			sp_runtime::DispatchError::BadOrigin
+		);
+	});
+}
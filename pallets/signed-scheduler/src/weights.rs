@@ -0,0 +1,96 @@
+
+//! Weights for pallet_signed_scheduler
+//!
+//! These are estimates from the storage each call accesses, not benchmark results. Regenerate
+//! them on reference hardware with:
+//!
+//! ./target/release/node-template benchmark pallet --chain dev --pallet pallet_signed_scheduler \
+//!     --extrinsic '*' --steps=50 --repeat=20 --output pallets/signed-scheduler/src/weights.rs \
+//!     --template .maintain/frame-weight-template.hbs
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use core::marker::PhantomData;
+
+/// Weight functions needed for pallet_signed_scheduler.
+pub trait WeightInfo {
+	fn schedule() -> Weight;
+	fn cancel() -> Weight;
+	fn dispatch_task() -> Weight;
+}
+
+/// Weights for pallet_signed_scheduler using the Substrate node and recommended hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	/// Storage: SignedScheduler NextTaskIndex (r:1 w:1)
+	/// Storage: SignedScheduler AccountTasks (r:1 w:1)
+	/// Storage: SignedScheduler BlockTasks (r:1 w:1)
+	/// Storage: System Account (r:1 w:1)
+	/// Storage: Scheduler Lookup (r:1 w:1)
+	/// Storage: Scheduler Agenda (r:1 w:1)
+	/// Storage: SignedScheduler Tasks (r:1 w:1)
+	fn schedule() -> Weight {
+		Weight::from_parts(38_000_000, 0)
+			.saturating_add(T::DbWeight::get().reads(7_u64))
+			.saturating_add(T::DbWeight::get().writes(7_u64))
+	}
+	/// Storage: SignedScheduler Tasks (r:1 w:1)
+	/// Storage: SignedScheduler AccountTasks (r:1 w:1)
+	/// Storage: SignedScheduler BlockTasks (r:1 w:1)
+	/// Storage: System Account (r:1 w:1)
+	/// Storage: Scheduler Lookup (r:1 w:1)
+	/// Storage: Scheduler Agenda (r:1 w:1)
+	fn cancel() -> Weight {
+		Weight::from_parts(36_000_000, 0)
+			.saturating_add(T::DbWeight::get().reads(6_u64))
+			.saturating_add(T::DbWeight::get().writes(6_u64))
+	}
+	/// Storage: SignedScheduler Tasks (r:1 w:1)
+	/// Storage: SignedScheduler AccountTasks (r:1 w:1)
+	/// Storage: SignedScheduler BlockTasks (r:1 w:1)
+	/// Storage: System Account (r:1 w:1)
+	fn dispatch_task() -> Weight {
+		Weight::from_parts(24_000_000, 0)
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().writes(4_u64))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	/// Storage: SignedScheduler NextTaskIndex (r:1 w:1)
+	/// Storage: SignedScheduler AccountTasks (r:1 w:1)
+	/// Storage: SignedScheduler BlockTasks (r:1 w:1)
+	/// Storage: System Account (r:1 w:1)
+	/// Storage: Scheduler Lookup (r:1 w:1)
+	/// Storage: Scheduler Agenda (r:1 w:1)
+	/// Storage: SignedScheduler Tasks (r:1 w:1)
+	fn schedule() -> Weight {
+		Weight::from_parts(38_000_000, 0)
+			.saturating_add(RocksDbWeight::get().reads(7_u64))
+			.saturating_add(RocksDbWeight::get().writes(7_u64))
+	}
+	/// Storage: SignedScheduler Tasks (r:1 w:1)
+	/// Storage: SignedScheduler AccountTasks (r:1 w:1)
+	/// Storage: SignedScheduler BlockTasks (r:1 w:1)
+	/// Storage: System Account (r:1 w:1)
+	/// Storage: Scheduler Lookup (r:1 w:1)
+	/// Storage: Scheduler Agenda (r:1 w:1)
+	fn cancel() -> Weight {
+		Weight::from_parts(36_000_000, 0)
+			.saturating_add(RocksDbWeight::get().reads(6_u64))
+			.saturating_add(RocksDbWeight::get().writes(6_u64))
+	}
+	/// Storage: SignedScheduler Tasks (r:1 w:1)
+	/// Storage: SignedScheduler AccountTasks (r:1 w:1)
+	/// Storage: SignedScheduler BlockTasks (r:1 w:1)
+	/// Storage: System Account (r:1 w:1)
+	fn dispatch_task() -> Weight {
+		Weight::from_parts(24_000_000, 0)
+			.saturating_add(RocksDbWeight::get().reads(4_u64))
+			.saturating_add(RocksDbWeight::get().writes(4_u64))
+	}
+}
@@ -0,0 +1,47 @@
+//! Benchmarking setup for pallet-sponsor
+#![cfg(feature = "runtime-benchmarks")]
+use super::*;
+
+#[allow(unused)]
+use crate::Pallet as Sponsor;
+use frame_benchmarking::v2::*;
+use frame_system::RawOrigin;
+use sp_runtime::traits::Bounded;
+
+#[benchmarks]
+mod benchmarks {
+	use super::*;
+
+	#[benchmark]
+	fn set_budget() {
+		let sponsor: T::AccountId = whitelisted_caller();
+		let budget = BalanceOf::<T>::max_value();
+		#[extrinsic_call]
+		set_budget(RawOrigin::Signed(sponsor.clone()), budget);
+
+		assert_eq!(Budgets::<T>::get(&sponsor), budget);
+	}
+
+	#[benchmark]
+	fn add_sponsored() {
+		let sponsor: T::AccountId = whitelisted_caller();
+		let who: T::AccountId = account("who", 0, 0);
+		#[extrinsic_call]
+		add_sponsored(RawOrigin::Signed(sponsor.clone()), who.clone());
+
+		assert!(Sponsored::<T>::contains_key(&sponsor, &who));
+	}
+
+	#[benchmark]
+	fn remove_sponsored() {
+		let sponsor: T::AccountId = whitelisted_caller();
+		let who: T::AccountId = account("who", 0, 0);
+		Sponsored::<T>::insert(&sponsor, &who, ());
+		#[extrinsic_call]
+		remove_sponsored(RawOrigin::Signed(sponsor.clone()), who.clone());
+
+		assert!(!Sponsored::<T>::contains_key(&sponsor, &who));
+	}
+
+	impl_benchmark_test_suite!(Sponsor, crate::mock::new_test_ext(), crate::mock::Test);
+}
@@ -0,0 +1,376 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! Lets an account sponsor the transaction fees of other accounts, e.g. a company paying for the
+//! PoE claims its employees make.
+//!
+//! A sponsor registers the accounts it pays for with [`Pallet::add_sponsored`] and sets how much
+//! it is willing to spend in total with [`Pallet::set_budget`]. Sponsored accounts then name the
+//! sponsor in the [`ChargeSponsor`] signed extension of their transactions, which withdraws the
+//! fee from the sponsor instead of the signer. Only [`Config::SponsoredCalls`] can be sponsored,
+//! and transactions whose fee exceeds the remaining budget are rejected in `validate`.
+//!
+//! Validation can't set budget aside for the transactions waiting in the pool, so the pool may
+//! hold more sponsored transactions than the budget covers. They don't conflict with each other,
+//! being told apart by the signer and nonce like any transaction, and many of them fit into one
+//! block. The budget is enforced again in `pre_dispatch`, which rejects the transactions it no
+//! longer covers.
+//!
+//! The remaining allowance is available to wallets through the [`SponsorApi`] runtime API.
+
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+pub mod weights;
+pub use weights::*;
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::WeightInfo;
+	use frame_support::{
+		dispatch::{DispatchInfo, PostDispatchInfo},
+		pallet_prelude::*,
+		traits::Contains,
+	};
+	use frame_system::pallet_prelude::*;
+	use pallet_transaction_payment::OnChargeTransaction;
+	use sp_runtime::{
+		traits::{
+			AtLeast32BitUnsigned, DispatchInfoOf, Dispatchable, PostDispatchInfoOf,
+			SaturatedConversion, Saturating, SignedExtension, Zero,
+		},
+		transaction_validity::{
+			InvalidTransaction, TransactionValidity, TransactionValidityError, ValidTransaction,
+		},
+		FixedPointOperand,
+	};
+	use sp_std::fmt;
+
+	pub type BalanceOf<T> = <T as Config>::Balance;
+	/// The balance type fees are computed and charged in.
+	pub type FeeOf<T> =
+		<<T as pallet_transaction_payment::Config>::OnChargeTransaction as OnChargeTransaction<
+			T,
+		>>::Balance;
+	type LiquidityInfoOf<T> =
+		<<T as pallet_transaction_payment::Config>::OnChargeTransaction as OnChargeTransaction<
+			T,
+		>>::LiquidityInfo;
+
+	/// `InvalidTransaction::Custom` code of transactions naming a sponsor that doesn't pay for
+	/// the signer.
+	pub const NOT_SPONSORED: u8 = 0;
+	/// `InvalidTransaction::Custom` code of transactions whose fee exceeds the sponsor's budget.
+	pub const BUDGET_EXCEEDED: u8 = 1;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config + pallet_transaction_payment::Config {
+		/// Because this pallet emits events, it depends on the runtime's definition of an event.
+		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+		/// The balance type budgets are kept in, which is the type fees are charged in.
+		type Balance: Parameter
+			+ MaxEncodedLen
+			+ AtLeast32BitUnsigned
+			+ Copy
+			+ Default
+			+ IsType<FeeOf<Self>>;
+		/// Calls whose fees can be paid by a sponsor.
+		type SponsoredCalls: Contains<<Self as frame_system::Config>::RuntimeCall>;
+		/// Weight information for extrinsics in this pallet.
+		type WeightInfo: WeightInfo;
+	}
+
+	/// The fees each sponsor is still willing to pay, for all the accounts it sponsors together.
+	#[pallet::storage]
+	#[pallet::getter(fn budget)]
+	pub type Budgets<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, BalanceOf<T>, ValueQuery>;
+
+	/// The accounts each sponsor pays for, keyed by sponsor.
+	#[pallet::storage]
+	pub type Sponsored<T: Config> =
+		StorageDoubleMap<_, Blake2_128Concat, T::AccountId, Blake2_128Concat, T::AccountId, ()>;
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		BudgetSet {
+			sponsor: T::AccountId,
+			budget: BalanceOf<T>,
+		},
+		SponsorshipAdded {
+			sponsor: T::AccountId,
+			who: T::AccountId,
+		},
+		SponsorshipRemoved {
+			sponsor: T::AccountId,
+			who: T::AccountId,
+		},
+		/// `sponsor` paid the fee of a transaction signed by `who`.
+		SponsoredFeePaid {
+			sponsor: T::AccountId,
+			who: T::AccountId,
+			actual_fee: BalanceOf<T>,
+		},
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The account is already sponsored by the caller.
+		AlreadySponsored,
+		/// The account is not sponsored by the caller.
+		NotSponsored,
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Set the fees the caller is willing to pay for the accounts it sponsors, replacing
+		/// whatever is left of the previous budget.
+		#[pallet::call_index(0)]
+		#[pallet::weight(T::WeightInfo::set_budget())]
+		pub fn set_budget(origin: OriginFor<T>, budget: BalanceOf<T>) -> DispatchResult {
+			let sponsor = ensure_signed(origin)?;
+			Budgets::<T>::insert(&sponsor, budget);
+			Self::deposit_event(Event::BudgetSet { sponsor, budget });
+			Ok(())
+		}
+
+		/// Pay the fees of the sponsored calls of `who`.
+		#[pallet::call_index(1)]
+		#[pallet::weight(T::WeightInfo::add_sponsored())]
+		pub fn add_sponsored(origin: OriginFor<T>, who: T::AccountId) -> DispatchResult {
+			let sponsor = ensure_signed(origin)?;
+			ensure!(!Sponsored::<T>::contains_key(&sponsor, &who), Error::<T>::AlreadySponsored);
+
+			Sponsored::<T>::insert(&sponsor, &who, ());
+			Self::deposit_event(Event::SponsorshipAdded { sponsor, who });
+			Ok(())
+		}
+
+		/// Stop paying the fees of `who`.
+		#[pallet::call_index(2)]
+		#[pallet::weight(T::WeightInfo::remove_sponsored())]
+		pub fn remove_sponsored(origin: OriginFor<T>, who: T::AccountId) -> DispatchResult {
+			let sponsor = ensure_signed(origin)?;
+			ensure!(Sponsored::<T>::contains_key(&sponsor, &who), Error::<T>::NotSponsored);
+
+			Sponsored::<T>::remove(&sponsor, &who);
+			Self::deposit_event(Event::SponsorshipRemoved { sponsor, who });
+			Ok(())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// The fees `sponsor` still pays for `who`, or `None` if it doesn't sponsor `who`.
+		pub fn allowance(sponsor: &T::AccountId, who: &T::AccountId) -> Option<BalanceOf<T>> {
+			Sponsored::<T>::contains_key(sponsor, who).then(|| Budgets::<T>::get(sponsor))
+		}
+	}
+
+	impl<T: Config> Pallet<T>
+	where
+		<T as frame_system::Config>::RuntimeCall:
+			Dispatchable<Info = DispatchInfo, PostInfo = PostDispatchInfo>,
+		FeeOf<T>: FixedPointOperand,
+	{
+		/// Checks that `sponsor` pays for `who` to make `call`, returning the fee it would pay.
+		pub fn check_sponsorship(
+			sponsor: &T::AccountId,
+			who: &T::AccountId,
+			call: &<T as frame_system::Config>::RuntimeCall,
+			info: &DispatchInfoOf<<T as frame_system::Config>::RuntimeCall>,
+			len: usize,
+		) -> Result<FeeOf<T>, TransactionValidityError> {
+			if !T::SponsoredCalls::contains(call) {
+				return Err(InvalidTransaction::Call.into())
+			}
+			let budget =
+				Self::allowance(sponsor, who).ok_or(InvalidTransaction::Custom(NOT_SPONSORED))?;
+			let fee = pallet_transaction_payment::Pallet::<T>::compute_fee(
+				len.saturated_into(),
+				info,
+				Zero::zero(),
+			);
+			if BalanceOf::<T>::from(fee) > budget {
+				return Err(InvalidTransaction::Custom(BUDGET_EXCEEDED).into())
+			}
+			Ok(fee)
+		}
+	}
+
+	/// Charges the fee of a transaction either to the signer, through the payment extension `S`,
+	/// or to a sponsor of the signer. Sponsored transactions can't carry a tip.
+	#[derive(Encode, Decode, Clone, Eq, PartialEq, TypeInfo)]
+	#[scale_info(skip_type_params(T))]
+	pub enum ChargeSponsor<T: Config + Send + Sync, S> {
+		/// The signer pays through `S`.
+		Signer(S),
+		/// The given sponsor pays.
+		Sponsor(T::AccountId),
+	}
+
+	impl<T: Config + Send + Sync, S: fmt::Debug> fmt::Debug for ChargeSponsor<T, S> {
+		fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+			match self {
+				Self::Signer(payment) => write!(f, "ChargeSponsor({:?})", payment),
+				Self::Sponsor(sponsor) => write!(f, "ChargeSponsor(sponsor: {:?})", sponsor),
+			}
+		}
+	}
+
+	/// What [`ChargeSponsor`] needs to settle the fee after dispatch.
+	pub enum SponsorPre<T: Config, S: SignedExtension> {
+		Signer(S::Pre),
+		Sponsor {
+			sponsor: T::AccountId,
+			who: T::AccountId,
+			/// The fee withdrawn before dispatch, which was taken from the budget.
+			fee: FeeOf<T>,
+			liquidity: LiquidityInfoOf<T>,
+		},
+	}
+
+	impl<T: Config + Send + Sync, S> SignedExtension for ChargeSponsor<T, S>
+	where
+		S: SignedExtension<
+			AccountId = T::AccountId,
+			Call = <T as frame_system::Config>::RuntimeCall,
+			AdditionalSigned = (),
+		>,
+		<T as frame_system::Config>::RuntimeCall:
+			Dispatchable<Info = DispatchInfo, PostInfo = PostDispatchInfo>,
+		FeeOf<T>: FixedPointOperand,
+	{
+		const IDENTIFIER: &'static str = "ChargeSponsor";
+		type AccountId = T::AccountId;
+		type Call = <T as frame_system::Config>::RuntimeCall;
+		type AdditionalSigned = ();
+		type Pre = SponsorPre<T, S>;
+
+		fn additional_signed(&self) -> Result<Self::AdditionalSigned, TransactionValidityError> {
+			Ok(())
+		}
+
+		fn validate(
+			&self,
+			who: &Self::AccountId,
+			call: &Self::Call,
+			info: &DispatchInfoOf<Self::Call>,
+			len: usize,
+		) -> TransactionValidity {
+			match self {
+				Self::Signer(payment) => payment.validate(who, call, info, len),
+				Self::Sponsor(sponsor) => {
+					let fee = Pallet::<T>::check_sponsorship(sponsor, who, call, info, len)?;
+					// Only checks the sponsor can pay; the state is discarded after validation.
+					T::OnChargeTransaction::withdraw_fee(sponsor, call, info, fee, Zero::zero())?;
+					Ok(ValidTransaction::default())
+				},
+			}
+		}
+
+		fn pre_dispatch(
+			self,
+			who: &Self::AccountId,
+			call: &Self::Call,
+			info: &DispatchInfoOf<Self::Call>,
+			len: usize,
+		) -> Result<Self::Pre, TransactionValidityError> {
+			match self {
+				Self::Signer(payment) =>
+					payment.pre_dispatch(who, call, info, len).map(SponsorPre::Signer),
+				Self::Sponsor(sponsor) => {
+					let fee = Pallet::<T>::check_sponsorship(&sponsor, who, call, info, len)?;
+					let liquidity = T::OnChargeTransaction::withdraw_fee(
+						&sponsor,
+						call,
+						info,
+						fee,
+						Zero::zero(),
+					)?;
+					Budgets::<T>::mutate(&sponsor, |budget| {
+						*budget = budget.saturating_sub(fee.into())
+					});
+					Ok(SponsorPre::Sponsor { sponsor, who: who.clone(), fee, liquidity })
+				},
+			}
+		}
+
+		fn validate_unsigned(
+			call: &Self::Call,
+			info: &DispatchInfoOf<Self::Call>,
+			len: usize,
+		) -> TransactionValidity {
+			S::validate_unsigned(call, info, len)
+		}
+
+		fn pre_dispatch_unsigned(
+			call: &Self::Call,
+			info: &DispatchInfoOf<Self::Call>,
+			len: usize,
+		) -> Result<(), TransactionValidityError> {
+			S::pre_dispatch_unsigned(call, info, len)
+		}
+
+		fn post_dispatch(
+			pre: Option<Self::Pre>,
+			info: &DispatchInfoOf<Self::Call>,
+			post_info: &PostDispatchInfoOf<Self::Call>,
+			len: usize,
+			result: &DispatchResult,
+		) -> Result<(), TransactionValidityError> {
+			match pre {
+				Some(SponsorPre::Signer(pre)) =>
+					S::post_dispatch(Some(pre), info, post_info, len, result),
+				Some(SponsorPre::Sponsor { sponsor, who, fee, liquidity }) => {
+					let actual_fee = pallet_transaction_payment::Pallet::<T>::compute_actual_fee(
+						len.saturated_into(),
+						info,
+						post_info,
+						Zero::zero(),
+					);
+					T::OnChargeTransaction::correct_and_deposit_fee(
+						&sponsor,
+						info,
+						post_info,
+						actual_fee,
+						Zero::zero(),
+						liquidity,
+					)?;
+					// Give back what was withdrawn from the budget but refunded to the sponsor.
+					Budgets::<T>::mutate(&sponsor, |budget| {
+						*budget = budget.saturating_add(fee.saturating_sub(actual_fee).into())
+					});
+					Pallet::<T>::deposit_event(Event::SponsoredFeePaid {
+						sponsor,
+						who,
+						actual_fee: actual_fee.into(),
+					});
+					Ok(())
+				},
+				None => S::post_dispatch(None, info, post_info, len, result),
+			}
+		}
+	}
+}
+
+sp_api::decl_runtime_apis! {
+	/// Lets wallets look up what a sponsor still pays for an account.
+	pub trait SponsorApi<AccountId, Balance>
+	where
+		AccountId: codec::Codec,
+		Balance: codec::Codec,
+	{
+		/// The fees `sponsor` still pays for `who`, or `None` if it doesn't sponsor `who`.
+		fn allowance(sponsor: AccountId, who: AccountId) -> Option<Balance>;
+	}
+}
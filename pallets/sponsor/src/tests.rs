@@ -0,0 +1,153 @@
+use crate::{mock::*, ChargeSponsor, Error, Event, BUDGET_EXCEEDED, NOT_SPONSORED};
+use frame_support::{
+	assert_noop, assert_ok,
+	dispatch::{DispatchInfo, Pays, PostDispatchInfo},
+	weights::Weight,
+};
+use sp_runtime::{traits::SignedExtension, transaction_validity::InvalidTransaction};
+
+const LEN: usize = 100;
+
+fn remark() -> RuntimeCall {
+	RuntimeCall::System(frame_system::Call::remark { remark: vec![] })
+}
+
+fn info() -> DispatchInfo {
+	DispatchInfo { weight: Weight::from_parts(1_000, 0), ..Default::default() }
+}
+
+fn sponsored() -> ChargeSponsor<Test, MockPayment> {
+	ChargeSponsor::Sponsor(SPONSOR)
+}
+
+fn fee() -> u64 {
+	TransactionPayment::compute_fee(LEN as u32, &info(), 0)
+}
+
+/// Lets `SPONSOR` pay for `EMPLOYEE` with a budget of `budget`.
+fn sponsor_employee(budget: u64) {
+	assert_ok!(Sponsor::set_budget(RuntimeOrigin::signed(SPONSOR), budget));
+	assert_ok!(Sponsor::add_sponsored(RuntimeOrigin::signed(SPONSOR), EMPLOYEE));
+}
+
+#[test]
+fn sponsors_manage_who_they_pay_for() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(Sponsor::allowance(&SPONSOR, &EMPLOYEE), None);
+		assert_ok!(Sponsor::set_budget(RuntimeOrigin::signed(SPONSOR), 500));
+		System::assert_last_event(Event::BudgetSet { sponsor: SPONSOR, budget: 500 }.into());
+		assert_ok!(Sponsor::add_sponsored(RuntimeOrigin::signed(SPONSOR), EMPLOYEE));
+		System::assert_last_event(
+			Event::SponsorshipAdded { sponsor: SPONSOR, who: EMPLOYEE }.into(),
+		);
+		assert_eq!(Sponsor::allowance(&SPONSOR, &EMPLOYEE), Some(500));
+		assert_noop!(
+			Sponsor::add_sponsored(RuntimeOrigin::signed(SPONSOR), EMPLOYEE),
+			Error::<Test>::AlreadySponsored
+		);
+
+		assert_ok!(Sponsor::remove_sponsored(RuntimeOrigin::signed(SPONSOR), EMPLOYEE));
+		System::assert_last_event(
+			Event::SponsorshipRemoved { sponsor: SPONSOR, who: EMPLOYEE }.into(),
+		);
+		assert_eq!(Sponsor::allowance(&SPONSOR, &EMPLOYEE), None);
+		assert_noop!(
+			Sponsor::remove_sponsored(RuntimeOrigin::signed(SPONSOR), EMPLOYEE),
+			Error::<Test>::NotSponsored
+		);
+	});
+}
+
+#[test]
+fn sponsors_pay_the_fee_and_get_unused_weight_back() {
+	new_test_ext().execute_with(|| {
+		sponsor_employee(10 * fee());
+		assert_ok!(sponsored().validate(&EMPLOYEE, &remark(), &info(), LEN));
+		let pre = sponsored().pre_dispatch(&EMPLOYEE, &remark(), &info(), LEN).unwrap();
+		assert_eq!(Balances::free_balance(SPONSOR), ENDOWMENT - fee());
+		assert_eq!(Sponsor::budget(SPONSOR), 9 * fee());
+
+		let post_info = PostDispatchInfo {
+			actual_weight: Some(Weight::from_parts(400, 0)),
+			pays_fee: Pays::Yes,
+		};
+		let actual_fee = TransactionPayment::compute_actual_fee(LEN as u32, &info(), &post_info, 0);
+		assert!(actual_fee < fee());
+		assert_ok!(ChargeSponsor::<Test, MockPayment>::post_dispatch(
+			Some(pre),
+			&info(),
+			&post_info,
+			LEN,
+			&Ok(())
+		));
+		assert_eq!(Balances::free_balance(SPONSOR), ENDOWMENT - actual_fee);
+		assert_eq!(Balances::free_balance(EMPLOYEE), 0);
+		assert_eq!(Sponsor::budget(SPONSOR), 10 * fee() - actual_fee);
+		System::assert_last_event(
+			Event::SponsoredFeePaid { sponsor: SPONSOR, who: EMPLOYEE, actual_fee }.into(),
+		);
+	});
+}
+
+#[test]
+fn transactions_over_the_budget_are_invalid() {
+	new_test_ext().execute_with(|| {
+		sponsor_employee(fee() - 1);
+		let exceeded = Err(InvalidTransaction::Custom(BUDGET_EXCEEDED).into());
+		assert_eq!(sponsored().validate(&EMPLOYEE, &remark(), &info(), LEN), exceeded);
+		assert_eq!(
+			sponsored().pre_dispatch(&EMPLOYEE, &remark(), &info(), LEN).err(),
+			exceeded.err()
+		);
+
+		assert_ok!(Sponsor::set_budget(RuntimeOrigin::signed(SPONSOR), fee()));
+		assert!(sponsored().pre_dispatch(&EMPLOYEE, &remark(), &info(), LEN).is_ok());
+		assert_eq!(sponsored().validate(&EMPLOYEE, &remark(), &info(), LEN), exceeded);
+	});
+}
+
+#[test]
+fn only_sponsored_accounts_and_calls_are_paid_for() {
+	new_test_ext().execute_with(|| {
+		sponsor_employee(10 * fee());
+		assert_eq!(
+			sponsored().validate(&STRANGER, &remark(), &info(), LEN),
+			Err(InvalidTransaction::Custom(NOT_SPONSORED).into())
+		);
+		let remark_with_event =
+			RuntimeCall::System(frame_system::Call::remark_with_event { remark: vec![] });
+		assert_eq!(
+			sponsored().validate(&EMPLOYEE, &remark_with_event, &info(), LEN),
+			Err(InvalidTransaction::Call.into())
+		);
+		assert_eq!(Balances::free_balance(SPONSOR), ENDOWMENT);
+
+		// Anybody can still pay for themselves.
+		let paid = ChargeSponsor::<Test, _>::Signer(MockPayment);
+		assert_ok!(paid.validate(&STRANGER, &remark_with_event, &info(), LEN));
+	});
+}
+
+#[test]
+fn several_sponsored_transactions_fit_in_one_block() {
+	new_test_ext().execute_with(|| {
+		sponsor_employee(3 * fee());
+		assert_ok!(Sponsor::add_sponsored(RuntimeOrigin::signed(SPONSOR), STRANGER));
+
+		// Nothing makes them conflict in the pool.
+		for who in [EMPLOYEE, STRANGER] {
+			let valid = sponsored().validate(&who, &remark(), &info(), LEN).unwrap();
+			assert!(valid.provides.is_empty());
+		}
+
+		// The block takes as many as the budget covers.
+		assert_ok!(sponsored().pre_dispatch(&EMPLOYEE, &remark(), &info(), LEN));
+		assert_ok!(sponsored().pre_dispatch(&STRANGER, &remark(), &info(), LEN));
+		assert_ok!(sponsored().pre_dispatch(&EMPLOYEE, &remark(), &info(), LEN));
+		assert_eq!(
+			sponsored().pre_dispatch(&STRANGER, &remark(), &info(), LEN).err(),
+			Some(InvalidTransaction::Custom(BUDGET_EXCEEDED).into())
+		);
+		assert_eq!(Sponsor::budget(SPONSOR), 0);
+	});
+}
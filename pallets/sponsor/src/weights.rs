@@ -0,0 +1,66 @@
+
+//! Weights for pallet_sponsor
+//!
+//! These are estimates from the storage each call accesses, not benchmark results. Regenerate
+//! them on reference hardware with:
+//!
+//! ./target/release/node-template benchmark pallet --chain dev --pallet pallet_sponsor \
+//!     --extrinsic '*' --steps=50 --repeat=20 --output pallets/sponsor/src/weights.rs \
+//!     --template .maintain/frame-weight-template.hbs
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use core::marker::PhantomData;
+
+/// Weight functions needed for pallet_sponsor.
+pub trait WeightInfo {
+	fn set_budget() -> Weight;
+	fn add_sponsored() -> Weight;
+	fn remove_sponsored() -> Weight;
+}
+
+/// Weights for pallet_sponsor using the Substrate node and recommended hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	/// Storage: Sponsorship Budgets (r:0 w:1)
+	fn set_budget() -> Weight {
+		Weight::from_parts(10_000_000, 0)
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: Sponsorship Sponsored (r:1 w:1)
+	fn add_sponsored() -> Weight {
+		Weight::from_parts(14_000_000, 0)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: Sponsorship Sponsored (r:1 w:1)
+	fn remove_sponsored() -> Weight {
+		Weight::from_parts(14_000_000, 0)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	/// Storage: Sponsorship Budgets (r:0 w:1)
+	fn set_budget() -> Weight {
+		Weight::from_parts(10_000_000, 0)
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: Sponsorship Sponsored (r:1 w:1)
+	fn add_sponsored() -> Weight {
+		Weight::from_parts(14_000_000, 0)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: Sponsorship Sponsored (r:1 w:1)
+	fn remove_sponsored() -> Weight {
+		Weight::from_parts(14_000_000, 0)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+}
@@ -0,0 +1,47 @@
+//! Benchmarking setup for pallet-tx-pause
+#![cfg(feature = "runtime-benchmarks")]
+use super::*;
+
+#[allow(unused)]
+use crate::Pallet as TxPause;
+use frame_benchmarking::v2::*;
+use frame_support::traits::EnsureOrigin;
+use sp_std::vec;
+
+/// A name of the longest length, for a call that isn't whitelisted.
+fn full_name<T: Config>() -> FullNameOf<T> {
+	let name = vec![b'x'; T::MaxNameLen::get() as usize];
+	(name.clone().try_into().unwrap(), Some(name.try_into().unwrap()))
+}
+
+#[benchmarks]
+mod benchmarks {
+	use super::*;
+
+	#[benchmark]
+	fn pause() -> Result<(), BenchmarkError> {
+		let origin =
+			T::PauseOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+		let full_name = full_name::<T>();
+		#[extrinsic_call]
+		pause(origin as T::RuntimeOrigin, full_name.clone());
+
+		assert!(PausedCalls::<T>::contains_key(full_name));
+		Ok(())
+	}
+
+	#[benchmark]
+	fn unpause() -> Result<(), BenchmarkError> {
+		let origin =
+			T::UnpauseOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+		let full_name = full_name::<T>();
+		PausedCalls::<T>::insert(&full_name, ());
+		#[extrinsic_call]
+		unpause(origin as T::RuntimeOrigin, full_name.clone());
+
+		assert!(!PausedCalls::<T>::contains_key(full_name));
+		Ok(())
+	}
+
+	impl_benchmark_test_suite!(TxPause, crate::mock::new_test_ext(), crate::mock::Test);
+}
@@ -0,0 +1,207 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! Lets a privileged origin pause single calls or whole pallets by name, e.g. `("Kitties",
+//! Some("breed"))` or `("Kitties", None)`.
+//!
+//! The pallet is meant to be part of the runtime's `BaseCallFilter`, which stops paused calls
+//! from being dispatched, and its [`CheckNotPaused`] signed extension keeps them out of the
+//! transaction pool.
+
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+pub mod weights;
+pub use weights::*;
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::WeightInfo;
+	use frame_support::{
+		dispatch::{DispatchInfo, GetCallMetadata},
+		pallet_prelude::*,
+		traits::Contains,
+	};
+	use frame_system::pallet_prelude::*;
+	use sp_runtime::{
+		traits::{DispatchInfoOf, SignedExtension},
+		transaction_validity::{
+			InvalidTransaction, TransactionValidity, TransactionValidityError, ValidTransaction,
+		},
+	};
+	use sp_std::{fmt, marker::PhantomData, prelude::*};
+
+	pub type PalletNameOf<T> = BoundedVec<u8, <T as Config>::MaxNameLen>;
+	pub type CallNameOf<T> = BoundedVec<u8, <T as Config>::MaxNameLen>;
+	/// A pallet name and either one of its calls or `None` for all of them.
+	pub type FullNameOf<T> = (PalletNameOf<T>, Option<CallNameOf<T>>);
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// Because this pallet emits events, it depends on the runtime's definition of an event.
+		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+		/// The runtime call, used to look up the pallet and call name of a transaction.
+		type RuntimeCall: Parameter
+			+ GetCallMetadata
+			+ IsType<<Self as frame_system::Config>::RuntimeCall>;
+		/// Origin allowed to pause calls.
+		type PauseOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+		/// Origin allowed to unpause calls.
+		type UnpauseOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+		/// Calls and pallets that can never be paused.
+		type WhitelistedCalls: Contains<FullNameOf<Self>>;
+		/// Maximum length of a pallet or call name.
+		#[pallet::constant]
+		type MaxNameLen: Get<u32>;
+		/// Weight information for extrinsics in this pallet.
+		type WeightInfo: WeightInfo;
+	}
+
+	/// The paused calls and pallets.
+	#[pallet::storage]
+	#[pallet::getter(fn paused)]
+	pub type PausedCalls<T: Config> = StorageMap<_, Blake2_128Concat, FullNameOf<T>, ()>;
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		CallPaused { full_name: FullNameOf<T> },
+		CallUnpaused { full_name: FullNameOf<T> },
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The call or pallet is already paused.
+		IsPaused,
+		/// The call or pallet is not paused.
+		IsUnpaused,
+		/// The call or pallet is whitelisted and can not be paused.
+		Unpausable,
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Pause a call, or every call of a pallet if `full_name.1` is `None`.
+		#[pallet::call_index(0)]
+		#[pallet::weight(T::WeightInfo::pause())]
+		pub fn pause(origin: OriginFor<T>, full_name: FullNameOf<T>) -> DispatchResult {
+			T::PauseOrigin::ensure_origin(origin)?;
+			ensure!(!T::WhitelistedCalls::contains(&full_name), Error::<T>::Unpausable);
+			ensure!(!PausedCalls::<T>::contains_key(&full_name), Error::<T>::IsPaused);
+
+			PausedCalls::<T>::insert(&full_name, ());
+			Self::deposit_event(Event::CallPaused { full_name });
+			Ok(())
+		}
+
+		/// Lift a pause set by [`Pallet::pause`].
+		#[pallet::call_index(1)]
+		#[pallet::weight(T::WeightInfo::unpause())]
+		pub fn unpause(origin: OriginFor<T>, full_name: FullNameOf<T>) -> DispatchResult {
+			T::UnpauseOrigin::ensure_origin(origin)?;
+			ensure!(PausedCalls::<T>::contains_key(&full_name), Error::<T>::IsUnpaused);
+
+			PausedCalls::<T>::remove(&full_name);
+			Self::deposit_event(Event::CallUnpaused { full_name });
+			Ok(())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// Whether `call` is paused, either by itself or because its whole pallet is.
+		pub fn is_paused(call: &<T as Config>::RuntimeCall) -> bool {
+			let metadata = call.get_call_metadata();
+			let pallet_name =
+				match PalletNameOf::<T>::try_from(metadata.pallet_name.as_bytes().to_vec()) {
+					Ok(name) => name,
+					// Names that don't fit can't have been paused.
+					Err(_) => return false,
+				};
+			if PausedCalls::<T>::contains_key((&pallet_name, None::<CallNameOf<T>>)) {
+				return true
+			}
+
+			match CallNameOf::<T>::try_from(metadata.function_name.as_bytes().to_vec()) {
+				Ok(call_name) => PausedCalls::<T>::contains_key((pallet_name, Some(call_name))),
+				Err(_) => false,
+			}
+		}
+	}
+
+	/// Lets through every call that is not paused; meant to be used in the `BaseCallFilter`.
+	impl<T: Config> Contains<<T as Config>::RuntimeCall> for Pallet<T> {
+		fn contains(call: &<T as Config>::RuntimeCall) -> bool {
+			!Self::is_paused(call)
+		}
+	}
+
+	/// Rejects transactions calling a paused call, so they never enter the transaction pool.
+	#[derive(Encode, Decode, Clone, Eq, PartialEq, TypeInfo)]
+	#[scale_info(skip_type_params(T))]
+	pub struct CheckNotPaused<T: Config + Send + Sync>(PhantomData<T>);
+
+	impl<T: Config + Send + Sync> CheckNotPaused<T> {
+		pub fn new() -> Self {
+			Self(PhantomData)
+		}
+	}
+
+	impl<T: Config + Send + Sync> Default for CheckNotPaused<T> {
+		fn default() -> Self {
+			Self::new()
+		}
+	}
+
+	impl<T: Config + Send + Sync> fmt::Debug for CheckNotPaused<T> {
+		fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+			write!(f, "CheckNotPaused")
+		}
+	}
+
+	impl<T: Config + Send + Sync> SignedExtension for CheckNotPaused<T>
+	where
+		<T as frame_system::Config>::RuntimeCall: Dispatchable<Info = DispatchInfo>,
+	{
+		const IDENTIFIER: &'static str = "CheckNotPaused";
+		type AccountId = T::AccountId;
+		type Call = <T as frame_system::Config>::RuntimeCall;
+		type AdditionalSigned = ();
+		type Pre = ();
+
+		fn additional_signed(&self) -> Result<Self::AdditionalSigned, TransactionValidityError> {
+			Ok(())
+		}
+
+		fn validate(
+			&self,
+			_who: &Self::AccountId,
+			call: &Self::Call,
+			_info: &DispatchInfoOf<Self::Call>,
+			_len: usize,
+		) -> TransactionValidity {
+			if Pallet::<T>::is_paused(<T as Config>::RuntimeCall::from_ref(call)) {
+				return InvalidTransaction::Call.into()
+			}
+			Ok(ValidTransaction::default())
+		}
+
+		fn pre_dispatch(
+			self,
+			who: &Self::AccountId,
+			call: &Self::Call,
+			info: &DispatchInfoOf<Self::Call>,
+			len: usize,
+		) -> Result<Self::Pre, TransactionValidityError> {
+			self.validate(who, call, info, len).map(|_| ())
+		}
+	}
+}
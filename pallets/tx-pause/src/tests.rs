@@ -0,0 +1,113 @@
+use crate::{mock::*, CheckNotPaused, Error, Event, FullNameOf};
+use frame_support::{assert_noop, assert_ok, dispatch::DispatchInfo};
+use sp_runtime::{
+	traits::{BadOrigin, Dispatchable, SignedExtension},
+	transaction_validity::InvalidTransaction,
+};
+
+fn full_name(pallet: &[u8], call: Option<&[u8]>) -> FullNameOf<Test> {
+	(pallet.to_vec().try_into().unwrap(), call.map(|call| call.to_vec().try_into().unwrap()))
+}
+
+fn remark() -> RuntimeCall {
+	RuntimeCall::System(frame_system::Call::remark { remark: vec![] })
+}
+
+#[test]
+fn pause_a_call() {
+	new_test_ext().execute_with(|| {
+		let name = full_name(b"System", Some(b"remark"));
+		assert_ok!(TxPause::pause(RuntimeOrigin::root(), name.clone()));
+		System::assert_last_event(Event::CallPaused { full_name: name }.into());
+
+		assert_noop!(
+			remark().dispatch(RuntimeOrigin::signed(1)),
+			frame_system::Error::<Test>::CallFiltered
+		);
+		// Other calls of the pallet are unaffected.
+		assert_ok!(RuntimeCall::System(frame_system::Call::remark_with_event { remark: vec![] })
+			.dispatch(RuntimeOrigin::signed(1)));
+	});
+}
+
+#[test]
+fn pause_a_whole_pallet() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TxPause::pause(RuntimeOrigin::root(), full_name(b"System", None)));
+
+		assert_noop!(
+			remark().dispatch(RuntimeOrigin::signed(1)),
+			frame_system::Error::<Test>::CallFiltered
+		);
+		assert_noop!(
+			RuntimeCall::System(frame_system::Call::remark_with_event { remark: vec![] })
+				.dispatch(RuntimeOrigin::signed(1)),
+			frame_system::Error::<Test>::CallFiltered
+		);
+	});
+}
+
+#[test]
+fn unpause_a_call() {
+	new_test_ext().execute_with(|| {
+		let name = full_name(b"System", Some(b"remark"));
+		assert_ok!(TxPause::pause(RuntimeOrigin::root(), name.clone()));
+		assert_ok!(TxPause::unpause(RuntimeOrigin::root(), name.clone()));
+		System::assert_last_event(Event::CallUnpaused { full_name: name }.into());
+
+		assert_ok!(remark().dispatch(RuntimeOrigin::signed(1)));
+	});
+}
+
+#[test]
+fn pause_failed_bad_origin() {
+	new_test_ext().execute_with(|| {
+		let name = full_name(b"System", Some(b"remark"));
+		assert_noop!(TxPause::pause(RuntimeOrigin::signed(1), name.clone()), BadOrigin);
+		assert_ok!(TxPause::pause(RuntimeOrigin::root(), name.clone()));
+		assert_noop!(TxPause::unpause(RuntimeOrigin::signed(1), name), BadOrigin);
+	});
+}
+
+#[test]
+fn pause_failed_already_paused() {
+	new_test_ext().execute_with(|| {
+		let name = full_name(b"System", Some(b"remark"));
+		assert_ok!(TxPause::pause(RuntimeOrigin::root(), name.clone()));
+		assert_noop!(TxPause::pause(RuntimeOrigin::root(), name), Error::<Test>::IsPaused);
+	});
+}
+
+#[test]
+fn pause_failed_whitelisted() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			TxPause::pause(RuntimeOrigin::root(), full_name(b"TxPause", None)),
+			Error::<Test>::Unpausable
+		);
+	});
+}
+
+#[test]
+fn unpause_failed_not_paused() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			TxPause::unpause(RuntimeOrigin::root(), full_name(b"System", Some(b"remark"))),
+			Error::<Test>::IsUnpaused
+		);
+	});
+}
+
+#[test]
+fn paused_calls_are_invalid_transactions() {
+	new_test_ext().execute_with(|| {
+		let info = DispatchInfo::default();
+		assert_ok!(CheckNotPaused::<Test>::new().validate(&1, &remark(), &info, 0));
+
+		assert_ok!(TxPause::pause(RuntimeOrigin::root(), full_name(b"System", Some(b"remark"))));
+		assert_eq!(
+			CheckNotPaused::<Test>::new().validate(&1, &remark(), &info, 0),
+			Err(InvalidTransaction::Call.into())
+		);
+	});
+}
@@ -0,0 +1,55 @@
+
+//! Weights for pallet_tx_pause
+//!
+//! These are estimates from the storage each call accesses, not benchmark results. Regenerate
+//! them on reference hardware with:
+//!
+//! ./target/release/node-template benchmark pallet --chain dev --pallet pallet_tx_pause \
+//!     --extrinsic '*' --steps=50 --repeat=20 --output pallets/tx-pause/src/weights.rs \
+//!     --template .maintain/frame-weight-template.hbs
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use core::marker::PhantomData;
+
+/// Weight functions needed for pallet_tx_pause.
+pub trait WeightInfo {
+	fn pause() -> Weight;
+	fn unpause() -> Weight;
+}
+
+/// Weights for pallet_tx_pause using the Substrate node and recommended hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	/// Storage: TxPause PausedCalls (r:1 w:1)
+	fn pause() -> Weight {
+		Weight::from_parts(12_000_000, 0)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: TxPause PausedCalls (r:1 w:1)
+	fn unpause() -> Weight {
+		Weight::from_parts(12_000_000, 0)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	/// Storage: TxPause PausedCalls (r:1 w:1)
+	fn pause() -> Weight {
+		Weight::from_parts(12_000_000, 0)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: TxPause PausedCalls (r:1 w:1)
+	fn unpause() -> Weight {
+		Weight::from_parts(12_000_000, 0)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+}
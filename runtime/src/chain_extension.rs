@@ -0,0 +1,67 @@
+//! Chain extension giving ink! contracts access to `pallet_kitties` and `pallet_poe`.
+//!
+//! Every function reads its SCALE encoded arguments from the contract's input buffer and, for
+//! lookups, writes the SCALE encoded result back to the output buffer.
+
+use crate::*;
+use frame_support::{dispatch::DispatchError, traits::Get, BoundedVec};
+use pallet_contracts::chain_extension::{ChainExtension, Environment, Ext, InitState, RetVal};
+use pallet_kitties::{KittyId, WeightInfo};
+
+/// `Kitties::owner(kitty_id: KittyId) -> Option<AccountId>`
+pub const KITTY_OWNER: u16 = 0x0001;
+/// `Kitties::kitties(kitty_id: KittyId) -> Option<Kitty>`
+pub const KITTY: u16 = 0x0002;
+/// Transfer `(to: AccountId, kitty_id: KittyId)` owned by the calling contract.
+pub const KITTY_TRANSFER: u16 = 0x0003;
+/// `PoeModule::Proofs(claim: Vec<u8>) -> Option<(AccountId, BlockNumber)>`
+pub const POE_CLAIM: u16 = 0x0101;
+
+/// Status returned by [`KITTY_TRANSFER`] when the contract does not own the kitty.
+pub const KITTY_TRANSFER_FAILED: u32 = 1;
+
+#[derive(Default)]
+pub struct KittiesPoeExtension;
+
+impl ChainExtension<Runtime> for KittiesPoeExtension {
+	fn call<E: Ext<T = Runtime>>(
+		&mut self,
+		env: Environment<E, InitState>,
+	) -> Result<RetVal, DispatchError> {
+		let func_id = env.func_id();
+		let mut env = env.buf_in_buf_out();
+		let db_weight = <Runtime as frame_system::Config>::DbWeight::get();
+
+		match func_id {
+			KITTY_OWNER => {
+				let kitty_id: KittyId = env.read_as()?;
+				env.charge_weight(db_weight.reads(1))?;
+				env.write(&Kitties::owner(kitty_id).encode(), false, None)?;
+			},
+			KITTY => {
+				let kitty_id: KittyId = env.read_as()?;
+				env.charge_weight(db_weight.reads(1))?;
+				env.write(&Kitties::kitties(kitty_id).encode(), false, None)?;
+			},
+			KITTY_TRANSFER => {
+				let (to, kitty_id): (AccountId, KittyId) = env.read_as()?;
+				env.charge_weight(<Runtime as pallet_kitties::Config>::WeightInfo::transfer())?;
+				// Contracts can only move kitties they own themselves.
+				let from = env.ext().address().clone();
+				if Kitties::do_transfer(from, to, kitty_id).is_err() {
+					return Ok(RetVal::Converging(KITTY_TRANSFER_FAILED))
+				}
+			},
+			POE_CLAIM => {
+				let len = env.in_len();
+				let claim: BoundedVec<u8, <Runtime as pallet_poe::Config>::MaxClaimLength> =
+					env.read_as_unbounded(len)?;
+				env.charge_weight(db_weight.reads(1))?;
+				env.write(&pallet_poe::Proofs::<Runtime>::get(&claim).encode(), false, None)?;
+			},
+			_ => return Err(DispatchError::Other("Unknown chain extension function")),
+		}
+
+		Ok(RetVal::Converging(0))
+	}
+}
@@ -0,0 +1,276 @@
+//! Some configurable implementations as associated type for the runtime.
+
+use crate::*;
+use frame_support::traits::{
+	fungibles::{Balanced, Credit},
+	Contains, Currency, FindAuthor, Imbalance, OnUnbalanced,
+};
+use pallet_asset_tx_payment::HandleCredit;
+use sp_runtime::ConsensusEngineId;
+
+type NegativeImbalance = <Balances as Currency<AccountId>>::NegativeImbalance;
+
+/// Finds the account of the block author from the Aura pre-runtime digest.
+///
+/// Aura authorities are sr25519 keys, so the author's account is the account of that key.
+pub struct AuraAccountAdapter;
+
+impl FindAuthor<AccountId> for AuraAccountAdapter {
+	fn find_author<'a, I>(digests: I) -> Option<AccountId>
+	where
+		I: 'a + IntoIterator<Item = (ConsensusEngineId, &'a [u8])>,
+	{
+		let index = Aura::find_author(digests)?;
+		let authority = Aura::authorities().get(index as usize)?.clone();
+		AccountId::try_from(authority.as_ref()).ok()
+	}
+}
+
+/// Credits the block author with whatever it is given.
+pub struct Author;
+
+impl OnUnbalanced<NegativeImbalance> for Author {
+	fn on_nonzero_unbalanced(amount: NegativeImbalance) {
+		if let Some(author) = Authorship::author() {
+			Balances::resolve_creating(&author, amount);
+		}
+	}
+}
+
+/// Splits transaction fees 80% to the treasury and 20% to the block author. Tips go to the block
+/// author in full.
+pub struct DealWithFees;
+
+impl OnUnbalanced<NegativeImbalance> for DealWithFees {
+	fn on_unbalanceds<B>(mut fees_then_tips: impl Iterator<Item = NegativeImbalance>) {
+		if let Some(fees) = fees_then_tips.next() {
+			let mut split = fees.ration(80, 20);
+			if let Some(tips) = fees_then_tips.next() {
+				tips.merge_into(&mut split.1);
+			}
+			Treasury::on_unbalanced(split.0);
+			Author::on_unbalanced(split.1);
+		}
+	}
+}
+
+/// Splits transaction fees paid in an asset like [`DealWithFees`]: 80% to the treasury and 20%
+/// to the block author. `pallet_asset_tx_payment` hands over fee and tip together, so the tip is
+/// split too.
+///
+/// A share is burned if it can't be deposited, i.e. if it is below the asset's `min_balance` and
+/// the receiving account doesn't hold the asset yet.
+pub struct DealWithAssetFees;
+
+impl HandleCredit<AccountId, Assets> for DealWithAssetFees {
+	fn handle_credit(credit: Credit<AccountId, Assets>) {
+		let author_share = credit.peek() / 5;
+		let (author_credit, treasury_credit) = credit.split(author_share);
+		let _ = Assets::resolve(&Treasury::account_id(), treasury_credit);
+		if let Some(author) = Authorship::author() {
+			let _ = Assets::resolve(&author, author_credit);
+		}
+	}
+}
+
+/// The runtime's `BaseCallFilter`, which rejects the calls paused through `TxPause`.
+pub struct BaseFilter;
+
+impl Contains<RuntimeCall> for BaseFilter {
+	fn contains(call: &RuntimeCall) -> bool {
+		TxPause::contains(call)
+	}
+}
+
+/// Calls that accounts can schedule through `pallet_signed_scheduler`.
+pub struct SchedulableCalls;
+
+impl Contains<RuntimeCall> for SchedulableCalls {
+	fn contains(call: &RuntimeCall) -> bool {
+		matches!(call, RuntimeCall::Kitties(..) | RuntimeCall::PoeModule(..))
+	}
+}
+
+#[cfg(feature = "runtime-benchmarks")]
+impl pallet_signed_scheduler::BenchmarkHelper<RuntimeCall> for SchedulableCalls {
+	fn call() -> RuntimeCall {
+		RuntimeCall::Kitties(pallet_kitties::Call::create {})
+	}
+}
+
+/// Calls that new accounts can make without fees, within their `pallet_free_quota` quota.
+pub struct FreeTierCalls;
+
+impl Contains<RuntimeCall> for FreeTierCalls {
+	fn contains(call: &RuntimeCall) -> bool {
+		matches!(
+			call,
+			RuntimeCall::Kitties(pallet_kitties::Call::create {}) |
+				RuntimeCall::PoeModule(pallet_poe::Call::create_claim { .. })
+		)
+	}
+}
+
+/// Calls whose fees a `pallet_sponsor` sponsor can pay.
+pub struct SponsoredCalls;
+
+impl Contains<RuntimeCall> for SponsoredCalls {
+	fn contains(call: &RuntimeCall) -> bool {
+		matches!(
+			call,
+			RuntimeCall::PoeModule(
+				pallet_poe::Call::create_claim { .. } | pallet_poe::Call::revoke_claim { .. }
+			)
+		)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use frame_support::{
+		assert_noop, assert_ok,
+		traits::{ExistenceRequirement, Hooks, WithdrawReasons},
+	};
+	use sp_consensus_aura::{Slot, AURA_ENGINE_ID};
+	use sp_runtime::{traits::Dispatchable, DigestItem};
+
+	const AUTHOR: [u8; 32] = [7u8; 32];
+
+	fn new_test_ext() -> sp_io::TestExternalities {
+		let genesis = GenesisConfig {
+			aura: AuraConfig {
+				authorities: vec![AuraId::from(sp_core::sr25519::Public::from_raw(AUTHOR))],
+			},
+			..Default::default()
+		};
+		let mut ext: sp_io::TestExternalities = genesis.build_storage().unwrap().into();
+		ext.execute_with(|| {
+			System::deposit_log(DigestItem::PreRuntime(AURA_ENGINE_ID, Slot::from(0).encode()))
+		});
+		ext
+	}
+
+	#[test]
+	fn fees_are_split_between_treasury_and_author() {
+		new_test_ext().execute_with(|| {
+			let pot = Treasury::pot();
+			let fees = Balances::issue(10 * UNIT);
+			let tips = Balances::issue(UNIT);
+
+			DealWithFees::on_unbalanceds(vec![fees, tips].into_iter());
+
+			assert_eq!(Treasury::pot(), pot + 8 * UNIT);
+			assert_eq!(Balances::free_balance(AccountId::new(AUTHOR)), 3 * UNIT);
+		});
+	}
+
+	#[test]
+	fn dust_goes_to_treasury() {
+		new_test_ext().execute_with(|| {
+			let who = AccountId::new([1u8; 32]);
+			let pot = Treasury::pot();
+			Balances::make_free_balance_be(&who, EXISTENTIAL_DEPOSIT + 100);
+
+			assert!(Balances::withdraw(
+				&who,
+				200,
+				WithdrawReasons::TRANSFER,
+				ExistenceRequirement::AllowDeath,
+			)
+			.is_ok());
+
+			assert_eq!(Balances::free_balance(&who), 0);
+			assert_eq!(Treasury::pot(), pot + EXISTENTIAL_DEPOSIT - 100);
+		});
+	}
+
+	fn schedule(call: RuntimeCall) -> RuntimeCall {
+		RuntimeCall::SignedScheduler(pallet_signed_scheduler::Call::schedule {
+			when: 3,
+			call: Box::new(call),
+		})
+	}
+
+	#[test]
+	fn signed_accounts_schedule_kitties_calls_on_their_own_behalf() {
+		new_test_ext().execute_with(|| {
+			let who = AccountId::new([1u8; 32]);
+			Balances::make_free_balance_be(&who, 100 * UNIT);
+			let create = RuntimeCall::Kitties(pallet_kitties::Call::create {});
+			assert_ok!(schedule(create).dispatch(RuntimeOrigin::signed(who.clone())));
+			assert_eq!(Balances::reserved_balance(&who), SignedScheduleDeposit::get());
+
+			System::set_block_number(3);
+			Scheduler::on_initialize(3);
+
+			assert_eq!(Kitties::owner(0), Some(who.clone()));
+			assert_eq!(Balances::reserved_balance(&who), 0);
+		});
+	}
+
+	#[test]
+	fn signed_accounts_can_not_schedule_other_calls() {
+		new_test_ext().execute_with(|| {
+			let who = AccountId::new([1u8; 32]);
+			Balances::make_free_balance_be(&who, 100 * UNIT);
+			let remark = RuntimeCall::System(SystemCall::remark { remark: vec![] });
+
+			assert_noop!(
+				schedule(remark).dispatch(RuntimeOrigin::signed(who)),
+				pallet_signed_scheduler::Error::<Runtime>::CallNotAllowed
+			);
+		});
+	}
+
+	#[test]
+	fn only_governance_uses_the_scheduler_directly() {
+		new_test_ext().execute_with(|| {
+			let who = AccountId::new([1u8; 32]);
+			let create = RuntimeCall::Kitties(pallet_kitties::Call::create {});
+			let schedule =
+				|origin| Scheduler::schedule(origin, 3, None, 0, Box::new(create.clone()));
+
+			assert_noop!(
+				schedule(RuntimeOrigin::signed(who)),
+				sp_runtime::DispatchError::BadOrigin
+			);
+			assert_ok!(schedule(RuntimeOrigin::root()));
+		});
+	}
+
+	#[test]
+	fn paused_calls_are_filtered() {
+		new_test_ext().execute_with(|| {
+			let who = AccountId::new([1u8; 32]);
+			let breed =
+				RuntimeCall::Kitties(pallet_kitties::Call::breed { kitty_id_1: 0, kitty_id_2: 1 });
+			let create = RuntimeCall::Kitties(pallet_kitties::Call::create {});
+			let name = (
+				b"Kitties".to_vec().try_into().unwrap(),
+				Some(b"breed".to_vec().try_into().unwrap()),
+			);
+			assert_ok!(TxPause::pause(RuntimeOrigin::root(), name));
+
+			assert!(!BaseFilter::contains(&breed));
+			assert!(BaseFilter::contains(&create));
+			assert_noop!(
+				breed.dispatch(RuntimeOrigin::signed(who)),
+				frame_system::Error::<Runtime>::CallFiltered
+			);
+		});
+	}
+
+	#[test]
+	fn system_calls_can_not_be_paused() {
+		new_test_ext().execute_with(|| {
+			assert_noop!(
+				TxPause::pause(
+					RuntimeOrigin::root(),
+					(b"System".to_vec().try_into().unwrap(), None)
+				),
+				pallet_tx_pause::Error::<Runtime>::Unpausable
+			);
+		});
+	}
+}
@@ -6,6 +6,7 @@
 #[cfg(feature = "std")]
 include!(concat!(env!("OUT_DIR"), "/wasm_binary.rs"));
 
+use codec::{Decode, Encode, MaxEncodedLen};
 use pallet_grandpa::AuthorityId as GrandpaId;
 use pallet_insecure_randomness_collective_flip;
 use sp_api::impl_runtime_apis;
@@ -14,10 +15,11 @@ use sp_core::{crypto::KeyTypeId, OpaqueMetadata};
 use sp_runtime::{
 	create_runtime_str, generic, impl_opaque_keys,
 	traits::{
-		AccountIdLookup, BlakeTwo256, Block as BlockT, IdentifyAccount, NumberFor, One, Verify,
+		AccountIdLookup, BlakeTwo256, Block as BlockT, Bounded, ConvertInto,
+		Extrinsic as ExtrinsicT, IdentifyAccount, NumberFor, SaturatedConversion, Verify,
 	},
 	transaction_validity::{TransactionSource, TransactionValidity},
-	ApplyExtrinsicResult, MultiSignature,
+	ApplyExtrinsicResult, MultiSignature, RuntimeDebug,
 };
 use sp_std::prelude::*;
 #[cfg(feature = "std")]
@@ -27,27 +29,38 @@ use sp_version::RuntimeVersion;
 pub use frame_support::{
 	construct_runtime, parameter_types,
 	traits::{
-		ConstU128, ConstU32, ConstU64, ConstU8, KeyOwnerProofSystem, Randomness, StorageInfo,
+		AsEnsureOriginWithArg, ConstBool, ConstU128, ConstU32, ConstU64, ConstU8, Contains,
+		EqualPrivilegeOnly, InstanceFilter, KeyOwnerProofSystem, Nothing, Randomness, StorageInfo,
+		WithdrawReasons,
 	},
 	weights::{
 		constants::{
 			BlockExecutionWeight, ExtrinsicBaseWeight, RocksDbWeight, WEIGHT_REF_TIME_PER_SECOND,
 		},
-		IdentityFee, Weight,
+		IdentityFee, Weight, WeightToFeeCoefficient, WeightToFeeCoefficients,
+		WeightToFeePolynomial,
 	},
-	StorageValue,
+	PalletId, StorageValue,
 };
 pub use frame_system::Call as SystemCall;
 pub use pallet_balances::Call as BalancesCall;
+pub use pallet_kitties::Call as KittiesCall;
+pub use pallet_poe::Call as PoeCall;
 pub use pallet_timestamp::Call as TimestampCall;
-use pallet_transaction_payment::{ConstFeeMultiplier, CurrencyAdapter, Multiplier};
+use pallet_transaction_payment::{CurrencyAdapter, Multiplier, TargetedFeeAdjustment};
+pub use pallet_utility::Call as UtilityCall;
+use smallvec::smallvec;
 #[cfg(any(feature = "std", test))]
 pub use sp_runtime::BuildStorage;
-pub use sp_runtime::{Perbill, Permill};
+pub use sp_runtime::{FixedPointNumber, Perbill, Permill, Perquintill};
 
 /// Import the template pallet.
 pub use pallet_template;
 
+pub mod chain_extension;
+pub mod impls;
+mod migrations;
+
 /// An index to a block.
 pub type BlockNumber = u32;
 
@@ -103,10 +116,12 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	//   `spec_version`, and `authoring_version` are the same between Wasm and native.
 	// This value is set to 100 to notify Polkadot-JS App (https://polkadot.js.org/apps) to use
 	//   the compatible custom types.
-	spec_version: 100,
+	spec_version: 101,
 	impl_version: 1,
 	apis: RUNTIME_API_VERSIONS,
-	transaction_version: 1,
+	// Bumped whenever the encoding of calls or of `SignedExtra` changes, so that signers built for
+	//   the previous version stop producing transactions.
+	transaction_version: 8,
 	state_version: 1,
 };
 
@@ -153,7 +168,7 @@ parameter_types! {
 
 impl frame_system::Config for Runtime {
 	/// The basic call filter to use in dispatchable.
-	type BaseCallFilter = frame_support::traits::Everything;
+	type BaseCallFilter = impls::BaseFilter;
 	/// Block & extrinsics weights: base values and limits.
 	type BlockWeights = BlockWeights;
 	/// The maximum length of a block (in bytes).
@@ -231,6 +246,16 @@ impl pallet_timestamp::Config for Runtime {
 /// Existential deposit.
 pub const EXISTENTIAL_DEPOSIT: u128 = 500;
 
+// Units of the native token, used to express deposits.
+pub const UNIT: Balance = 1_000_000_000_000;
+pub const MILLIUNIT: Balance = 1_000_000_000;
+pub const MICROUNIT: Balance = 1_000_000;
+
+/// Deposit required to store `items` entries taking up `bytes` bytes in total.
+pub const fn deposit(items: u32, bytes: u32) -> Balance {
+	items as Balance * 20 * UNIT + (bytes as Balance) * 100 * MICROUNIT
+}
+
 impl pallet_balances::Config for Runtime {
 	type MaxLocks = ConstU32<50>;
 	type MaxReserves = ();
@@ -239,7 +264,7 @@ impl pallet_balances::Config for Runtime {
 	type Balance = Balance;
 	/// The ubiquitous event type.
 	type RuntimeEvent = RuntimeEvent;
-	type DustRemoval = ();
+	type DustRemoval = Treasury;
 	type ExistentialDeposit = ConstU128<EXISTENTIAL_DEPOSIT>;
 	type AccountStore = System;
 	type WeightInfo = pallet_balances::weights::SubstrateWeight<Runtime>;
@@ -249,28 +274,30 @@ impl pallet_balances::Config for Runtime {
 	type MaxHolds = ();
 }
 
-impl pallet_nicks::Config for Runtime {
-	type RuntimeEvent = RuntimeEvent;
-	type Currency = Balances;
-
-	// Set ReservationFee to a value.
-	type ReservationFee = ConstU128<100>;
+/// Origin allowed to perform governance operations. Until a council is introduced this is the
+/// sudo key.
+pub type GovernanceOrigin = frame_system::EnsureRoot<AccountId>;
 
-	// No action is taken when deposits are forfeited.
-	type Slashed = ();
-
-	// Configure the FRAME System Root origin as the Nick pallet admin.
-	// https://paritytech.github.io/substrate/master/frame_system/enum.RawOrigin.html#variant.Root
-	type ForceOrigin = frame_system::EnsureRoot<AccountId>;
-
-	// Set MinLength of nick name to a desired value.
-	type MinLength = ConstU32<8>;
-
-	// Set MaxLength of nick name to a desired value.
-	type MaxLength = ConstU32<32>;
+parameter_types! {
+	pub const BasicDeposit: Balance = deposit(1, 258);
+	pub const FieldDeposit: Balance = deposit(0, 66);
+	pub const SubAccountDeposit: Balance = deposit(1, 53);
+}
 
-	// The ubiquitous event type.
-	//type RuntimeEvent = RuntimeEvent;
+impl pallet_identity::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type Currency = Balances;
+	type BasicDeposit = BasicDeposit;
+	type FieldDeposit = FieldDeposit;
+	type SubAccountDeposit = SubAccountDeposit;
+	type MaxSubAccounts = ConstU32<100>;
+	type MaxAdditionalFields = ConstU32<100>;
+	type MaxRegistrars = ConstU32<20>;
+	// Forfeited deposits go to the treasury.
+	type Slashed = Treasury;
+	type ForceOrigin = GovernanceOrigin;
+	type RegistrarOrigin = GovernanceOrigin;
+	type WeightInfo = pallet_identity::weights::SubstrateWeight<Runtime>;
 }
 
 impl pallet_poe::Config for Runtime {
@@ -278,26 +305,474 @@ impl pallet_poe::Config for Runtime {
 	// Set MaxLength of nick name to a desired value.
 	type MaxClaimLength = ConstU32<32>;
 	type WeightInfo = pallet_poe::weights::SubstrateWeight<Runtime>;
+	type MaxMetadataLength = ConstU32<256>;
+	type AuthorityId = pallet_poe::crypto::PoeAuthId;
+	type TsaOrigin = GovernanceOrigin;
+	type MaxTsaKeys = ConstU32<8>;
+	type OffchainSignature = Signature;
+	type OffchainPublic = <Signature as Verify>::Signer;
+	type MaxCosigners = ConstU32<16>;
+	type UnixTime = Timestamp;
+	// An hour, in milliseconds.
+	type AttestationWindow = ConstU64<{ 60 * 60 * 1000 }>;
+	// Only the DID pallet may anchor credentials.
+	type ReservedClaims = pallet_did::AnchoredClaims;
+	#[cfg(feature = "runtime-benchmarks")]
+	type BenchmarkHelper = ();
+}
+
+impl<LocalCall> frame_system::offchain::CreateSignedTransaction<LocalCall> for Runtime
+where
+	RuntimeCall: From<LocalCall>,
+{
+	fn create_transaction<C: frame_system::offchain::AppCrypto<Self::Public, Self::Signature>>(
+		call: RuntimeCall,
+		public: <Signature as Verify>::Signer,
+		account: AccountId,
+		nonce: Index,
+	) -> Option<(RuntimeCall, <UncheckedExtrinsic as ExtrinsicT>::SignaturePayload)> {
+		let period =
+			BlockHashCount::get().checked_next_power_of_two().map(|c| c / 2).unwrap_or(2) as u64;
+		let current_block = System::block_number().saturated_into::<u64>().saturating_sub(1);
+		let extra: SignedExtra = (
+			frame_system::CheckNonZeroSender::<Runtime>::new(),
+			frame_system::CheckSpecVersion::<Runtime>::new(),
+			frame_system::CheckTxVersion::<Runtime>::new(),
+			frame_system::CheckGenesis::<Runtime>::new(),
+			frame_system::CheckEra::<Runtime>::from(generic::Era::mortal(period, current_block)),
+			frame_system::CheckWeight::<Runtime>::new(),
+			pallet_tx_pause::CheckNotPaused::<Runtime>::new(),
+			ChargeTransaction::paid(
+				nonce,
+				pallet_sponsor::ChargeSponsor::Signer(
+					pallet_asset_tx_payment::ChargeAssetTxPayment::from(0, None),
+				),
+			),
+		);
+		let raw_payload = SignedPayload::new(call, extra).ok()?;
+		let signature = raw_payload.using_encoded(|payload| C::sign(payload, public))?;
+		let (call, extra, _) = raw_payload.deconstruct();
+		Some((call, (sp_runtime::MultiAddress::Id(account), signature, extra)))
+	}
+}
+
+impl frame_system::offchain::SigningTypes for Runtime {
+	type Public = <Signature as Verify>::Signer;
+	type Signature = Signature;
+}
+
+impl<C> frame_system::offchain::SendTransactionTypes<C> for Runtime
+where
+	RuntimeCall: From<C>,
+{
+	type OverarchingCall = RuntimeCall;
+	type Extrinsic = UncheckedExtrinsic;
 }
 
 impl pallet_insecure_randomness_collective_flip::Config for Runtime {}
 
 impl pallet_kitties::Config for Runtime {
+	type Assets = Assets;
 	type RuntimeEvent = RuntimeEvent;
 	type Randomness = RandomnessCollectiveFlip;
+	type WeightInfo = pallet_kitties::weights::SubstrateWeight<Runtime>;
+}
+
+impl pallet_utility::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type RuntimeCall = RuntimeCall;
+	type PalletsOrigin = OriginCaller;
+	type WeightInfo = pallet_utility::weights::SubstrateWeight<Runtime>;
+}
+
+parameter_types! {
+	// One storage item; key size is 32; value is size 4+4+16+32 bytes = 56 bytes.
+	pub const DepositBase: Balance = deposit(1, 88);
+	// Additional storage item size of 32 bytes.
+	pub const DepositFactor: Balance = deposit(0, 32);
+}
+
+impl pallet_multisig::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type RuntimeCall = RuntimeCall;
+	type Currency = Balances;
+	type DepositBase = DepositBase;
+	type DepositFactor = DepositFactor;
+	type MaxSignatories = ConstU32<100>;
+	type WeightInfo = pallet_multisig::weights::SubstrateWeight<Runtime>;
 }
 
 parameter_types! {
-	pub FeeMultiplier: Multiplier = Multiplier::one();
+	// One storage item; key size 32, value size 8.
+	pub const ProxyDepositBase: Balance = deposit(1, 8);
+	// Additional storage item size of 33 bytes.
+	pub const ProxyDepositFactor: Balance = deposit(0, 33);
+	pub const AnnouncementDepositBase: Balance = deposit(1, 8);
+	pub const AnnouncementDepositFactor: Balance = deposit(0, 66);
+}
+
+/// The type used to represent the kinds of proxying allowed.
+#[derive(
+	Copy,
+	Clone,
+	Eq,
+	PartialEq,
+	Ord,
+	PartialOrd,
+	Encode,
+	Decode,
+	RuntimeDebug,
+	MaxEncodedLen,
+	scale_info::TypeInfo,
+)]
+pub enum ProxyType {
+	/// Can dispatch any call.
+	Any,
+	/// Can dispatch the calls that do not move balances, assets, kitties or claims.
+	NonTransfer,
+	/// Can only dispatch calls to `pallet_kitties`.
+	KittiesOnly,
+	/// Can only dispatch calls to `pallet_poe`.
+	PoeOnly,
+}
+
+impl Default for ProxyType {
+	fn default() -> Self {
+		Self::Any
+	}
+}
+
+impl InstanceFilter<RuntimeCall> for ProxyType {
+	fn filter(&self, c: &RuntimeCall) -> bool {
+		match self {
+			ProxyType::Any => true,
+			// An allow-list, so that pallets added later are kept out until they are checked. Left
+			// out are the pallets that move value, or that dispatch calls later without the
+			// proxy's filter, like `SignedScheduler`.
+			ProxyType::NonTransfer => match c {
+				RuntimeCall::System(..) |
+				RuntimeCall::TemplateModule(..) |
+				RuntimeCall::Identity(..) |
+				RuntimeCall::Did(..) |
+				RuntimeCall::Utility(..) |
+				RuntimeCall::Multisig(..) |
+				RuntimeCall::Proxy(..) => true,
+				RuntimeCall::PoeModule(call) =>
+					!matches!(call, pallet_poe::Call::transfer_claim { .. }),
+				RuntimeCall::Kitties(call) => matches!(
+					call,
+					pallet_kitties::Call::create { .. } | pallet_kitties::Call::breed { .. }
+				),
+				RuntimeCall::Vesting(call) => matches!(
+					call,
+					pallet_vesting::Call::vest { .. } | pallet_vesting::Call::vest_other { .. }
+				),
+				_ => false,
+			},
+			// Batches are allowed, the inner calls are filtered again by the proxy.
+			ProxyType::KittiesOnly =>
+				matches!(c, RuntimeCall::Kitties(..) | RuntimeCall::Utility(..)),
+			ProxyType::PoeOnly =>
+				matches!(c, RuntimeCall::PoeModule(..) | RuntimeCall::Utility(..)),
+		}
+	}
+
+	fn is_superset(&self, o: &Self) -> bool {
+		match (self, o) {
+			(x, y) if x == y => true,
+			(ProxyType::Any, _) => true,
+			_ => false,
+		}
+	}
 }
 
+impl pallet_proxy::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type RuntimeCall = RuntimeCall;
+	type Currency = Balances;
+	type ProxyType = ProxyType;
+	type ProxyDepositBase = ProxyDepositBase;
+	type ProxyDepositFactor = ProxyDepositFactor;
+	type MaxProxies = ConstU32<32>;
+	type WeightInfo = pallet_proxy::weights::SubstrateWeight<Runtime>;
+	type MaxPending = ConstU32<32>;
+	type CallHasher = BlakeTwo256;
+	type AnnouncementDepositBase = AnnouncementDepositBase;
+	type AnnouncementDepositFactor = AnnouncementDepositFactor;
+}
+
+/// Converts a weight into a fee, charging one `MILLIUNIT` for the base weight of an extrinsic and
+/// growing linearly from there.
+pub struct WeightToFee;
+
+impl WeightToFeePolynomial for WeightToFee {
+	type Balance = Balance;
+
+	fn polynomial() -> WeightToFeeCoefficients<Self::Balance> {
+		let p = MILLIUNIT;
+		let q = Balance::from(ExtrinsicBaseWeight::get().ref_time());
+		smallvec![WeightToFeeCoefficient {
+			degree: 1,
+			negative: false,
+			coeff_frac: Perbill::from_rational(p % q, q),
+			coeff_integer: p / q,
+		}]
+	}
+}
+
+parameter_types! {
+	/// Blocks filled above this ratio of their normal dispatch weight make the next block more
+	/// expensive, emptier blocks make it cheaper.
+	pub const TargetBlockFullness: Perquintill = Perquintill::from_percent(25);
+	/// How fast the multiplier reacts; with this value a day of full blocks roughly doubles fees.
+	pub AdjustmentVariable: Multiplier = Multiplier::saturating_from_rational(64, 1_000_000);
+	/// Fees never drop below this fraction of their weight based value.
+	pub MinimumMultiplier: Multiplier = Multiplier::saturating_from_rational(1, 10u128);
+	pub MaximumMultiplier: Multiplier = Bounded::max_value();
+}
+
+/// Updates the fee multiplier after every block according to how full it was.
+pub type SlowAdjustingFeeUpdate<R> = TargetedFeeAdjustment<
+	R,
+	TargetBlockFullness,
+	AdjustmentVariable,
+	MinimumMultiplier,
+	MaximumMultiplier,
+>;
+
 impl pallet_transaction_payment::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
-	type OnChargeTransaction = CurrencyAdapter<Balances, ()>;
+	type OnChargeTransaction = CurrencyAdapter<Balances, impls::DealWithFees>;
 	type OperationalFeeMultiplier = ConstU8<5>;
-	type WeightToFee = IdentityFee<Balance>;
+	type WeightToFee = WeightToFee;
 	type LengthToFee = IdentityFee<Balance>;
-	type FeeMultiplierUpdate = ConstFeeMultiplier<FeeMultiplier>;
+	type FeeMultiplierUpdate = SlowAdjustingFeeUpdate<Self>;
+}
+
+/// Identifier of an asset in `pallet_assets`.
+pub type AssetId = u32;
+
+parameter_types! {
+	pub const AssetDeposit: Balance = 100 * UNIT;
+	pub const ApprovalDeposit: Balance = UNIT;
+	pub const StringLimit: u32 = 50;
+	pub const MetadataDepositBase: Balance = 10 * UNIT;
+	pub const MetadataDepositPerByte: Balance = UNIT;
+}
+
+impl pallet_assets::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type Balance = Balance;
+	type AssetId = AssetId;
+	type AssetIdParameter = codec::Compact<AssetId>;
+	type Currency = Balances;
+	// Anyone can create an in-game currency, only governance can make it sufficient.
+	type CreateOrigin = AsEnsureOriginWithArg<frame_system::EnsureSigned<AccountId>>;
+	type ForceOrigin = GovernanceOrigin;
+	type AssetDeposit = AssetDeposit;
+	type AssetAccountDeposit = ConstU128<UNIT>;
+	type MetadataDepositBase = MetadataDepositBase;
+	type MetadataDepositPerByte = MetadataDepositPerByte;
+	type ApprovalDeposit = ApprovalDeposit;
+	type StringLimit = StringLimit;
+	type Freezer = ();
+	type Extra = ();
+	type CallbackHandle = ();
+	type WeightInfo = pallet_assets::weights::SubstrateWeight<Runtime>;
+	type RemoveItemsLimit = ConstU32<1000>;
+	#[cfg(feature = "runtime-benchmarks")]
+	type BenchmarkHelper = ();
+}
+
+impl pallet_asset_tx_payment::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type Fungibles = Assets;
+	// Fees paid in an asset are converted at the ratio between the asset's `min_balance` and the
+	// native existential deposit. Only sufficient assets can be used, and both their sufficiency
+	// and `min_balance` are set by `GovernanceOrigin` through `Assets::force_asset_status`.
+	type OnChargeAssetTransaction = pallet_asset_tx_payment::FungiblesAdapter<
+		pallet_assets::BalanceToAssetBalance<Balances, Runtime, ConvertInto>,
+		impls::DealWithAssetFees,
+	>;
+}
+
+parameter_types! {
+	pub const DepositPerItem: Balance = deposit(1, 0);
+	pub const DepositPerByte: Balance = deposit(0, 1);
+	pub const DefaultDepositLimit: Balance = deposit(1024, 1024 * 1024);
+	pub Schedule: pallet_contracts::Schedule<Runtime> = Default::default();
+}
+
+impl pallet_contracts::Config for Runtime {
+	type Time = Timestamp;
+	type Randomness = RandomnessCollectiveFlip;
+	type Currency = Balances;
+	type RuntimeEvent = RuntimeEvent;
+	type RuntimeCall = RuntimeCall;
+	/// Contracts can not dispatch runtime calls, they go through the chain extension instead.
+	type CallFilter = Nothing;
+	type DepositPerItem = DepositPerItem;
+	type DepositPerByte = DepositPerByte;
+	type DefaultDepositLimit = DefaultDepositLimit;
+	type CallStack = [pallet_contracts::Frame<Self>; 5];
+	type WeightPrice = pallet_transaction_payment::Pallet<Self>;
+	type WeightInfo = pallet_contracts::weights::SubstrateWeight<Self>;
+	type ChainExtension = chain_extension::KittiesPoeExtension;
+	type Schedule = Schedule;
+	type AddressGenerator = pallet_contracts::DefaultAddressGenerator;
+	type MaxCodeLen = ConstU32<{ 123 * 1024 }>;
+	type MaxStorageKeyLen = ConstU32<128>;
+	type UnsafeUnstableInterface = ConstBool<false>;
+	type MaxDebugBufferLen = ConstU32<{ 2 * 1024 * 1024 }>;
+}
+
+/// Whether contract calls made through the runtime API return the contract's debug buffer.
+const CONTRACTS_DEBUG_OUTPUT: bool = true;
+
+impl pallet_authorship::Config for Runtime {
+	type FindAuthor = impls::AuraAccountAdapter;
+	type EventHandler = ();
+}
+
+parameter_types! {
+	pub const ProposalBond: Permill = Permill::from_percent(5);
+	pub const ProposalBondMinimum: Balance = UNIT;
+	pub const SpendPeriod: BlockNumber = DAYS;
+	pub const Burn: Permill = Permill::zero();
+	pub const TreasuryPalletId: PalletId = PalletId(*b"py/trsry");
+	pub const MaxBalance: Balance = Balance::max_value();
+}
+
+impl pallet_treasury::Config for Runtime {
+	type PalletId = TreasuryPalletId;
+	type Currency = Balances;
+	type ApproveOrigin = GovernanceOrigin;
+	type RejectOrigin = GovernanceOrigin;
+	type RuntimeEvent = RuntimeEvent;
+	// Bonds of rejected proposals stay with the treasury.
+	type OnSlash = Treasury;
+	type ProposalBond = ProposalBond;
+	type ProposalBondMinimum = ProposalBondMinimum;
+	type ProposalBondMaximum = ();
+	type SpendPeriod = SpendPeriod;
+	type Burn = Burn;
+	type BurnDestination = ();
+	type SpendFunds = ();
+	type WeightInfo = pallet_treasury::weights::SubstrateWeight<Runtime>;
+	type MaxApprovals = ConstU32<100>;
+	type SpendOrigin = frame_system::EnsureWithSuccess<GovernanceOrigin, AccountId, MaxBalance>;
+}
+
+parameter_types! {
+	pub const MinVestedTransfer: Balance = 100 * UNIT;
+	pub UnvestedFundsAllowedWithdrawReasons: WithdrawReasons =
+		WithdrawReasons::except(WithdrawReasons::TRANSFER | WithdrawReasons::RESERVE);
+}
+
+impl pallet_vesting::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type Currency = Balances;
+	type BlockNumberToBalance = ConvertInto;
+	type MinVestedTransfer = MinVestedTransfer;
+	type WeightInfo = pallet_vesting::weights::SubstrateWeight<Runtime>;
+	type UnvestedFundsAllowedWithdrawReasons = UnvestedFundsAllowedWithdrawReasons;
+	const MAX_VESTING_SCHEDULES: u32 = 28;
+}
+
+parameter_types! {
+	pub const PreimageBaseDeposit: Balance = deposit(2, 64);
+	pub const PreimageByteDeposit: Balance = deposit(0, 1);
+}
+
+impl pallet_preimage::Config for Runtime {
+	type WeightInfo = pallet_preimage::weights::SubstrateWeight<Runtime>;
+	type RuntimeEvent = RuntimeEvent;
+	type Currency = Balances;
+	type ManagerOrigin = GovernanceOrigin;
+	type BaseDeposit = PreimageBaseDeposit;
+	type ByteDeposit = PreimageByteDeposit;
+}
+
+parameter_types! {
+	pub MaximumSchedulerWeight: Weight = Perbill::from_percent(80) * BlockWeights::get().max_block;
+	pub const SignedScheduleDeposit: Balance = deposit(1, 128);
+}
+
+impl pallet_scheduler::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type RuntimeOrigin = RuntimeOrigin;
+	type PalletsOrigin = OriginCaller;
+	type RuntimeCall = RuntimeCall;
+	type MaximumWeight = MaximumSchedulerWeight;
+	// Signed accounts schedule calls through `SignedScheduler`.
+	type ScheduleOrigin = GovernanceOrigin;
+	type MaxScheduledPerBlock = ConstU32<50>;
+	type WeightInfo = pallet_scheduler::weights::SubstrateWeight<Runtime>;
+	type OriginPrivilegeCmp = EqualPrivilegeOnly;
+	type Preimages = Preimage;
+}
+
+impl pallet_signed_scheduler::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type RuntimeCall = RuntimeCall;
+	type PalletsOrigin = OriginCaller;
+	type Scheduler = Scheduler;
+	type Currency = Balances;
+	type SchedulableCalls = impls::SchedulableCalls;
+	type Deposit = SignedScheduleDeposit;
+	type MaxTasksPerAccount = ConstU32<10>;
+	// Leaves 10 of the scheduler's 50 calls per block to governance.
+	type MaxTasksPerBlock = ConstU32<40>;
+	type WeightInfo = pallet_signed_scheduler::weights::SubstrateWeight<Runtime>;
+	#[cfg(feature = "runtime-benchmarks")]
+	type BenchmarkHelper = impls::SchedulableCalls;
+}
+
+/// Pallets that must keep working to run or recover the chain, so they can never be paused.
+pub struct UnpausablePallets;
+
+impl Contains<pallet_tx_pause::FullNameOf<Runtime>> for UnpausablePallets {
+	fn contains(full_name: &pallet_tx_pause::FullNameOf<Runtime>) -> bool {
+		matches!(full_name.0.as_slice(), b"System" | b"Timestamp" | b"Sudo" | b"TxPause")
+	}
+}
+
+impl pallet_tx_pause::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type RuntimeCall = RuntimeCall;
+	type PauseOrigin = GovernanceOrigin;
+	type UnpauseOrigin = GovernanceOrigin;
+	type WhitelistedCalls = UnpausablePallets;
+	type MaxNameLen = ConstU32<256>;
+	type WeightInfo = pallet_tx_pause::weights::SubstrateWeight<Runtime>;
+}
+
+parameter_types! {
+	pub const FreeCallsPerEra: u32 = 5;
+	pub const FreeQuotaEraLength: BlockNumber = DAYS;
+}
+
+impl pallet_free_quota::Config for Runtime {
+	type FreeCalls = impls::FreeTierCalls;
+	type FreeCallsPerEra = FreeCallsPerEra;
+	type EraLength = FreeQuotaEraLength;
+}
+
+impl pallet_sponsor::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type Balance = Balance;
+	type SponsoredCalls = impls::SponsoredCalls;
+	type WeightInfo = pallet_sponsor::weights::SubstrateWeight<Runtime>;
+}
+
+impl pallet_did::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type MaxControllers = ConstU32<8>;
+	type MaxKeys = ConstU32<8>;
+	type MaxServices = ConstU32<8>;
+	type MaxIdLength = ConstU32<32>;
+	type MaxUrlLength = ConstU32<256>;
+	type WeightInfo = pallet_did::weights::SubstrateWeight<Runtime>;
 }
 
 impl pallet_sudo::Config for Runtime {
@@ -328,10 +803,26 @@ construct_runtime!(
 		Sudo: pallet_sudo,
 		// Include the custom logic from the pallet-template in the runtime.
 		TemplateModule: pallet_template,
-		Nicks: pallet_nicks,
+		Identity: pallet_identity,
 		PoeModule: pallet_poe,
 		Kitties: pallet_kitties,
 		RandomnessCollectiveFlip: pallet_insecure_randomness_collective_flip,
+		Utility: pallet_utility,
+		Multisig: pallet_multisig,
+		Proxy: pallet_proxy,
+		Assets: pallet_assets,
+		AssetTxPayment: pallet_asset_tx_payment,
+		Contracts: pallet_contracts,
+		Authorship: pallet_authorship,
+		Treasury: pallet_treasury,
+		Vesting: pallet_vesting,
+		Preimage: pallet_preimage,
+		Scheduler: pallet_scheduler,
+		TxPause: pallet_tx_pause,
+		FreeQuota: pallet_free_quota,
+		Sponsorship: pallet_sponsor,
+		Did: pallet_did,
+		SignedScheduler: pallet_signed_scheduler,
 	}
 );
 
@@ -348,16 +839,35 @@ pub type SignedExtra = (
 	frame_system::CheckTxVersion<Runtime>,
 	frame_system::CheckGenesis<Runtime>,
 	frame_system::CheckEra<Runtime>,
-	frame_system::CheckNonce<Runtime>,
 	frame_system::CheckWeight<Runtime>,
-	pallet_transaction_payment::ChargeTransactionPayment<Runtime>,
+	pallet_tx_pause::CheckNotPaused<Runtime>,
+	ChargeTransaction,
 );
 
+/// Checks the nonce of a transaction and charges its fee to the signer's free quota, to a sponsor
+/// of the signer or to the signer itself, as the signer chooses.
+///
+/// It takes the place of `frame_system::CheckNonce`, so that free transactions can be limited by
+/// how far ahead of the signer's nonce they are.
+pub type ChargeTransaction = pallet_free_quota::CheckFreeQuota<
+	Runtime,
+	pallet_sponsor::ChargeSponsor<Runtime, pallet_asset_tx_payment::ChargeAssetTxPayment<Runtime>>,
+>;
+
 /// Unchecked extrinsic type as expected by this runtime.
 pub type UncheckedExtrinsic =
 	generic::UncheckedExtrinsic<Address, RuntimeCall, Signature, SignedExtra>;
 /// The payload being signed in transactions.
 pub type SignedPayload = generic::SignedPayload<RuntimeCall, SignedExtra>;
+/// Migrations to apply on runtime upgrade.
+pub type Migrations = (
+	migrations::NicksToIdentity,
+	// The custom pallets were unversioned before; their layout did not change.
+	migrations::VersionedMigration<0, 1, (), TemplateModule>,
+	migrations::VersionedMigration<0, 1, (), PoeModule>,
+	migrations::VersionedMigration<0, 1, (), Kitties>,
+);
+
 /// Executive: handles dispatch to the various modules.
 pub type Executive = frame_executive::Executive<
 	Runtime,
@@ -365,6 +875,7 @@ pub type Executive = frame_executive::Executive<
 	frame_system::ChainContext<Runtime>,
 	Runtime,
 	AllPalletsWithSystem,
+	Migrations,
 >;
 
 #[cfg(feature = "runtime-benchmarks")]
@@ -380,6 +891,21 @@ mod benches {
 		[pallet_timestamp, Timestamp]
 		[pallet_template, TemplateModule]
 		[pallet_poe, PoeModule]
+		[pallet_kitties, Kitties]
+		[pallet_tx_pause, TxPause]
+		[pallet_sponsor, Sponsorship]
+		[pallet_did, Did]
+		[pallet_signed_scheduler, SignedScheduler]
+		[pallet_utility, Utility]
+		[pallet_multisig, Multisig]
+		[pallet_proxy, Proxy]
+		[pallet_identity, Identity]
+		[pallet_assets, Assets]
+		[pallet_contracts, Contracts]
+		[pallet_treasury, Treasury]
+		[pallet_vesting, Vesting]
+		[pallet_preimage, Preimage]
+		[pallet_scheduler, Scheduler]
 	);
 }
 
@@ -551,6 +1077,88 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl pallet_sponsor::SponsorApi<Block, AccountId, Balance> for Runtime {
+		fn allowance(sponsor: AccountId, who: AccountId) -> Option<Balance> {
+			Sponsorship::allowance(&sponsor, &who)
+		}
+	}
+
+	impl pallet_poe::PoeApi<Block, AccountId> for Runtime {
+		fn signature_status(claim: Vec<u8>) -> Option<pallet_poe::SignatureStatus<AccountId>> {
+			PoeModule::signature_status(&claim)
+		}
+	}
+
+	impl pallet_did::DidApi<Block, AccountId, BlockNumber> for Runtime {
+		fn verify_credential(
+			credential: Hash,
+			signature: Signature,
+		) -> pallet_did::CredentialStatus<AccountId, BlockNumber> {
+			Did::verify_credential(credential, &signature)
+		}
+	}
+
+	impl pallet_contracts::ContractsApi<Block, AccountId, Balance, BlockNumber, Hash> for Runtime {
+		fn call(
+			origin: AccountId,
+			dest: AccountId,
+			value: Balance,
+			gas_limit: Option<Weight>,
+			storage_deposit_limit: Option<Balance>,
+			input_data: Vec<u8>,
+		) -> pallet_contracts_primitives::ContractExecResult<Balance> {
+			let gas_limit = gas_limit.unwrap_or(BlockWeights::get().max_block);
+			Contracts::bare_call(
+				origin,
+				dest,
+				value,
+				gas_limit,
+				storage_deposit_limit,
+				input_data,
+				CONTRACTS_DEBUG_OUTPUT,
+				pallet_contracts::Determinism::Enforced,
+			)
+		}
+
+		fn instantiate(
+			origin: AccountId,
+			value: Balance,
+			gas_limit: Option<Weight>,
+			storage_deposit_limit: Option<Balance>,
+			code: pallet_contracts_primitives::Code<Hash>,
+			data: Vec<u8>,
+			salt: Vec<u8>,
+		) -> pallet_contracts_primitives::ContractInstantiateResult<AccountId, Balance> {
+			let gas_limit = gas_limit.unwrap_or(BlockWeights::get().max_block);
+			Contracts::bare_instantiate(
+				origin,
+				value,
+				gas_limit,
+				storage_deposit_limit,
+				code,
+				data,
+				salt,
+				CONTRACTS_DEBUG_OUTPUT,
+			)
+		}
+
+		fn upload_code(
+			origin: AccountId,
+			code: Vec<u8>,
+			storage_deposit_limit: Option<Balance>,
+			determinism: pallet_contracts::Determinism,
+		) -> pallet_contracts_primitives::CodeUploadResult<Hash, Balance> {
+			Contracts::bare_upload_code(origin, code, storage_deposit_limit, determinism)
+		}
+
+		fn get_storage(
+			address: AccountId,
+			key: Vec<u8>,
+		) -> pallet_contracts_primitives::GetStorageResult {
+			Contracts::get_storage(address, key)
+		}
+	}
+
 	#[cfg(feature = "runtime-benchmarks")]
 	impl frame_benchmarking::Benchmark<Block> for Runtime {
 		fn benchmark_metadata(extra: bool) -> (
@@ -650,4 +1258,178 @@ mod tests {
 			whitelist.contains("26aa394eea5630e07c48ae0c9558cef780d41e5e16056765bc8461851072c9d7")
 		);
 	}
+
+	#[test]
+	fn proxy_types_only_allow_their_pallet() {
+		let create_kitty = RuntimeCall::Kitties(pallet_kitties::Call::create {});
+		let transfer_kitty = RuntimeCall::Kitties(pallet_kitties::Call::transfer {
+			to: AccountId::new([1u8; 32]),
+			kitty_id: 0,
+		});
+		let revoke_claim =
+			RuntimeCall::PoeModule(pallet_poe::Call::revoke_claim { claim: Default::default() });
+		let remark = RuntimeCall::System(SystemCall::remark { remark: vec![] });
+
+		assert!(ProxyType::KittiesOnly.filter(&create_kitty));
+		assert!(ProxyType::KittiesOnly.filter(&transfer_kitty));
+		assert!(!ProxyType::KittiesOnly.filter(&revoke_claim));
+		assert!(!ProxyType::KittiesOnly.filter(&remark));
+
+		assert!(ProxyType::PoeOnly.filter(&revoke_claim));
+		assert!(!ProxyType::PoeOnly.filter(&create_kitty));
+
+		assert!(ProxyType::NonTransfer.filter(&create_kitty));
+		assert!(!ProxyType::NonTransfer.filter(&transfer_kitty));
+
+		assert!(ProxyType::Any.is_superset(&ProxyType::KittiesOnly));
+		assert!(!ProxyType::KittiesOnly.is_superset(&ProxyType::PoeOnly));
+	}
+
+	#[test]
+	fn non_transfer_proxies_can_not_move_value() {
+		let who = AccountId::new([1u8; 32]);
+		let to = || Address::Id(AccountId::new([2u8; 32]));
+		let schedule = pallet_vesting::VestingInfo::new(UNIT, UNIT, 1);
+		let moving_value = [
+			RuntimeCall::Balances(pallet_balances::Call::transfer { dest: to(), value: 1 }),
+			RuntimeCall::Assets(pallet_assets::Call::transfer {
+				id: 0.into(),
+				target: to(),
+				amount: 1,
+			}),
+			RuntimeCall::Assets(pallet_assets::Call::approve_transfer {
+				id: 0.into(),
+				delegate: to(),
+				amount: 1,
+			}),
+			RuntimeCall::Kitties(pallet_kitties::Call::transfer { to: who.clone(), kitty_id: 0 }),
+			RuntimeCall::Kitties(pallet_kitties::Call::set_price {
+				kitty_id: 0,
+				price: Some((0, 1)),
+			}),
+			RuntimeCall::Kitties(pallet_kitties::Call::buy { kitty_id: 0, asset: 0, max_price: 1 }),
+			RuntimeCall::PoeModule(pallet_poe::Call::transfer_claim {
+				claim: Default::default(),
+				dest: who.clone(),
+			}),
+			RuntimeCall::Vesting(pallet_vesting::Call::vested_transfer { target: to(), schedule }),
+			RuntimeCall::Vesting(pallet_vesting::Call::force_vested_transfer {
+				source: to(),
+				target: to(),
+				schedule,
+			}),
+			RuntimeCall::Contracts(pallet_contracts::Call::call {
+				dest: to(),
+				value: 1,
+				gas_limit: Weight::zero(),
+				storage_deposit_limit: None,
+				data: vec![],
+			}),
+			RuntimeCall::Contracts(pallet_contracts::Call::instantiate {
+				value: 1,
+				gas_limit: Weight::zero(),
+				storage_deposit_limit: None,
+				code_hash: Default::default(),
+				data: vec![],
+				salt: vec![],
+			}),
+			RuntimeCall::Contracts(pallet_contracts::Call::instantiate_with_code {
+				value: 1,
+				gas_limit: Weight::zero(),
+				storage_deposit_limit: None,
+				code: vec![],
+				data: vec![],
+				salt: vec![],
+			}),
+			RuntimeCall::Sponsorship(pallet_sponsor::Call::set_budget { budget: UNIT }),
+			RuntimeCall::Sponsorship(pallet_sponsor::Call::add_sponsored { who: who.clone() }),
+			RuntimeCall::Treasury(pallet_treasury::Call::propose_spend {
+				value: UNIT,
+				beneficiary: to(),
+			}),
+		];
+		for call in moving_value {
+			assert!(!ProxyType::NonTransfer.filter(&call), "{:?} is allowed", call);
+		}
+
+		let not_moving_value = [
+			RuntimeCall::System(SystemCall::remark { remark: vec![] }),
+			RuntimeCall::Kitties(pallet_kitties::Call::breed { kitty_id_1: 0, kitty_id_2: 1 }),
+			RuntimeCall::PoeModule(pallet_poe::Call::revoke_claim { claim: Default::default() }),
+			RuntimeCall::Vesting(pallet_vesting::Call::vest {}),
+			RuntimeCall::Did(pallet_did::Call::create_did {}),
+		];
+		for call in not_moving_value {
+			assert!(ProxyType::NonTransfer.filter(&call), "{:?} is not allowed", call);
+		}
+	}
+
+	mod fee_multiplier {
+		use super::*;
+		use frame_support::dispatch::DispatchClass;
+		use sp_runtime::traits::{Convert, One};
+
+		fn max_normal() -> Weight {
+			BlockWeights::get()
+				.get(DispatchClass::Normal)
+				.max_total
+				.unwrap_or_else(|| BlockWeights::get().max_block)
+		}
+
+		fn target() -> Weight {
+			TargetBlockFullness::get() * max_normal()
+		}
+
+		/// Runs `blocks` blocks that each consume `weight` and returns the multiplier after each.
+		fn simulate(start: Multiplier, weight: Weight, blocks: usize) -> Vec<Multiplier> {
+			let mut ext: sp_io::TestExternalities = frame_system::GenesisConfig::default()
+				.build_storage::<Runtime>()
+				.unwrap()
+				.into();
+			ext.execute_with(|| {
+				System::set_block_consumed_resources(weight, 0);
+				let mut multiplier = start;
+				(0..blocks)
+					.map(|_| {
+						multiplier = SlowAdjustingFeeUpdate::<Runtime>::convert(multiplier);
+						multiplier
+					})
+					.collect()
+			})
+		}
+
+		#[test]
+		fn full_blocks_raise_the_multiplier() {
+			let multipliers = simulate(Multiplier::one(), max_normal(), DAYS as usize);
+			assert!(multipliers.windows(2).all(|w| w[1] > w[0]));
+			// A day of congestion roughly doubles fees.
+			let last = *multipliers.last().unwrap();
+			assert!(last > Multiplier::saturating_from_rational(18, 10), "{:?}", last);
+			assert!(last < Multiplier::saturating_from_rational(22, 10), "{:?}", last);
+		}
+
+		#[test]
+		fn empty_blocks_lower_the_multiplier_down_to_the_minimum() {
+			// Dropping to a tenth of the fees takes about ten days of empty blocks.
+			let multipliers = simulate(Multiplier::one(), Weight::zero(), 20 * DAYS as usize);
+			assert!(multipliers.windows(2).all(|w| w[1] <= w[0]));
+			assert_eq!(*multipliers.last().unwrap(), MinimumMultiplier::get());
+		}
+
+		#[test]
+		fn blocks_at_target_keep_the_multiplier() {
+			let start = Multiplier::saturating_from_rational(3, 2);
+			let last = *simulate(start, target(), DAYS as usize).last().unwrap();
+			assert!(last > Multiplier::saturating_from_rational(1499, 1000), "{:?}", last);
+			assert!(last < Multiplier::saturating_from_rational(1501, 1000), "{:?}", last);
+		}
+
+		#[test]
+		fn base_extrinsic_costs_one_milliunit() {
+			let fee = <WeightToFee as frame_support::weights::WeightToFee>::weight_to_fee(
+				&ExtrinsicBaseWeight::get(),
+			);
+			assert!(fee.max(MILLIUNIT) - fee.min(MILLIUNIT) < MICROUNIT, "{}", fee);
+		}
+	}
 }
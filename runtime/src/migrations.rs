@@ -0,0 +1,241 @@
+//! Storage migrations applied by [`crate::Executive`] on runtime upgrade.
+
+use crate::*;
+use frame_support::{
+	storage_alias,
+	traits::{Get, OnRuntimeUpgrade, PalletInfoAccess, StorageVersion},
+	BoundedVec, Twox64Concat,
+};
+use pallet_identity::{Data, IdentityInfo, Registration};
+use sp_std::marker::PhantomData;
+
+/// Runs `Inner` if pallet `P` is at storage version `FROM` and then moves it to version `TO`;
+/// does nothing at any other version.
+///
+/// With `()` as `Inner` this only records a version, for pallets whose layout did not change.
+pub struct VersionedMigration<const FROM: u16, const TO: u16, Inner, P>(PhantomData<(Inner, P)>);
+
+impl<const FROM: u16, const TO: u16, Inner, P> OnRuntimeUpgrade
+	for VersionedMigration<FROM, TO, Inner, P>
+where
+	Inner: OnRuntimeUpgrade,
+	P: PalletInfoAccess,
+{
+	fn on_runtime_upgrade() -> Weight {
+		let db_weight = <Runtime as frame_system::Config>::DbWeight::get();
+		if StorageVersion::get::<P>() != FROM {
+			return db_weight.reads(1)
+		}
+
+		let weight = Inner::on_runtime_upgrade();
+		StorageVersion::new(TO).put::<P>();
+		weight.saturating_add(db_weight.reads_writes(1, 1))
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn pre_upgrade() -> Result<Vec<u8>, &'static str> {
+		let state =
+			if StorageVersion::get::<P>() == FROM { Some(Inner::pre_upgrade()?) } else { None };
+		Ok(state.encode())
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn post_upgrade(state: Vec<u8>) -> Result<(), &'static str> {
+		let state = Option::<Vec<u8>>::decode(&mut &state[..])
+			.map_err(|_| "failed to decode the migration state")?;
+		if let Some(state) = state {
+			Inner::post_upgrade(state)?;
+		}
+		frame_support::ensure!(StorageVersion::get::<P>() != FROM, "storage version not updated");
+		Ok(())
+	}
+}
+
+/// The storage prefix of the removed `pallet_nicks`.
+fn nicks_prefix() -> [u8; 16] {
+	sp_io::hashing::twox_128(b"Nicks")
+}
+
+/// The maximum nick length `pallet_nicks` was configured with.
+type MaxNickLength = ConstU32<32>;
+
+type IdentityRegistration = Registration<
+	Balance,
+	<Runtime as pallet_identity::Config>::MaxRegistrars,
+	<Runtime as pallet_identity::Config>::MaxAdditionalFields,
+>;
+
+/// `pallet_nicks` storage: the nick and the deposit reserved for it.
+#[storage_alias]
+type NameOf = StorageMap<Nicks, Twox64Concat, AccountId, (BoundedVec<u8, MaxNickLength>, Balance)>;
+
+/// `pallet_identity` storage, which the pallet does not expose for writing.
+#[storage_alias]
+type IdentityOf = StorageMap<Identity, Twox64Concat, AccountId, IdentityRegistration>;
+
+/// Moves every nick of the removed `pallet_nicks` into an identity with that nick as the display
+/// name.
+///
+/// The deposit stays reserved on the account and is recorded as the identity deposit, so it is
+/// returned or topped up by `pallet_identity` like any other identity deposit. Everything else
+/// under the `Nicks` prefix, such as its storage version, is removed too, so the migration does
+/// nothing once it ran.
+pub struct NicksToIdentity;
+
+impl OnRuntimeUpgrade for NicksToIdentity {
+	fn on_runtime_upgrade() -> Weight {
+		let mut migrated = 0u64;
+		for (who, (name, deposit)) in NameOf::drain() {
+			let info = IdentityInfo {
+				additional: Default::default(),
+				display: Data::Raw(name),
+				legal: Data::None,
+				web: Data::None,
+				riot: Data::None,
+				email: Data::None,
+				pgp_fingerprint: None,
+				image: Data::None,
+				twitter: Data::None,
+			};
+			IdentityOf::insert(who, Registration { judgements: Default::default(), deposit, info });
+			migrated += 1;
+		}
+		let removed = frame_support::storage::unhashed::clear_prefix(&nicks_prefix(), None, None);
+
+		<Runtime as frame_system::Config>::DbWeight::get().reads_writes(
+			migrated + u64::from(removed.loops),
+			migrated * 2 + u64::from(removed.unique),
+		)
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn pre_upgrade() -> Result<Vec<u8>, &'static str> {
+		Ok((NameOf::iter().count() as u32).encode())
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn post_upgrade(state: Vec<u8>) -> Result<(), &'static str> {
+		let nicks = u32::decode(&mut &state[..]).map_err(|_| "failed to decode nick count")?;
+		let prefix = nicks_prefix();
+		frame_support::ensure!(
+			sp_io::storage::next_key(&prefix).map_or(true, |key| !key.starts_with(&prefix)),
+			"nicks storage left behind"
+		);
+		frame_support::ensure!(
+			IdentityOf::iter().count() as u32 >= nicks,
+			"not every nick became an identity"
+		);
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use frame_support::{
+		assert_ok,
+		traits::{Currency, GetStorageVersion, ReservableCurrency},
+	};
+	#[cfg(feature = "try-runtime")]
+	use frame_try_runtime::UpgradeCheckSelect;
+
+	fn new_test_ext() -> sp_io::TestExternalities {
+		frame_system::GenesisConfig::default()
+			.build_storage::<Runtime>()
+			.unwrap()
+			.into()
+	}
+
+	#[test]
+	fn nicks_become_identity_display_names() {
+		new_test_ext().execute_with(|| {
+			let who = AccountId::new([1u8; 32]);
+			let name: BoundedVec<u8, MaxNickLength> = b"kitty-lover".to_vec().try_into().unwrap();
+			Balances::make_free_balance_be(&who, UNIT);
+			assert_ok!(Balances::reserve(&who, 100));
+			NameOf::insert(&who, (name.clone(), 100));
+			let version_key = [nicks_prefix(), sp_io::hashing::twox_128(b":__STORAGE_VERSION__:")];
+			sp_io::storage::set(&version_key.concat(), &StorageVersion::new(1).encode());
+
+			NicksToIdentity::on_runtime_upgrade();
+
+			assert!(NameOf::iter().next().is_none());
+			let next = sp_io::storage::next_key(&nicks_prefix());
+			assert!(next.map_or(true, |key| !key.starts_with(&nicks_prefix())));
+			let registration = Identity::identity(&who).unwrap();
+			assert_eq!(registration.info.display, Data::Raw(name));
+			assert_eq!(registration.deposit, 100);
+			assert_eq!(Balances::reserved_balance(&who), 100);
+		});
+	}
+
+	/// Resets the custom pallets to the unversioned layout they had before `Migrations` ran.
+	fn unversioned_ext() -> sp_io::TestExternalities {
+		let mut ext = new_test_ext();
+		ext.execute_with(|| {
+			let claim = b"claim".to_vec().try_into().unwrap();
+			let who = AccountId::new([1u8; 32]);
+			assert_ok!(Kitties::create(RuntimeOrigin::signed(who.clone())));
+			assert_ok!(PoeModule::create_claim(RuntimeOrigin::signed(who), claim, None));
+
+			StorageVersion::new(0).put::<TemplateModule>();
+			StorageVersion::new(0).put::<PoeModule>();
+			StorageVersion::new(0).put::<Kitties>();
+		});
+		ext
+	}
+
+	#[test]
+	fn migrations_run_once() {
+		unversioned_ext().execute_with(|| {
+			let who = AccountId::new([1u8; 32]);
+			let name: BoundedVec<u8, MaxNickLength> = b"kitty-lover".to_vec().try_into().unwrap();
+			NameOf::insert(&who, (name, 0));
+
+			Migrations::on_runtime_upgrade();
+
+			assert_eq!(TemplateModule::on_chain_storage_version(), 1);
+			assert_eq!(PoeModule::on_chain_storage_version(), 1);
+			assert_eq!(Kitties::on_chain_storage_version(), 1);
+			assert!(NameOf::iter().next().is_none());
+			let identity = Identity::identity(&who);
+			assert!(identity.is_some());
+
+			// Running them again does nothing.
+			Migrations::on_runtime_upgrade();
+			assert_eq!(Identity::identity(&who), identity);
+		});
+	}
+
+	#[cfg(feature = "try-runtime")]
+	#[test]
+	fn migrations_pass_try_runtime_checks() {
+		unversioned_ext().execute_with(|| {
+			assert_ok!(Executive::try_runtime_upgrade(UpgradeCheckSelect::All));
+		});
+	}
+
+	/// Runs `Migrations` with all try-runtime checks, including the pallets' `try_state`, against a
+	/// state snapshot, e.g. one taken with `node-template try-runtime create-snapshot`:
+	///
+	/// ```sh
+	/// SNAP=chain.snap cargo test -p node-template-runtime --features try-runtime \
+	/// 	-- --ignored migrations_pass_try_runtime_checks_on_snapshot
+	/// ```
+	#[cfg(feature = "try-runtime")]
+	#[tokio::test]
+	#[ignore = "needs a state snapshot in SNAP"]
+	async fn migrations_pass_try_runtime_checks_on_snapshot() {
+		use frame_remote_externalities::{Builder, Mode, OfflineConfig, SnapshotConfig};
+
+		let snapshot = std::env::var("SNAP").expect("SNAP points to a state snapshot");
+		let mut ext = Builder::<Block>::new()
+			.mode(Mode::Offline(OfflineConfig { state_snapshot: SnapshotConfig::new(snapshot) }))
+			.build()
+			.await
+			.unwrap();
+		ext.execute_with(|| {
+			assert_ok!(Executive::try_runtime_upgrade(UpgradeCheckSelect::All));
+		});
+	}
+}
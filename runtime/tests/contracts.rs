@@ -0,0 +1,100 @@
+use codec::{Decode, Encode};
+use frame_support::{assert_ok, traits::Currency, weights::Weight, BoundedVec};
+use node_template_runtime::{
+	chain_extension::{KITTY_OWNER, KITTY_TRANSFER, KITTY_TRANSFER_FAILED, POE_CLAIM},
+	AccountId, Balances, BuildStorage, Contracts, GenesisConfig, Kitties, PoeModule, RuntimeOrigin,
+	System, UNIT,
+};
+use pallet_contracts_primitives::Code;
+
+/// Prebuilt from `fixtures/kitties_poe_extension.wat`.
+const FIXTURE: &[u8] = include_bytes!("fixtures/kitties_poe_extension.wasm");
+
+const ALICE: AccountId = AccountId::new([1u8; 32]);
+const BOB: AccountId = AccountId::new([2u8; 32]);
+const GAS_LIMIT: Weight = Weight::from_parts(100_000_000_000, 3 * 1024 * 1024);
+
+fn new_test_ext() -> sp_io::TestExternalities {
+	let mut ext: sp_io::TestExternalities =
+		GenesisConfig::default().build_storage().unwrap().into();
+	ext.execute_with(|| {
+		System::set_block_number(1);
+		Balances::make_free_balance_be(&ALICE, 1_000_000 * UNIT);
+	});
+	ext
+}
+
+fn instantiate_fixture() -> AccountId {
+	Contracts::bare_instantiate(
+		ALICE,
+		0,
+		GAS_LIMIT,
+		None,
+		Code::Upload(FIXTURE.to_vec()),
+		vec![],
+		vec![],
+		false,
+	)
+	.result
+	.unwrap()
+	.account_id
+}
+
+/// Calls `func_id` through the fixture, returning the extension's status and output.
+fn call_extension(contract: &AccountId, func_id: u16, input: impl Encode) -> (u32, Vec<u8>) {
+	let mut data = (func_id as u32).encode();
+	input.encode_to(&mut data);
+	let result = Contracts::bare_call(
+		ALICE,
+		contract.clone(),
+		0,
+		GAS_LIMIT,
+		None,
+		data,
+		false,
+		pallet_contracts::Determinism::Enforced,
+	);
+	let data = result.result.unwrap().data;
+	let (status, output) = data.split_at(4);
+	(u32::decode(&mut &status[..]).unwrap(), output.to_vec())
+}
+
+#[test]
+fn contract_reads_and_transfers_kitties() {
+	new_test_ext().execute_with(|| {
+		let contract = instantiate_fixture();
+		assert_ok!(Kitties::create(RuntimeOrigin::signed(ALICE)));
+		assert_ok!(Kitties::transfer(RuntimeOrigin::signed(ALICE), contract.clone(), 0));
+
+		assert_eq!(
+			call_extension(&contract, KITTY_OWNER, 0u32),
+			(0, Some(contract.clone()).encode())
+		);
+
+		assert_eq!(call_extension(&contract, KITTY_TRANSFER, (BOB, 0u32)).0, 0);
+		assert_eq!(Kitties::owner(0), Some(BOB));
+	});
+}
+
+#[test]
+fn contract_can_not_transfer_kitties_it_does_not_own() {
+	new_test_ext().execute_with(|| {
+		let contract = instantiate_fixture();
+		assert_ok!(Kitties::create(RuntimeOrigin::signed(ALICE)));
+
+		assert_eq!(call_extension(&contract, KITTY_TRANSFER, (BOB, 0u32)).0, KITTY_TRANSFER_FAILED);
+		assert_eq!(Kitties::owner(0), Some(ALICE));
+	});
+}
+
+#[test]
+fn contract_looks_up_poe_claims() {
+	new_test_ext().execute_with(|| {
+		let contract = instantiate_fixture();
+		let claim: BoundedVec<u8, _> = vec![7u8; 16].try_into().unwrap();
+		assert_ok!(PoeModule::create_claim(RuntimeOrigin::signed(ALICE), claim.clone(), None));
+
+		assert_eq!(call_extension(&contract, POE_CLAIM, claim).1, Some((ALICE, 1u32)).encode());
+		assert_eq!(call_extension(&contract, POE_CLAIM, vec![8u8; 16]).1, None::<()>.encode());
+	});
+}
@@ -0,0 +1,466 @@
+//! Runs blocks of signed extrinsics through `Executive` on the real runtime, to catch mistakes
+//! in the wiring of `runtime/src/lib.rs` that the pallets' mock runtimes can't see.
+
+use codec::Encode;
+use frame_support::{assert_ok, dispatch::GetDispatchInfo, traits::Get, BoundedVec};
+use node_template_runtime::{
+	AccountId, AssetId, Assets, AssetsConfig, AuraConfig, Balance, Balances, BalancesCall,
+	BalancesConfig, BuildStorage, ChargeTransaction, Executive, FreeQuota, GenesisConfig, Header,
+	Identity, Kitties, KittiesCall, PoeCall, Runtime, RuntimeCall, RuntimeEvent, SignedExtra,
+	SignedPayload, Sponsorship, System, TimestampCall, TransactionPayment, Treasury,
+	UncheckedExtrinsic, EXISTENTIAL_DEPOSIT, SLOT_DURATION, UNIT,
+};
+use pallet_identity::{Data, IdentityInfo};
+use sp_consensus_aura::{Slot, AURA_ENGINE_ID};
+use sp_keyring::Sr25519Keyring::{self, Alice, Bob, Charlie, Dave, Ferdie};
+use sp_runtime::{
+	generic::Era,
+	traits::{Header as _, SignedExtension},
+	transaction_validity::{
+		InvalidTransaction, TransactionSource, TransactionValidity, TransactionValidityError,
+	},
+	Digest, DigestItem,
+};
+
+const ENDOWMENT: Balance = 1_000 * UNIT;
+/// A sufficient asset Charlie holds instead of the native token.
+const ASSET: AssetId = 1;
+
+fn new_test_ext() -> sp_io::TestExternalities {
+	GenesisConfig {
+		balances: BalancesConfig {
+			balances: vec![(Alice.to_account_id(), ENDOWMENT), (Bob.to_account_id(), ENDOWMENT)],
+		},
+		// Ferdie authors every block, so fees paid by Alice and Bob are easy to follow.
+		aura: AuraConfig { authorities: vec![Ferdie.public().into()] },
+		// With the existential deposit as `min_balance`, fees cost as much of the asset as of the
+		// native token.
+		assets: AssetsConfig {
+			assets: vec![(ASSET, Alice.to_account_id(), true, EXISTENTIAL_DEPOSIT)],
+			metadata: vec![],
+			accounts: vec![(ASSET, Charlie.to_account_id(), ENDOWMENT)],
+		},
+		..Default::default()
+	}
+	.build_storage()
+	.unwrap()
+	.into()
+}
+
+/// Signs `call` the way wallets do, with an immortal era.
+fn sign(signer: Sr25519Keyring, call: RuntimeCall, nonce: u32) -> UncheckedExtrinsic {
+	let payment = ChargeTransaction::paid(
+		nonce,
+		pallet_sponsor::ChargeSponsor::Signer(pallet_asset_tx_payment::ChargeAssetTxPayment::from(
+			0, None,
+		)),
+	);
+	sign_with(signer, call, payment)
+}
+
+/// Signs `call` like [`sign`], paying with `payment`, which also holds the nonce.
+fn sign_with(
+	signer: Sr25519Keyring,
+	call: RuntimeCall,
+	payment: ChargeTransaction,
+) -> UncheckedExtrinsic {
+	let extra: SignedExtra = (
+		frame_system::CheckNonZeroSender::<Runtime>::new(),
+		frame_system::CheckSpecVersion::<Runtime>::new(),
+		frame_system::CheckTxVersion::<Runtime>::new(),
+		frame_system::CheckGenesis::<Runtime>::new(),
+		frame_system::CheckEra::<Runtime>::from(Era::Immortal),
+		frame_system::CheckWeight::<Runtime>::new(),
+		pallet_tx_pause::CheckNotPaused::<Runtime>::new(),
+		payment,
+	);
+	let payload =
+		SignedPayload::from_raw(call.clone(), extra.clone(), extra.additional_signed().unwrap());
+	let signature = payload.using_encoded(|payload| signer.sign(payload));
+	UncheckedExtrinsic::new_signed(call, signer.to_account_id().into(), signature.into(), extra)
+}
+
+/// The fee `xt` is charged before any weight is refunded.
+fn fee(xt: &UncheckedExtrinsic) -> Balance {
+	TransactionPayment::compute_fee(xt.encoded_size() as u32, &xt.get_dispatch_info(), 0)
+}
+
+/// The fees `who` paid in the current block.
+fn fees_paid(who: &AccountId) -> Balance {
+	System::events()
+		.into_iter()
+		.filter_map(|record| match record.event {
+			RuntimeEvent::AssetTxPayment(pallet_asset_tx_payment::Event::AssetTxFeePaid {
+				who: payer,
+				actual_fee,
+				..
+			}) if payer == *who => Some(actual_fee),
+			_ => None,
+		})
+		.sum()
+}
+
+/// Builds blocks the way the node does: Aura slot digest, timestamp inherent, then extrinsics.
+struct Chain {
+	parent_hash: <Runtime as frame_system::Config>::Hash,
+	number: u32,
+}
+
+impl Chain {
+	fn new() -> Self {
+		Self { parent_hash: System::block_hash(0), number: 0 }
+	}
+
+	/// Executes a block containing `extrinsics`, all of which must be applied successfully.
+	/// Events of the block can be inspected afterwards.
+	fn execute_block(&mut self, extrinsics: Vec<UncheckedExtrinsic>) {
+		self.number += 1;
+		let slot = Slot::from(u64::from(self.number));
+		let digest = Digest { logs: vec![DigestItem::PreRuntime(AURA_ENGINE_ID, slot.encode())] };
+		Executive::initialize_block(&Header::new(
+			self.number,
+			Default::default(),
+			Default::default(),
+			self.parent_hash,
+			digest,
+		));
+
+		let now = *slot * SLOT_DURATION;
+		let timestamp = UncheckedExtrinsic::new_unsigned(TimestampCall::set { now }.into());
+		assert_ok!(Executive::apply_extrinsic(timestamp).unwrap());
+		for xt in extrinsics {
+			assert_ok!(Executive::apply_extrinsic(xt).unwrap());
+		}
+		self.parent_hash = Executive::finalize_block().hash();
+	}
+
+	/// Validates `xt` the way the transaction pool does, on top of the last block and without
+	/// keeping any changes.
+	fn validate(&self, xt: UncheckedExtrinsic) -> TransactionValidity {
+		sp_io::storage::start_transaction();
+		let validity =
+			Executive::validate_transaction(TransactionSource::External, xt, self.parent_hash);
+		sp_io::storage::rollback_transaction();
+		validity
+	}
+}
+
+#[test]
+fn transfers_charge_the_fee() {
+	new_test_ext().execute_with(|| {
+		let xt = sign(
+			Alice,
+			BalancesCall::transfer_keep_alive { dest: Bob.to_account_id().into(), value: UNIT }
+				.into(),
+			0,
+		);
+		let fee = fee(&xt);
+		assert!(fee > 0);
+
+		Chain::new().execute_block(vec![xt]);
+		assert_eq!(fees_paid(&Alice.to_account_id()), fee);
+		assert_eq!(Balances::free_balance(Alice.to_account_id()), ENDOWMENT - UNIT - fee);
+		assert_eq!(Balances::free_balance(Bob.to_account_id()), ENDOWMENT + UNIT);
+		// 80% of the fee goes to the treasury and 20% to the block author.
+		let author_share = Balances::free_balance(Ferdie.to_account_id());
+		assert_eq!(author_share, fee - fee * 80 / 100);
+		assert_eq!(Balances::free_balance(Treasury::account_id()), fee - author_share);
+		System::assert_has_event(
+			pallet_balances::Event::Transfer {
+				from: Alice.to_account_id(),
+				to: Bob.to_account_id(),
+				amount: UNIT,
+			}
+			.into(),
+		);
+		System::assert_has_event(
+			pallet_asset_tx_payment::Event::AssetTxFeePaid {
+				who: Alice.to_account_id(),
+				actual_fee: fee,
+				tip: 0,
+				asset_id: None,
+			}
+			.into(),
+		);
+		assert_eq!(System::account_nonce(Alice.to_account_id()), 1);
+	});
+}
+
+#[test]
+fn kitties_get_distinct_random_dna() {
+	new_test_ext().execute_with(|| {
+		let mut chain = Chain::new();
+		chain.execute_block(vec![
+			sign(Alice, KittiesCall::create {}.into(), 0),
+			sign(Alice, KittiesCall::create {}.into(), 1),
+		]);
+		chain.execute_block(vec![
+			sign(Alice, KittiesCall::create {}.into(), 2),
+			sign(Alice, KittiesCall::transfer { to: Bob.to_account_id(), kitty_id: 0 }.into(), 3),
+		]);
+
+		let dna: Vec<_> = (0..3).map(|id| Kitties::kitties(id).unwrap()).collect();
+		assert!(dna[0] != dna[1] && dna[1] != dna[2] && dna[0] != dna[2]);
+		assert_eq!(Kitties::owner(0), Some(Bob.to_account_id()));
+		assert_eq!(Kitties::owner(2), Some(Alice.to_account_id()));
+		System::assert_has_event(
+			pallet_kitties::Event::KittyTransferred {
+				from: Alice.to_account_id(),
+				to: Bob.to_account_id(),
+				kitty_id: 0,
+			}
+			.into(),
+		);
+	});
+}
+
+#[test]
+fn claims_up_to_max_claim_length_can_be_created() {
+	new_test_ext().execute_with(|| {
+		let max_length = <Runtime as pallet_poe::Config>::MaxClaimLength::get();
+		assert_eq!(max_length, 32);
+		let claim: BoundedVec<u8, <Runtime as pallet_poe::Config>::MaxClaimLength> =
+			vec![7u8; max_length as usize].try_into().unwrap();
+
+		Chain::new().execute_block(vec![sign(
+			Alice,
+			PoeCall::create_claim { claim: claim.clone(), metadata: None }.into(),
+			0,
+		)]);
+		assert_eq!(pallet_poe::Proofs::<Runtime>::get(&claim), Some((Alice.to_account_id(), 1)));
+		System::assert_has_event(
+			pallet_poe::Event::ClaimCreated(Alice.to_account_id(), claim).into(),
+		);
+	});
+}
+
+#[test]
+fn identities_replace_nicks_and_reserve_the_deposit() {
+	new_test_ext().execute_with(|| {
+		let info = IdentityInfo {
+			additional: Default::default(),
+			display: Data::Raw(b"alice".to_vec().try_into().unwrap()),
+			legal: Data::None,
+			web: Data::None,
+			riot: Data::None,
+			email: Data::None,
+			pgp_fingerprint: None,
+			image: Data::None,
+			twitter: Data::None,
+		};
+		let xt = sign(
+			Alice,
+			pallet_identity::Call::set_identity { info: Box::new(info.clone()) }.into(),
+			0,
+		);
+		let estimated_fee = fee(&xt);
+
+		Chain::new().execute_block(vec![xt]);
+		// `set_identity` refunds the weight of the registrars and fields it didn't need.
+		let fee = fees_paid(&Alice.to_account_id());
+		assert!(fee > 0 && fee < estimated_fee);
+		let deposit = <Runtime as pallet_identity::Config>::BasicDeposit::get();
+		assert_eq!(Identity::identity(Alice.to_account_id()).unwrap().info, info);
+		assert_eq!(Balances::reserved_balance(Alice.to_account_id()), deposit);
+		assert_eq!(Balances::free_balance(Alice.to_account_id()), ENDOWMENT - deposit - fee);
+		assert!(System::events().iter().any(|record| matches!(
+			record.event,
+			RuntimeEvent::Identity(pallet_identity::Event::IdentitySet { .. })
+		)));
+	});
+}
+
+#[test]
+fn invalid_signatures_are_rejected() {
+	new_test_ext().execute_with(|| {
+		// Bob can't spend Alice's funds by signing in her name.
+		let call: RuntimeCall =
+			BalancesCall::transfer_keep_alive { dest: Bob.to_account_id().into(), value: UNIT }
+				.into();
+		let mut xt = sign(Bob, call, 0);
+		let alice: AccountId = Alice.to_account_id();
+		xt.signature.as_mut().unwrap().0 = alice.into();
+		assert!(Executive::apply_extrinsic(xt).is_err());
+		assert_eq!(Balances::free_balance(Alice.to_account_id()), ENDOWMENT);
+	});
+}
+
+#[test]
+fn unfunded_accounts_create_kitties_for_free_within_their_quota() {
+	new_test_ext().execute_with(|| {
+		let quota = <Runtime as pallet_free_quota::Config>::FreeCallsPerEra::get();
+		let free_kitty = |nonce| {
+			sign_with(Charlie, KittiesCall::create {}.into(), ChargeTransaction::free(nonce))
+		};
+
+		let mut chain = Chain::new();
+		chain.execute_block((0..quota).map(free_kitty).collect());
+		assert_eq!(Kitties::owner(quota - 1), Some(Charlie.to_account_id()));
+		assert_eq!(Balances::free_balance(Charlie.to_account_id()), 0);
+		assert_eq!(FreeQuota::remaining_free_calls(&Charlie.to_account_id()), 0);
+
+		// Over the quota, and for anything but the free-tier calls, Charlie has to pay.
+		let over_quota = free_kitty(quota);
+		let transfer = sign_with(
+			Charlie,
+			BalancesCall::transfer_keep_alive { dest: Bob.to_account_id().into(), value: 0 }.into(),
+			ChargeTransaction::free(quota),
+		);
+		assert_eq!(
+			Executive::apply_extrinsic(over_quota),
+			Err(TransactionValidityError::Invalid(InvalidTransaction::Custom(
+				pallet_free_quota::QUOTA_EXHAUSTED
+			)))
+		);
+		assert_eq!(
+			Executive::apply_extrinsic(transfer),
+			Err(TransactionValidityError::Invalid(InvalidTransaction::Call))
+		);
+	});
+}
+
+#[test]
+fn the_pool_takes_no_more_free_transactions_than_the_quota() {
+	new_test_ext().execute_with(|| {
+		let quota = <Runtime as pallet_free_quota::Config>::FreeCallsPerEra::get();
+		let free_kitty = |nonce| {
+			sign_with(Charlie, KittiesCall::create {}.into(), ChargeTransaction::free(nonce))
+		};
+		let exhausted = Err(TransactionValidityError::Invalid(InvalidTransaction::Custom(
+			pallet_free_quota::QUOTA_EXHAUSTED,
+		)));
+
+		// All but the first wait in the pool for the ones before them, and each of them may need
+		// the quota.
+		let mut chain = Chain::new();
+		for nonce in 0..quota {
+			assert_ok!(chain.validate(free_kitty(nonce)));
+		}
+		assert_eq!(chain.validate(free_kitty(quota)), exhausted);
+
+		chain.execute_block(vec![free_kitty(0)]);
+		assert_ok!(chain.validate(free_kitty(quota - 1)));
+		assert_eq!(chain.validate(free_kitty(quota)), exhausted);
+	});
+}
+
+#[test]
+fn keys_without_an_account_have_no_free_quota() {
+	new_test_ext().execute_with(|| {
+		let free_kitty = sign_with(Dave, KittiesCall::create {}.into(), ChargeTransaction::free(0));
+		assert_eq!(
+			Chain::new().validate(free_kitty),
+			Err(TransactionValidityError::Invalid(InvalidTransaction::Custom(
+				pallet_free_quota::NO_ACCOUNT
+			)))
+		);
+	});
+}
+
+#[test]
+fn sponsors_pay_for_the_claims_of_the_accounts_they_sponsor() {
+	new_test_ext().execute_with(|| {
+		let budget = 10 * UNIT;
+		let mut chain = Chain::new();
+		chain.execute_block(vec![
+			sign(Alice, pallet_sponsor::Call::set_budget { budget }.into(), 0),
+			sign(
+				Alice,
+				pallet_sponsor::Call::add_sponsored { who: Charlie.to_account_id() }.into(),
+				1,
+			),
+		]);
+		let alice_balance = Balances::free_balance(Alice.to_account_id());
+		assert_eq!(
+			Sponsorship::allowance(&Alice.to_account_id(), &Charlie.to_account_id()),
+			Some(budget)
+		);
+
+		let claim: BoundedVec<u8, _> = vec![1u8; 32].try_into().unwrap();
+		let sponsored = |nonce| {
+			ChargeTransaction::paid(
+				nonce,
+				pallet_sponsor::ChargeSponsor::Sponsor(Alice.to_account_id()),
+			)
+		};
+		chain.execute_block(vec![sign_with(
+			Charlie,
+			PoeCall::create_claim { claim: claim.clone(), metadata: None }.into(),
+			sponsored(0),
+		)]);
+		assert_eq!(pallet_poe::Proofs::<Runtime>::get(&claim), Some((Charlie.to_account_id(), 2)));
+		assert_eq!(Balances::free_balance(Charlie.to_account_id()), 0);
+		let fee = alice_balance - Balances::free_balance(Alice.to_account_id());
+		assert!(fee > 0);
+		assert_eq!(
+			Sponsorship::allowance(&Alice.to_account_id(), &Charlie.to_account_id()),
+			Some(budget - fee)
+		);
+		System::assert_has_event(
+			pallet_sponsor::Event::SponsoredFeePaid {
+				sponsor: Alice.to_account_id(),
+				who: Charlie.to_account_id(),
+				actual_fee: fee,
+			}
+			.into(),
+		);
+
+		// Bob isn't sponsored, and kitties aren't sponsored calls.
+		let bob_claim = sign_with(
+			Bob,
+			PoeCall::create_claim { claim: vec![2u8; 32].try_into().unwrap(), metadata: None }
+				.into(),
+			sponsored(0),
+		);
+		assert_eq!(
+			Executive::apply_extrinsic(bob_claim),
+			Err(TransactionValidityError::Invalid(InvalidTransaction::Custom(
+				pallet_sponsor::NOT_SPONSORED
+			)))
+		);
+		let kitty = sign_with(Charlie, KittiesCall::create {}.into(), sponsored(1));
+		assert_eq!(
+			Executive::apply_extrinsic(kitty),
+			Err(TransactionValidityError::Invalid(InvalidTransaction::Call))
+		);
+	});
+}
+
+#[test]
+fn kitties_are_bought_and_fees_paid_in_sufficient_assets() {
+	new_test_ext().execute_with(|| {
+		let price = 10 * UNIT;
+		let mut chain = Chain::new();
+		chain.execute_block(vec![
+			sign(Alice, KittiesCall::create {}.into(), 0),
+			sign(
+				Alice,
+				KittiesCall::set_price { kitty_id: 0, price: Some((ASSET, price)) }.into(),
+				1,
+			),
+		]);
+
+		let pay_in_asset = ChargeTransaction::paid(
+			0,
+			pallet_sponsor::ChargeSponsor::Signer(
+				pallet_asset_tx_payment::ChargeAssetTxPayment::from(0, Some(ASSET)),
+			),
+		);
+		let buy = sign_with(
+			Charlie,
+			KittiesCall::buy { kitty_id: 0, asset: ASSET, max_price: price }.into(),
+			pay_in_asset,
+		);
+		let fee = fee(&buy);
+		chain.execute_block(vec![buy]);
+
+		assert_eq!(Kitties::owner(0), Some(Charlie.to_account_id()));
+		assert_eq!(Assets::balance(ASSET, Alice.to_account_id()), price);
+		assert_eq!(Assets::balance(ASSET, Charlie.to_account_id()), ENDOWMENT - price - fee);
+		assert_eq!(Balances::free_balance(Charlie.to_account_id()), 0);
+		// Like fees in the native token, 80% go to the treasury and 20% to the block author.
+		let author_share = Assets::balance(ASSET, Ferdie.to_account_id());
+		assert_eq!(author_share, fee / 5);
+		assert_eq!(Assets::balance(ASSET, Treasury::account_id()), fee - author_share);
+	});
+}
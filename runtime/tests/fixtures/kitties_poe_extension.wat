@@ -0,0 +1,47 @@
+;; Forwards its input to the runtime's chain extension and returns the extension's status as a
+;; little endian `u32` followed by its output.
+;;
+;; The input is the chain extension function id as a little endian `u32` followed by the
+;; function's SCALE encoded arguments. `kitties_poe_extension.wasm` is built from this file with
+;; `wat2wasm`.
+(module
+	(import "seal0" "seal_input" (func $seal_input (param i32 i32)))
+	(import "seal0" "seal_call_chain_extension"
+		(func $seal_call_chain_extension (param i32 i32 i32 i32 i32) (result i32))
+	)
+	(import "seal0" "seal_return" (func $seal_return (param i32 i32 i32)))
+	(import "env" "memory" (memory 1 1))
+
+	;; [0, 4) length of the input buffer
+	(data (i32.const 0) "\00\01")
+
+	;; [4, 260) input buffer: function id followed by the function's input
+
+	;; [260, 264) length of the output buffer
+	(data (i32.const 260) "\00\01")
+
+	;; [264, 268) status returned by the chain extension
+
+	;; [268, 524) output buffer
+
+	(func (export "deploy"))
+
+	(func (export "call")
+		(call $seal_input (i32.const 4) (i32.const 0))
+		(i32.store
+			(i32.const 264)
+			(call $seal_call_chain_extension
+				(i32.load (i32.const 4))
+				(i32.const 8)
+				(i32.sub (i32.load (i32.const 0)) (i32.const 4))
+				(i32.const 268)
+				(i32.const 260)
+			)
+		)
+		(call $seal_return
+			(i32.const 0)
+			(i32.const 264)
+			(i32.add (i32.load (i32.const 260)) (i32.const 4))
+		)
+	)
+)